@@ -4,14 +4,93 @@ use std::ops::Range;
 
 use crate::lexer::Token;
 
+/// Wrapper around [`TypeKind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Type {
+    pub kind: TypeKind,
+    pub span: Range<usize>,
+}
+
 /// Represents a type in the source code.
 #[derive(Debug, Clone, PartialEq)]
-pub struct TypePath {
-    /// The identifier of the the type.
+pub enum TypeKind {
+    /// A named type with optional type arguments (e.g. `number`, `Map<string, number>` or `util.Point`).
+    Path {
+        /// The imported module that declares the type (e.g. `util` in `util.Point`).
+        module: Option<String>,
+        /// The identifier of the the type.
+        ident: String,
+        /// The type arguments. Empty if there are no type arguments.
+        args: Vec<Type>,
+    },
+    /// A function type (e.g. `fn(number, string) -> bool` or `fn(string, ...List<number>)`).
+    Fn {
+        params: Vec<Type>,
+        /// `true` if the last param is a rest param (prefixed with `...`).
+        variadic: bool,
+        /// The return type. A missing return type means any type.
+        ret: Option<Box<Type>>,
+    },
+    /// A tuple type (e.g. `(number, string)`).
+    Tuple(Vec<Type>),
+}
+
+/// Represents a field in a struct declaration or an enum variant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField {
+    pub ident: String,
+    /// Optional type annotation.
+    pub ty: Option<Type>,
+    pub span: Range<usize>,
+}
+
+/// Represents a variant in an enum declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    pub ident: String,
+    /// The payload of the variant. Empty if the variant does not have a payload.
+    pub fields: Vec<StructField>,
+    pub span: Range<usize>,
+}
+
+/// Represents a named argument in a function call (e.g. `port: 9000` in `connect("x", port: 9000)`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedArg {
+    /// The identifier of the parameter.
     pub ident: String,
+    pub value: Expr,
     pub span: Range<usize>,
 }
 
+/// Represents an arm in a match expression (e.g. `Shape.Circle(r) => r * r`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Expr,
+}
+
+/// Wrapper around [`PatternKind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Range<usize>,
+}
+
+/// Represents a pattern in a match arm.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternKind {
+    /// Matches any value (`_`).
+    Wildcard,
+    /// Matches an enum variant and binds its payload (e.g. `Shape.Rect(w, h)`).
+    Variant {
+        /// Should always be an [`ExprKind::Identifier`] that refers to the enum.
+        enum_ident: Box<Expr>,
+        variant: String,
+        /// An array of [`StmtKind::Binding`], one for every field in the payload.
+        bindings: Vec<Stmt>,
+    },
+}
+
 /// Wrapper around [`ExprKind`]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Expr {
@@ -28,10 +107,29 @@ pub enum ExprKind {
     BoolLit(bool),
     /// String literal.
     StringLit(String),
+    /// An interpolated string literal (e.g. `"x = ${x}"`).
+    /// Stores the text segments (as [`ExprKind::StringLit`]) and the interpolated expressions in order.
+    Interpolation(Vec<Expr>),
     /// An identifier (e.g. `foo`).
     Identifier(String),
     /// A function call (e.g. `foo(1, bar, baz())`).
-    FnCall { callee: Box<Expr>, args: Vec<Expr> },
+    FnCall {
+        callee: Box<Expr>,
+        /// Positional arguments.
+        args: Vec<Expr>,
+        /// Named arguments. Named arguments always come after the positional arguments.
+        named_args: Vec<NamedArg>,
+    },
+    /// A list literal (e.g. `[1, 2, 3]`).
+    ListLit(Vec<Expr>),
+    /// A tuple literal (e.g. `(1, "a")` or `(1,)` for a tuple with one element).
+    TupleLit(Vec<Expr>),
+    /// A map literal (e.g. `{ "a": 1, "b": 2 }`). Stores key-value pairs.
+    MapLit(Vec<(Expr, Expr)>),
+    /// An index expression (e.g. `foo[1]`).
+    Index { target: Box<Expr>, index: Box<Expr> },
+    /// A field access expression (e.g. `foo.bar`).
+    FieldAccess { target: Box<Expr>, ident: String },
     /// A binary expression (e.g. `1+1`).
     Binary {
         lhs: Box<Expr>,
@@ -46,8 +144,33 @@ pub enum ExprKind {
         inner_stmt: Box<Stmt>,
         /// An array of [`Stmt::FnParam`]. Parameters are declarations so they are a separate ast node.
         params: Vec<Stmt>,
+        /// Optional return type annotation (e.g. `-> number`).
+        ret_ty: Option<Type>,
         body: Vec<Stmt>,
     },
+    /// A block expression (e.g. `{ let x = 1; x + 1 }`).
+    /// Evaluates to the trailing expression or `()` if there is no trailing expression.
+    Block {
+        stmts: Vec<Stmt>,
+        /// The trailing expression (without a semicolon).
+        expr: Option<Box<Expr>>,
+    },
+    /// An if expression (e.g. `if x > 0 { 1 } else { 2 }`).
+    /// Evaluates to `()` if there is no `else` branch.
+    If {
+        condition: Box<Expr>,
+        /// Should always be an [`ExprKind::Block`].
+        then_branch: Box<Expr>,
+        /// Should always be an [`ExprKind::Block`] or an [`ExprKind::If`] (for `else if`).
+        else_branch: Option<Box<Expr>>,
+    },
+    /// A match expression (e.g. `match shape { Shape.Circle(r) => r * r, _ => 0 }`).
+    Match {
+        scrutinee: Box<Expr>,
+        /// Should always be a [`StmtKind::Binding`]. The matched value is stored in this temporary while matching.
+        inner_stmt: Box<Stmt>,
+        arms: Vec<MatchArm>,
+    },
     /// Error token. Used for error recovery.
     Error,
 }
@@ -69,22 +192,68 @@ pub struct Stmt {
 /// Represents a statement node in the AST.
 #[derive(Debug, Clone, PartialEq)]
 pub enum StmtKind {
-    /// Variable declaration.
+    /// Variable declaration (`let`) or constant declaration (`const`).
     LetDeclaration {
         ident: String,
         initializer: Expr,
         /// Optional type annotation.
-        ty: Option<TypePath>,
+        ty: Option<Type>,
+        /// `true` if declared with `const`. Constants can not be reassigned.
+        is_const: bool,
+        /// Doc comment (`///`) preceding the declaration.
+        doc: Option<String>,
+    },
+    /// Destructuring declaration (e.g. `let (q, r) = divmod(7, 2);`). Declares a variable for every element of a tuple.
+    TupleDeclaration {
+        /// An array of [`StmtKind::Binding`]. Every binding is a separate variable.
+        bindings: Vec<Stmt>,
+        initializer: Expr,
+        /// Optional type annotation for the whole tuple.
+        ty: Option<Type>,
+        /// `true` if declared with `const`. Constants can not be reassigned.
+        is_const: bool,
+        /// Doc comment (`///`) preceding the declaration.
+        doc: Option<String>,
     },
     FnParam {
         ident: String,
+        /// Optional type annotation.
+        ty: Option<Type>,
+        /// Optional default value (e.g. `8080` in `port = 8080`). Default values are always literals.
+        /// Parameters with a default value always come after the parameters without one.
+        default: Option<Expr>,
+        /// `true` if this is a rest parameter (e.g. `...xs`). A rest parameter is always the last parameter and
+        /// collects the extra arguments into a list.
+        is_rest: bool,
     },
+    /// A variable that is bound by a pattern or a temporary introduced by the compiler.
+    /// There is no initializer as the value is assigned by the enclosing expression.
+    Binding { ident: String },
     /// Function declaration.
     FnDeclaration {
         ident: String,
+        /// The type parameters of a generic function (e.g. `T` and `U` in `fn map<T, U>(...)`).
+        type_params: Vec<String>,
         /// An array of [`Stmt::FnParam`]. Parameters are declarations so they are a separate ast node.
         params: Vec<Stmt>,
+        /// Optional return type annotation (e.g. `-> number`).
+        ret_ty: Option<Type>,
         body: Vec<Stmt>,
+        /// Doc comment (`///`) preceding the declaration.
+        doc: Option<String>,
+    },
+    /// Struct declaration (e.g. `struct Point { x, y }`).
+    StructDeclaration {
+        ident: String,
+        fields: Vec<StructField>,
+    },
+    /// Class declaration (e.g. `class Counter { fn inc(self) { ... } }`).
+    /// Every method is a [`StmtKind::FnDeclaration`] that takes `self` as its first parameter.
+    ClassDeclaration { ident: String, methods: Vec<Stmt> },
+    /// Enum declaration (e.g. `enum Shape { Circle(r), Rect(w, h) }`).
+    EnumDeclaration {
+        ident: String,
+        variants: Vec<EnumVariant>,
     },
     /// Block statement.
     Block(Vec<Stmt>),
@@ -96,14 +265,45 @@ pub enum StmtKind {
         else_block: Option<Vec<Stmt>>,
     },
     /// While statement.
-    WhileStmt {
-        condition: Expr,
+    WhileStmt { condition: Expr, body: Vec<Stmt> },
+    /// For statement (e.g. `for let i = 0; i < 10; i += 1 { ... }`).
+    /// Every clause is optional. A missing `condition` loops forever.
+    ForStmt {
+        /// Usually a [`StmtKind::LetDeclaration`]. Variables declared here are scoped to the loop.
+        initializer: Option<Box<Stmt>>,
+        condition: Option<Expr>,
+        /// Evaluated after every iteration.
+        step: Option<Expr>,
         body: Vec<Stmt>,
     },
+    /// Import declaration (e.g. `import util;` or `import "lib/util.ella";`).
+    /// The members exported by the module are accessed through `ident` (e.g. `util.foo`).
+    ImportDeclaration {
+        /// The name of the module. This is the file name without the extension.
+        ident: String,
+        /// The path of the module relative to the importing file.
+        path: String,
+    },
+    /// Exported declaration (e.g. `export fn foo() {}`). The declaration can be accessed by modules that import this module.
+    Export(Box<Stmt>),
     /// Expression statement (expression with side effect).
     ExprStmt(Expr),
     /// Return statement.
-    ReturnStmt(Expr),
+    ReturnStmt(Option<Expr>),
+    /// Break statement. Exits the innermost loop.
+    BreakStmt,
+    /// Continue statement. Skips to the next iteration of the innermost loop.
+    ContinueStmt,
+    /// Try statement (e.g. `try { ... } catch e { ... }`).
+    /// If a value is thrown inside `try_block`, it is bound to `binding` and `catch_block` is executed.
+    TryStmt {
+        try_block: Vec<Stmt>,
+        /// A [`StmtKind::Binding`] for the thrown value. Only accessible inside `catch_block`.
+        binding: Box<Stmt>,
+        catch_block: Vec<Stmt>,
+    },
+    /// Throw statement (e.g. `throw "error";`). Unwinds to the innermost enclosing `try` statement.
+    ThrowStmt(Expr),
     /// Error token. Used for error recovery.
     Error,
     /// A lambda "statement". There are no fields as this is only a marker, stored inside [`ExprKind::Lambda`] for variable resolution.
@@ -1,17 +1,27 @@
 //! Source code lexing (aka scanning, tokenizing).
 
-use logos::Logos;
+use std::iter::Peekable;
+use std::mem;
+use std::ops::Range;
+use std::str::CharIndices;
+
+use ella_source::SyntaxError;
+use logos::{Filter, Lexer, Logos, Skip};
 
 /// Represents a source code `Token`.
+/// Errors that are recoverable (e.g. invalid escape sequences) are accumulated in `extras` instead of producing [`Token::Error`].
 #[derive(Debug, Logos, Clone, PartialEq)]
+#[logos(extras = Vec<SyntaxError>)]
 pub enum Token {
     // literals
-    #[regex(r"[0-9.]+", |lex| lex.slice().parse())]
+    /// A number literal (e.g. `1.5`, `1e-9`, `0xff`, `0b1010` or `1_000_000`). See [`lex_number`].
+    #[regex("[0-9]", lex_number)]
     NumberLit(f64),
-    #[regex(r"true|false", |lex| if lex.slice() == "true" { true } else { false } )]
+    #[regex(r"true|false", |lex| lex.slice() == "true" )]
     BoolLit(bool),
-    #[regex(r#""[^"]*""#, |lex| lex.slice()[1..lex.slice().len() - 1].to_string())]
-    StringLit(String),
+    /// A string literal. Contains a single [`StringSegment::Text`] unless the string is interpolated.
+    #[token("\"", lex_string)]
+    StringLit(Vec<StringSegment>),
 
     // identifiers
     #[regex("[a-zA-Z_]+[a-zA-Z0-9_]*", |lex| lex.slice().to_string())]
@@ -20,6 +30,8 @@ pub enum Token {
     // unary operators
     #[token("!")]
     LogicalNot,
+    #[token("~")]
+    Tilde,
 
     // binary operators
     // - arithmetics
@@ -31,6 +43,21 @@ pub enum Token {
     Asterisk,
     #[token("/")]
     Slash,
+    #[token("%")]
+    Percent,
+    #[token("**")]
+    DoubleAsterisk,
+    // - bitwise
+    #[token("&")]
+    Ampersand,
+    #[token("|")]
+    Pipe,
+    #[token("^")]
+    Caret,
+    #[token("<<")]
+    ShiftLeft,
+    #[token(">>")]
+    ShiftRight,
     // - assignment
     #[token("=")]
     Equals,
@@ -42,6 +69,20 @@ pub enum Token {
     AsteriskEquals,
     #[token("/=")]
     SlashEquals,
+    #[token("%=")]
+    PercentEquals,
+    #[token("**=")]
+    DoubleAsteriskEquals,
+    #[token("&=")]
+    AmpersandEquals,
+    #[token("|=")]
+    PipeEquals,
+    #[token("^=")]
+    CaretEquals,
+    #[token("<<=")]
+    ShiftLeftEquals,
+    #[token(">>=")]
+    ShiftRightEquals,
     // - equality
     #[token("==")]
     EqualsEquals,
@@ -56,6 +97,17 @@ pub enum Token {
     LessThan,
     #[token("<=")]
     LessThanEquals,
+    // - match arms
+    #[token("=>")]
+    FatArrow,
+    // - return type
+    #[token("->")]
+    Arrow,
+    // - logical
+    #[token("&&")]
+    LogicalAnd,
+    #[token("||")]
+    LogicalOr,
 
     // punctuation
     #[token("(")]
@@ -66,10 +118,18 @@ pub enum Token {
     OpenBrace,
     #[token("}")]
     CloseBrace,
+    #[token("[")]
+    OpenBracket,
+    #[token("]")]
+    CloseBracket,
     #[token(",")]
     Comma,
     #[token(":")]
     Colon,
+    #[token(".")]
+    Dot,
+    #[token("...")]
+    Ellipsis,
     #[token(";")]
     Semi,
 
@@ -78,6 +138,8 @@ pub enum Token {
     Fn,
     #[token("let")]
     Let,
+    #[token("const")]
+    Const,
     #[token("return")]
     Return,
     #[token("if")]
@@ -86,10 +148,39 @@ pub enum Token {
     Else,
     #[token("while")]
     While,
+    #[token("for")]
+    For,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
+    #[token("struct")]
+    Struct,
+    #[token("class")]
+    Class,
+    #[token("enum")]
+    Enum,
+    #[token("match")]
+    Match,
+    #[token("import")]
+    Import,
+    #[token("export")]
+    Export,
+    #[token("try")]
+    Try,
+    #[token("catch")]
+    Catch,
+    #[token("throw")]
+    Throw,
 
     // misc
+    /// A doc comment (e.g. `/// Adds two numbers.`) without the leading `///` and space.
+    /// Doc comments are attached to the declaration that follows them by the parser.
+    #[regex(r"///[^\n]*", lex_doc_comment)]
+    DocComment(String),
     #[regex(r"[ \t\n\r\f]+", logos::skip)]
     #[regex(r"//[^\n]*", logos::skip)] // single line comments
+    #[token("/*", lex_block_comment)] // block comments
     #[error]
     Error,
 
@@ -97,28 +188,364 @@ pub enum Token {
     Eof,
 }
 
+/// A segment of a string literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringSegment {
+    /// Literal text with escape sequences already processed.
+    Text(String, Range<usize>),
+    /// An interpolated expression (e.g. `${x + 1}`). Stores the span of the expression without the `${` and `}`.
+    Hole(Range<usize>),
+}
+
+/// Lexes a doc comment. Comments starting with more than 3 slashes (e.g. `//// ...`) are regular comments.
+fn lex_doc_comment(lex: &mut Lexer<Token>) -> Filter<String> {
+    let content = &lex.slice()[3..];
+    if content.starts_with('/') {
+        Filter::Skip
+    } else {
+        let content = content.strip_prefix(' ').unwrap_or(content);
+        Filter::Emit(content.trim_end().to_string())
+    }
+}
+
+/// Skips a block comment. The opening `/*` is already consumed. Block comments can be nested.
+/// An unterminated block comment is reported in `lex.extras` and extends to the end of the source.
+fn lex_block_comment(lex: &mut Lexer<Token>) -> Skip {
+    let source = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut index = 0;
+    while index < source.len() {
+        if source[index..].starts_with(b"/*") {
+            depth += 1;
+            index += 2;
+        } else if source[index..].starts_with(b"*/") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                lex.bump(index);
+                return Skip;
+            }
+        } else {
+            index += 1;
+        }
+    }
+
+    lex.extras.push(
+        SyntaxError::new("unterminated block comment", lex.span())
+            .with_help("add a closing \"*/\" (block comments can be nested)"),
+    );
+    lex.bump(source.len());
+    Skip
+}
+
+/// Lexes a number literal. The first digit is already consumed.
+/// Malformed literals (e.g. `1.2.3` or `0xg`) are reported in `lex.extras` and have the value `NaN`.
+///
+/// The grammar is (`_` can be used as a separator anywhere after the first digit):
+/// * decimal: `[0-9]+ ("." [0-9]+)? ([eE] [+-]? [0-9]+)?`
+/// * hexadecimal: `0x [0-9a-fA-F]+`
+/// * binary: `0b [01]+`
+fn lex_number(lex: &mut Lexer<Token>) -> f64 {
+    let start = lex.span().start;
+    let source = &lex.source()[start..];
+    let is_decimal = !(source.starts_with("0x") || source.starts_with("0b"));
+
+    // The literal is the longest sequence of alphanumeric characters, `_`, `.` followed by a digit
+    // and signs in exponents. Everything in the sequence has to be valid.
+    let bytes = source.as_bytes();
+    let mut len = 1;
+    while let Some(&byte) = bytes.get(len) {
+        let is_part = match byte {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' => true,
+            b'.' => bytes.get(len + 1).is_some_and(u8::is_ascii_digit),
+            b'+' | b'-' => is_decimal && matches!(bytes[len - 1], b'e' | b'E'),
+            _ => false,
+        };
+        if !is_part {
+            break;
+        }
+        len += 1;
+    }
+    let literal = &source[..len];
+    lex.bump(len - 1);
+
+    match parse_number(literal) {
+        Ok(value) => value,
+        Err(message) => {
+            lex.extras.push(
+                SyntaxError::new(
+                    format!("invalid number literal \"{}\": {}", literal, message),
+                    start..start + len,
+                )
+                .with_help("number literals can be decimal (1.5e-3), hexadecimal (0xff) or binary (0b1010)"),
+            );
+            f64::NAN
+        }
+    }
+}
+
+/// Parses a number literal (see [`lex_number`]). Returns an error message if the literal is malformed.
+fn parse_number(literal: &str) -> Result<f64, String> {
+    let digits: String = literal.chars().filter(|ch| *ch != '_').collect();
+    let radix_digits = |radix: u32, name: &str| {
+        let digits = &digits[2..];
+        if digits.is_empty() {
+            return Err(format!(
+                "expected {} digits after \"{}\"",
+                name,
+                &literal[..2]
+            ));
+        }
+        if let Some(ch) = digits.chars().find(|ch| !ch.is_digit(radix)) {
+            return Err(format!("invalid {} digit \"{}\"", name, ch));
+        }
+        u64::from_str_radix(digits, radix)
+            .map(|value| value as f64)
+            .map_err(|_| "literal is too large".to_string())
+    };
+    if literal.starts_with("0x") {
+        return radix_digits(16, "hexadecimal");
+    } else if literal.starts_with("0b") {
+        return radix_digits(2, "binary");
+    }
+
+    // decimal
+    let mut chars = digits.chars().peekable();
+    let mut integer_digits = 0;
+    while chars.next_if(char::is_ascii_digit).is_some() {
+        integer_digits += 1;
+    }
+    debug_assert!(integer_digits > 0, "literal starts with a digit");
+    if chars.next_if_eq(&'.').is_some() {
+        // `.` is only part of the literal if it is followed by a digit
+        while chars.next_if(char::is_ascii_digit).is_some() {}
+        if chars.peek() == Some(&'.') {
+            return Err("more than one decimal point".to_string());
+        }
+    }
+    if chars.next_if(|ch| *ch == 'e' || *ch == 'E').is_some() {
+        chars.next_if(|ch| *ch == '+' || *ch == '-');
+        if chars.next_if(char::is_ascii_digit).is_none() {
+            return Err("expected digits in exponent".to_string());
+        }
+        while chars.next_if(char::is_ascii_digit).is_some() {}
+    }
+    match chars.next() {
+        Some('.') => Err("decimal point after exponent".to_string()),
+        Some(ch) => Err(format!("invalid digit \"{}\"", ch)),
+        None => Ok(digits.parse().expect("literal is a valid float")),
+    }
+}
+
+/// Lexes a string literal. The opening quote is already consumed.
+/// The string is split into text and interpolated expressions (see [`StringSegment`]).
+/// Errors (e.g. invalid escape sequences) are reported in `lex.extras` and are skipped.
+fn lex_string(lex: &mut Lexer<Token>) -> Vec<StringSegment> {
+    let start = lex.span().end; // position after opening quote
+    let remainder = lex.remainder();
+
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut text_start = start;
+    let mut chars = remainder.char_indices().peekable();
+    let (content_end, end) = loop {
+        let (i, ch) = match chars.next() {
+            Some(next) => next,
+            None => {
+                lex.extras.push(SyntaxError::new(
+                    "unterminated string literal",
+                    lex.span().start..start + remainder.len(),
+                ));
+                break (remainder.len(), remainder.len());
+            }
+        };
+        match ch {
+            '"' => break (i, i + 1),
+            '\\' => {
+                if let Some(ch) = lex_escape(&mut lex.extras, &mut chars, start, i) {
+                    text.push(ch);
+                }
+            }
+            '$' if chars.peek().map(|(_, ch)| *ch) == Some('{') => {
+                match find_hole_end(&remainder[i + 2..]) {
+                    Some(len) => {
+                        if !text.is_empty() {
+                            segments.push(StringSegment::Text(
+                                mem::take(&mut text),
+                                text_start..start + i,
+                            ));
+                        }
+                        let hole_start = start + i + 2;
+                        segments.push(StringSegment::Hole(hole_start..hole_start + len));
+                        // skip opening brace, expression and closing brace
+                        while chars.next_if(|(j, _)| *j <= i + 2 + len).is_some() {}
+                        text_start = hole_start + len + 1;
+                    }
+                    None => {
+                        lex.extras.push(SyntaxError::new(
+                            "unterminated string interpolation",
+                            start + i..start + remainder.len(),
+                        ));
+                        break (remainder.len(), remainder.len());
+                    }
+                }
+            }
+            _ => text.push(ch),
+        }
+    };
+    if !text.is_empty() || segments.is_empty() {
+        segments.push(StringSegment::Text(text, text_start..start + content_end));
+    }
+
+    lex.bump(end);
+    segments
+}
+
+/// Lexes an escape sequence. `chars` should start right after the backslash at index `backslash` (relative to `offset`).
+/// Returns `None` and reports an error in `errors` if the escape sequence is invalid.
+fn lex_escape(
+    errors: &mut Vec<SyntaxError>,
+    chars: &mut Peekable<CharIndices>,
+    offset: usize,
+    backslash: usize,
+) -> Option<char> {
+    let (_, escape) = chars.next()?; // unterminated string is reported by the caller
+    let unescaped = match escape {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '$' => Some('$'),
+        'u' => {
+            // unicode escape with 1 to 6 hex digits, e.g. `\u{1F600}`
+            let mut digits = String::new();
+            let mut terminated = false;
+            if chars.next_if(|(_, ch)| *ch == '{').is_some() {
+                while let Some((_, ch)) = chars.next_if(|(_, ch)| *ch != '"') {
+                    if ch == '}' {
+                        terminated = true;
+                        break;
+                    }
+                    digits.push(ch);
+                }
+            }
+            let end = chars
+                .peek()
+                .map_or(backslash + 2 + digits.len(), |(i, _)| *i);
+            let unescaped = match terminated && (1..=6).contains(&digits.len()) {
+                true => u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32),
+                false => None,
+            };
+            if unescaped.is_none() {
+                errors.push(
+                    SyntaxError::new("invalid unicode escape", offset + backslash..offset + end)
+                        .with_help(
+                            "unicode escapes must have the form \\u{XXXX} with 1 to 6 hex digits",
+                        ),
+                );
+            }
+            return unescaped;
+        }
+        _ => None,
+    };
+    if unescaped.is_none() {
+        let end = backslash + 1 + escape.len_utf8();
+        errors.push(SyntaxError::new(
+            format!("unknown character escape \"\\{}\"", escape),
+            offset + backslash..offset + end,
+        ));
+    }
+    unescaped
+}
+
+/// Returns the index of the `}` that closes an interpolated expression or `None` if it is unterminated.
+/// `source` should start right after the opening `${`. Nested braces and string literals are skipped.
+fn find_hole_end(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while let Some(ch) = source[i..].chars().next() {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            '"' => i += find_string_end(&source[i + 1..])? + 1,
+            _ => {}
+        }
+        i += ch.len_utf8();
+    }
+    None
+}
+
+/// Returns the index of the closing quote of a string literal or `None` if it is unterminated.
+/// `source` should start right after the opening quote.
+fn find_string_end(source: &str) -> Option<usize> {
+    let mut i = 0;
+    while let Some(ch) = source[i..].chars().next() {
+        match ch {
+            '"' => return Some(i),
+            '\\' => i += source[i + 1..].chars().next()?.len_utf8(),
+            '$' if source[i + 1..].starts_with('{') => i += find_hole_end(&source[i + 2..])? + 2,
+            _ => {}
+        }
+        i += ch.len_utf8();
+    }
+    None
+}
+
 impl Token {
     /// Returns the binary binding power or `None` if invalid binop token.
     /// Binding power `0` and `1` is reserved for accepting any expression.
     /// Assignment ([`Token::Equals`]) has the lowest precedence with `(3, 2)`.
     pub fn binop_bp(&self) -> Option<(u8, u8)> {
         match self {
-            /* Additive */
-            Token::Plus | Token::Minus => Some((8, 9)),
+            /* Exponentiation */
+            // Right associative and binds tighter than prefix operators (`-2 ** 2` is `-(2 ** 2)`).
+            Token::DoubleAsterisk => Some((26, 25)),
             /* Multiplicative */
-            Token::Asterisk | Token::Slash => Some((10, 11)),
+            Token::Asterisk | Token::Slash | Token::Percent => Some((22, 23)),
+            /* Additive */
+            Token::Plus | Token::Minus => Some((20, 21)),
+            /* Bitwise */
+            Token::ShiftLeft | Token::ShiftRight => Some((18, 19)),
+            Token::Ampersand => Some((16, 17)),
+            Token::Caret => Some((14, 15)),
+            Token::Pipe => Some((12, 13)),
             /* Assignment */
             Token::Equals
             | Token::PlusEquals
             | Token::MinusEquals
             | Token::AsteriskEquals
-            | Token::SlashEquals => Some((3, 2)),
+            | Token::SlashEquals
+            | Token::PercentEquals
+            | Token::DoubleAsteriskEquals
+            | Token::AmpersandEquals
+            | Token::PipeEquals
+            | Token::CaretEquals
+            | Token::ShiftLeftEquals
+            | Token::ShiftRightEquals => Some((3, 2)),
+            /* Logical */
+            Token::LogicalOr => Some((4, 5)),
+            Token::LogicalAnd => Some((6, 7)),
             /* Equality */
-            Token::EqualsEquals | Token::NotEquals => Some((4, 5)),
+            Token::EqualsEquals | Token::NotEquals => Some((8, 9)),
             Token::GreaterThan
             | Token::GreaterThanEquals
             | Token::LessThan
-            | Token::LessThanEquals => Some((6, 7)),
+            | Token::LessThanEquals => Some((10, 11)),
+            _ => None,
+        }
+    }
+
+    /// Returns the prefix binding power or `None` if invalid prefix token.
+    /// Prefix operators bind tighter than all binary operators.
+    pub fn prefix_bp(&self) -> Option<((), u8)> {
+        match self {
+            Token::LogicalNot | Token::Minus | Token::Tilde => Some(((), 24)),
             _ => None,
         }
     }
@@ -127,22 +554,22 @@ impl Token {
     /// Binding power `0` and `1` is reserved for accepting any expression.
     pub fn postfix_bp(&self) -> Option<(u8, ())> {
         match self {
-            Token::OpenParen => Some((12, ())),
+            Token::OpenParen | Token::OpenBracket | Token::Dot => Some((28, ())),
             _ => None,
         }
     }
 
     /// Returns `true` if the token is synchronization point for error recovery.
     pub fn is_sync_point(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Token::Semi
-            | Token::OpenBrace
-            | Token::CloseBrace
-            | Token::OpenParen
-            | Token::CloseParen
-            | Token::Error
-            | Token::Eof => true,
-            _ => false,
-        }
+                | Token::OpenBrace
+                | Token::CloseBrace
+                | Token::OpenParen
+                | Token::CloseParen
+                | Token::Error
+                | Token::Eof
+        )
     }
 }
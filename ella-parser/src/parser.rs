@@ -9,8 +9,6 @@ use std::ops::Range;
 
 mod expr;
 mod stmt;
-pub use expr::*;
-pub use stmt::*;
 
 /// A parser instance.
 pub struct Parser<'a> {
@@ -19,6 +17,8 @@ pub struct Parser<'a> {
     previous_span: Range<usize>,
     /// Location of the current token.
     current_span: Range<usize>,
+    /// Doc comments (`///`) directly preceding the current token.
+    current_doc: Option<String>,
     lexer: Lexer<'a, Token>,
     /// Source code.
     source: &'a Source<'a>,
@@ -27,14 +27,16 @@ pub struct Parser<'a> {
 impl<'a> Parser<'a> {
     /// Create a new [`Parser`] from the `source`.
     pub fn new(source: &'a Source<'a>) -> Self {
-        let mut lexer = Token::lexer(source.content);
-        Self {
-            current_token: lexer.next().unwrap_or(Token::Eof),
+        let mut parser = Self {
+            current_token: Token::Eof,
             previous_span: 0..0,
-            current_span: lexer.span(),
-            lexer,
+            current_span: 0..0,
+            current_doc: None,
+            lexer: Token::lexer(source.content),
             source,
-        }
+        };
+        parser.next(); // load first token
+        parser
     }
 }
 
@@ -51,13 +53,17 @@ impl<'a> Parser<'a> {
         StmtKind::FnDeclaration {
             body,
             ident: "<global>".to_string(),
+            type_params: Vec::new(),
             params: Vec::new(),
+            ret_ty: None,
+            doc: None,
         }
         .with_span(lo..self.node_end())
     }
 
     /// Returns an anonymous top level function.
-    /// If the last statement is an [`Stmt::ExprStmt`], it will create a function call to `println()`.
+    /// If the last statement is an [`Stmt::ExprStmt`], it will create a function call to `repl_println()`,
+    /// which prints the result unless it is the unit value.
     pub fn parse_repl_input(&mut self) -> Stmt {
         let lo = self.node_start();
 
@@ -73,7 +79,8 @@ impl<'a> Parser<'a> {
         {
             *expr = ExprKind::FnCall {
                 args: vec![expr.clone()],
-                callee: Box::new(ExprKind::Identifier("println".to_string()).with_span(0..0)),
+                callee: Box::new(ExprKind::Identifier("repl_println".to_string()).with_span(0..0)),
+                named_args: Vec::new(),
             }
             .with_span(0..0)
         }
@@ -81,7 +88,10 @@ impl<'a> Parser<'a> {
         StmtKind::FnDeclaration {
             body,
             ident: "<global>".to_string(),
+            type_params: Vec::new(),
             params: Vec::new(),
+            ret_ty: None,
+            doc: None,
         }
         .with_span(lo..self.node_end())
     }
@@ -89,15 +99,35 @@ impl<'a> Parser<'a> {
 
 /// Parser utilities.
 impl<'a> Parser<'a> {
+    /// Advances to the next token. Doc comments are not returned as tokens but are stored in `current_doc`.
     fn next(&mut self) -> Token {
-        let token = self.lexer.next().unwrap_or(Token::Eof);
+        let mut doc_lines = Vec::new();
+        let token = loop {
+            match self.lexer.next().unwrap_or(Token::Eof) {
+                Token::DocComment(line) => doc_lines.push(line),
+                token => break token,
+            }
+        };
         self.current_token = token.clone();
+        self.current_doc = if doc_lines.is_empty() {
+            None
+        } else {
+            Some(doc_lines.join("\n"))
+        };
 
         self.previous_span = self.current_span.clone();
         self.current_span = self.lexer.span();
+        self.report_lexer_errors();
         token
     }
 
+    /// Moves the errors accumulated by the lexer into the [`Source`].
+    fn report_lexer_errors(&mut self) {
+        for error in self.lexer.extras.drain(..) {
+            self.source.errors.add_error(error);
+        }
+    }
+
     /// Predicate that tests whether the next token has the same discriminant and eats the next token if yes as a side effect.
     #[must_use = "to unconditionally eat a token, use Self::next"]
     fn eat(&mut self, tok: Token) -> bool {
@@ -126,6 +156,24 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Eats a `>` token that closes a list of type arguments.
+    /// Tokens starting with `>` (e.g. `>>` in `List<List<number>>`) are split and only the first `>` is eaten.
+    #[must_use]
+    fn eat_closing_angle(&mut self) -> bool {
+        let rest = match self.current_token {
+            Token::GreaterThan => return self.eat(Token::GreaterThan),
+            Token::ShiftRight => Token::GreaterThan,
+            Token::GreaterThanEquals => Token::Equals,
+            Token::ShiftRightEquals => Token::GreaterThanEquals,
+            _ => return false,
+        };
+        let start = self.current_span.start;
+        self.previous_span = start..start + 1;
+        self.current_span = start + 1..self.current_span.end;
+        self.current_token = rest;
+        true
+    }
+
     /// Raises an unexpected token error.
     fn unexpected(&mut self) {
         self.source.errors.add_error(SyntaxError::new(
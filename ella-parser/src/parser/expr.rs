@@ -1,4 +1,5 @@
-use crate::ast::ExprKind;
+use crate::ast::{ExprKind, MatchArm, NamedArg, Pattern, PatternKind, StmtKind};
+use crate::lexer::StringSegment;
 
 use super::*;
 
@@ -14,35 +15,26 @@ impl<'a> Parser<'a> {
     fn parse_primary_expr(&mut self) -> Expr {
         // NOTE: prefix operators are handled here
         match self.current_token {
-            Token::NumberLit(_) | Token::BoolLit(_) | Token::StringLit(_) => {
-                self.parse_literal_expr()
-            }
+            Token::NumberLit(_) | Token::BoolLit(_) => self.parse_literal_expr(),
+            Token::StringLit(_) => self.parse_string_lit_expr(),
             Token::Identifier(_) => self.parse_identifier_expr(),
-            Token::LogicalNot => {
-                let lo = self.node_start();
-                self.next();
-                ExprKind::Unary {
-                    op: Token::LogicalNot,
-                    arg: Box::new(self.parse_expr()),
-                }
-                .with_span(lo..self.node_end())
-            }
-            Token::Minus => {
+            Token::LogicalNot | Token::Minus | Token::Tilde => {
                 let lo = self.node_start();
+                let op = self.current_token.clone();
+                let ((), r_bp) = op.prefix_bp().unwrap();
                 self.next();
                 ExprKind::Unary {
-                    op: Token::Minus,
-                    arg: Box::new(self.parse_expr()),
+                    op,
+                    arg: Box::new(self.parse_expr_bp(r_bp)),
                 }
                 .with_span(lo..self.node_end())
             }
-            Token::OpenParen => {
-                self.next();
-                let expr = self.parse_expr();
-                self.expect(Token::CloseParen);
-                expr
-            }
+            Token::OpenParen => self.parse_paren_expr(),
+            Token::OpenBracket => self.parse_list_lit_expr(),
+            Token::OpenBrace => self.parse_brace_expr(),
+            Token::If => self.parse_if_expr(),
             Token::Fn => self.parse_lambda_expr(),
+            Token::Match => self.parse_match_expr(),
             _ => {
                 let lo = self.node_start();
                 self.next();
@@ -70,10 +62,33 @@ impl<'a> Parser<'a> {
                     Token::OpenParen => {
                         // parse call expression
                         let mut args = Vec::new();
+                        let mut named_args = Vec::new();
 
                         if !self.eat(Token::CloseParen) {
                             loop {
-                                args.push(self.parse_expr());
+                                let arg = self.parse_expr();
+                                match arg.kind {
+                                    // named argument (e.g. `port: 9000`)
+                                    ExprKind::Identifier(ident) if self.eat(Token::Colon) => {
+                                        let value = self.parse_expr();
+                                        named_args.push(NamedArg {
+                                            ident,
+                                            value,
+                                            span: arg.span.start..self.node_end(),
+                                        });
+                                    }
+                                    _ => {
+                                        if !named_args.is_empty() {
+                                            self.source.errors.add_error(
+                                                SyntaxError::new(
+                                                    "positional arguments must come before named arguments",
+                                                    arg.span.clone(),
+                                                ),
+                                            );
+                                        }
+                                        args.push(arg);
+                                    }
+                                }
 
                                 if self.eat(Token::CloseParen) {
                                     break;
@@ -89,6 +104,38 @@ impl<'a> Parser<'a> {
                         lhs = ExprKind::FnCall {
                             callee: Box::new(lhs),
                             args,
+                            named_args,
+                        }
+                        .with_span(lo..self.node_end());
+                    }
+                    Token::OpenBracket => {
+                        // parse index expression
+                        let index = self.parse_expr();
+                        self.expect(Token::CloseBracket);
+
+                        let lo = lhs.span.start;
+                        lhs = ExprKind::Index {
+                            target: Box::new(lhs),
+                            index: Box::new(index),
+                        }
+                        .with_span(lo..self.node_end());
+                    }
+                    Token::Dot => {
+                        // parse field access expression
+                        let ident = if let Token::Identifier(ref ident) = self.current_token {
+                            let ident = ident.clone();
+                            self.next();
+                            ident
+                        } else {
+                            self.next();
+                            self.unexpected();
+                            return ExprKind::Error.with_span(lhs.span.start..self.node_end());
+                        };
+
+                        let lo = lhs.span.start;
+                        lhs = ExprKind::FieldAccess {
+                            target: Box::new(lhs),
+                            ident,
                         }
                         .with_span(lo..self.node_end());
                     }
@@ -135,7 +182,6 @@ impl<'a> Parser<'a> {
         let val = match self.current_token {
             Token::NumberLit(val) => ExprKind::NumberLit(val),
             Token::BoolLit(val) => ExprKind::BoolLit(val),
-            Token::StringLit(ref val) => ExprKind::StringLit(val.clone()),
             _ => {
                 self.unexpected();
                 ExprKind::Error
@@ -148,6 +194,70 @@ impl<'a> Parser<'a> {
         val.with_span(lo..self.node_end())
     }
 
+    /* Expressions.String */
+    /// Parses a string literal or an interpolated string (e.g. `"x = ${x}"`).
+    fn parse_string_lit_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        let segments = match &self.current_token {
+            Token::StringLit(segments) => segments.clone(),
+            _ => unreachable!("current token should be a string literal"),
+        };
+        self.next();
+        let hi = self.node_end();
+
+        let kind = match segments.as_slice() {
+            [StringSegment::Text(text, _)] => ExprKind::StringLit(text.clone()),
+            _ => ExprKind::Interpolation(
+                segments
+                    .into_iter()
+                    .map(|segment| match segment {
+                        StringSegment::Text(text, span) => {
+                            ExprKind::StringLit(text).with_span(span)
+                        }
+                        StringSegment::Hole(span) => self.parse_interpolated_expr(span),
+                    })
+                    .collect(),
+            ),
+        };
+        kind.with_span(lo..hi)
+    }
+
+    /// Parses an interpolated expression inside a string. `span` is the location of the expression (without the `${` and `}`).
+    /// The expression is parsed with a new lexer that stops at the end of the expression, so syntax errors can not consume
+    /// the rest of the string. The state of the current lexer is restored afterwards.
+    fn parse_interpolated_expr(&mut self, span: Range<usize>) -> Expr {
+        if self.source.content[span.clone()].trim().is_empty() {
+            self.source.errors.add_error(SyntaxError::new(
+                "expected an expression in string interpolation",
+                span.clone(),
+            ));
+            return ExprKind::Error.with_span(span);
+        }
+
+        let mut lexer = Token::lexer(&self.source.content[..span.end]);
+        lexer.bump(span.start);
+        let outer_lexer = mem::replace(&mut self.lexer, lexer);
+        let outer_token = self.current_token.clone();
+        let outer_previous_span = self.previous_span.clone();
+        let outer_current_span = self.current_span.clone();
+
+        self.next();
+        let expr = self.parse_expr();
+        if self.current_span.start < span.end {
+            self.source.errors.add_error(SyntaxError::new(
+                "expected `}` after expression in string interpolation",
+                self.current_span.start..span.end,
+            ));
+        }
+
+        self.lexer = outer_lexer;
+        self.current_token = outer_token;
+        self.previous_span = outer_previous_span;
+        self.current_span = outer_current_span;
+        expr
+    }
+
     /* Expressions.Identifier */
     /// Parses an identifier or a call expression.
     fn parse_identifier_expr(&mut self) -> Expr {
@@ -168,26 +278,292 @@ impl<'a> Parser<'a> {
         ExprKind::Identifier(ident).with_span(lo..self.node_end())
     }
 
-    /* Expressions.Lambda */
-    /// Parses a lambda expression.
-    fn parse_lambda_expr(&mut self) -> Expr {
+    /* Expressions.Tuple */
+    /// Parses a parenthesized expression (e.g. `(1 + 2)`) or a tuple literal (e.g. `(1, 2)` or `(1,)`).
+    fn parse_paren_expr(&mut self) -> Expr {
         let lo = self.node_start();
 
-        self.expect(Token::Fn);
-
         self.expect(Token::OpenParen);
-        let mut params = Vec::new();
-        if !self.eat(Token::CloseParen) {
+        let first = self.parse_expr();
+        if !self.eat(Token::Comma) {
+            self.expect(Token::CloseParen);
+            return first;
+        }
+
+        let mut elements = vec![first];
+        // allow trailing comma
+        while !self.eat(Token::CloseParen) {
+            elements.push(self.parse_expr());
+
+            if self.eat(Token::CloseParen) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.next();
+                self.unexpected();
+                break;
+            }
+        }
+
+        ExprKind::TupleLit(elements).with_span(lo..self.node_end())
+    }
+
+    /* Expressions.List */
+    /// Parses a list literal (e.g. `[1, 2, 3]`).
+    fn parse_list_lit_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::OpenBracket);
+        let mut elements = Vec::new();
+        if !self.eat(Token::CloseBracket) {
             loop {
-                params.push(if let Token::Identifier(ref ident) = self.current_token {
+                elements.push(self.parse_expr());
+
+                if self.eat(Token::CloseBracket) {
+                    break;
+                } else if !self.eat(Token::Comma) {
+                    self.next();
+                    self.unexpected();
+                    break;
+                }
+            }
+        }
+
+        ExprKind::ListLit(elements).with_span(lo..self.node_end())
+    }
+
+    /// Parses an expression starting with a `{`.
+    /// This is a map literal if the first expression is followed by a `:` (or if it is empty) and a block expression otherwise.
+    fn parse_brace_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::OpenBrace);
+        if self.eat(Token::CloseBrace) {
+            return ExprKind::MapLit(Vec::new()).with_span(lo..self.node_end());
+        }
+        if Self::is_declaration_start(&self.current_token) {
+            return self.parse_block_expr_rest(lo, None);
+        }
+
+        let first = self.parse_expr();
+        if self.eat(Token::Colon) {
+            self.parse_map_lit_expr_rest(lo, first)
+        } else {
+            self.parse_block_expr_rest(lo, Some(first))
+        }
+    }
+
+    /* Expressions.Map */
+    /// Parses the rest of a map literal (e.g. `{ "a": 1, "b": 2 }`) after the first key and the `:`.
+    fn parse_map_lit_expr_rest(&mut self, lo: usize, first_key: Expr) -> Expr {
+        let mut entries = Vec::new();
+        let mut key = first_key;
+        loop {
+            let value = self.parse_expr();
+            entries.push((key, value));
+
+            if self.eat(Token::CloseBrace) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.next();
+                self.unexpected();
+                break;
+            }
+            key = self.parse_expr();
+            self.expect(Token::Colon);
+        }
+
+        ExprKind::MapLit(entries).with_span(lo..self.node_end())
+    }
+
+    /* Expressions.Block */
+    /// Parses a block expression (e.g. `{ let x = 1; x + 1 }`). Unlike [`Self::parse_brace_expr`], `{}` is an empty block.
+    fn parse_block_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::OpenBrace);
+        self.parse_block_expr_rest(lo, None)
+    }
+
+    /// Parses the rest of a block expression after the `{`.
+    /// `first` is the first expression in the block if it was already parsed (see [`Self::parse_brace_expr`]).
+    fn parse_block_expr_rest(&mut self, lo: usize, mut first: Option<Expr>) -> Expr {
+        let mut stmts = Vec::new();
+        let mut expr = None;
+        loop {
+            let item = match first.take() {
+                Some(item) => item,
+                None => {
+                    if self.eat(Token::CloseBrace) {
+                        break;
+                    } else if self.current_token == Token::Eof {
+                        self.unexpected();
+                        break;
+                    } else if Self::is_declaration_start(&self.current_token) {
+                        stmts.push(self.parse_declaration());
+                        continue;
+                    }
+                    self.parse_expr()
+                }
+            };
+
+            if self.current_token == Token::CloseBrace {
+                expr = Some(Box::new(item));
+            } else {
+                // block-like expressions do not need a trailing semicolon
+                let is_block_like = matches!(
+                    item.kind,
+                    ExprKind::Block { .. } | ExprKind::If { .. } | ExprKind::Match { .. }
+                );
+                if !is_block_like || self.current_token == Token::Semi {
+                    self.expect(Token::Semi);
+                }
+                let item_lo = item.span.start;
+                stmts.push(StmtKind::ExprStmt(item).with_span(item_lo..self.node_end()));
+            }
+        }
+
+        ExprKind::Block { stmts, expr }.with_span(lo..self.node_end())
+    }
+
+    /// Returns `true` if `token` starts a declaration or a statement that is not an expression statement.
+    fn is_declaration_start(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Let
+                | Token::Const
+                | Token::Fn
+                | Token::Struct
+                | Token::Class
+                | Token::Enum
+                | Token::Import
+                | Token::Export
+                | Token::Return
+                | Token::Break
+                | Token::Continue
+                | Token::Try
+                | Token::Throw
+                | Token::While
+                | Token::For
+        )
+    }
+
+    /* Expressions.If */
+    /// Parses an if expression (e.g. `if x > 0 { 1 } else { 2 }`).
+    fn parse_if_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::If);
+        let condition = self.parse_expr();
+        let then_branch = self.parse_block_expr();
+        let else_branch = if self.eat(Token::Else) {
+            Some(Box::new(if self.current_token == Token::If {
+                self.parse_if_expr()
+            } else {
+                self.parse_block_expr()
+            }))
+        } else {
+            None
+        };
+
+        ExprKind::If {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
+        }
+        .with_span(lo..self.node_end())
+    }
+
+    /* Expressions.Match */
+    /// Parses a match expression (e.g. `match shape { Shape.Circle(r) => r * r, _ => 0 }`).
+    fn parse_match_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::Match);
+        let scrutinee = self.parse_expr();
+
+        self.expect(Token::OpenBrace);
+        let mut arms = Vec::new();
+        // allow trailing comma
+        while !self.eat(Token::CloseBrace) {
+            let pattern = self.parse_pattern();
+            self.expect(Token::FatArrow);
+            let body = self.parse_expr();
+            arms.push(MatchArm { pattern, body });
+
+            if self.eat(Token::CloseBrace) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.next();
+                self.unexpected();
+                break;
+            }
+        }
+
+        let hi = self.node_end();
+        ExprKind::Match {
+            scrutinee: Box::new(scrutinee),
+            inner_stmt: Box::new(
+                StmtKind::Binding {
+                    ident: "match".to_string(), // not a valid identifier, can not be referenced
+                }
+                .with_span(lo..hi),
+            ),
+            arms,
+        }
+        .with_span(lo..hi)
+    }
+
+    /// Parses a pattern in a match arm (e.g. `_` or `Shape.Rect(w, h)`).
+    fn parse_pattern(&mut self) -> Pattern {
+        let lo = self.node_start();
+
+        let enum_ident = match self.current_token {
+            Token::Identifier(ref ident) if ident == "_" => {
+                self.next();
+                return Pattern {
+                    kind: PatternKind::Wildcard,
+                    span: lo..self.node_end(),
+                };
+            }
+            Token::Identifier(ref ident) => {
+                let ident = ident.clone();
+                self.next();
+                ExprKind::Identifier(ident).with_span(lo..self.node_end())
+            }
+            _ => {
+                self.next();
+                self.unexpected();
+                return Pattern {
+                    kind: PatternKind::Wildcard, // error recovery
+                    span: lo..self.node_end(),
+                };
+            }
+        };
+
+        self.expect(Token::Dot);
+        let variant = if let Token::Identifier(ref ident) = self.current_token {
+            let ident = ident.clone();
+            self.next();
+            ident
+        } else {
+            self.next();
+            self.unexpected();
+            String::new()
+        };
+
+        let mut bindings = Vec::new();
+        if self.eat(Token::OpenParen) && !self.eat(Token::CloseParen) {
+            loop {
+                if let Token::Identifier(ref ident) = self.current_token {
                     let ident_lo = self.node_start();
                     let ident = ident.clone();
                     self.next();
-                    StmtKind::FnParam { ident }.with_span(ident_lo..self.node_end())
+                    bindings.push(StmtKind::Binding { ident }.with_span(ident_lo..self.node_end()));
                 } else {
+                    self.next();
                     self.unexpected();
-                    return ExprKind::Error.with_span(lo..self.node_end());
-                });
+                    break;
+                }
 
                 if self.eat(Token::CloseParen) {
                     break;
@@ -198,6 +574,29 @@ impl<'a> Parser<'a> {
             }
         }
 
+        Pattern {
+            kind: PatternKind::Variant {
+                enum_ident: Box::new(enum_ident),
+                variant,
+                bindings,
+            },
+            span: lo..self.node_end(),
+        }
+    }
+
+    /* Expressions.Lambda */
+    /// Parses a lambda expression.
+    fn parse_lambda_expr(&mut self) -> Expr {
+        let lo = self.node_start();
+
+        self.expect(Token::Fn);
+
+        let params = match self.parse_fn_params() {
+            Some(params) => params,
+            None => return ExprKind::Error.with_span(lo..self.node_end()),
+        };
+        let ret_ty = self.parse_optional_return_type();
+
         self.expect(Token::OpenBrace);
         let mut body = Vec::new();
         if !self.eat(Token::CloseBrace) {
@@ -214,6 +613,7 @@ impl<'a> Parser<'a> {
         ExprKind::Lambda {
             inner_stmt: Box::new(StmtKind::Lambda.with_span(lo..hi)),
             params,
+            ret_ty,
             body,
         }
         .with_span(lo..hi)
@@ -232,6 +632,18 @@ mod tests {
         ast
     }
 
+    #[test]
+    fn test_match_expr() {
+        assert_debug_snapshot!(
+            "match-expr",
+            expr("match shape { Shape.Circle(r) => r * r, Shape.Rect(w, h) => w * h, Shape.Empty => 0 }")
+        );
+        assert_debug_snapshot!(
+            "match-expr-wildcard",
+            expr("match shape { Shape.Circle(r) => r, _ => 0, }")
+        );
+    }
+
     #[test]
     fn test_literal() {
         assert_debug_snapshot!("bool-lit-true", expr("true"));
@@ -239,6 +651,52 @@ mod tests {
         assert_debug_snapshot!("int", expr("1"));
         assert_debug_snapshot!("double-2.0", expr("2.0"));
         assert_debug_snapshot!("double-2.5", expr("2.5"));
+        assert_debug_snapshot!("number-exponent", expr("1.5e-3"));
+        assert_debug_snapshot!("number-hex", expr("0xff"));
+        assert_debug_snapshot!("number-binary", expr("0b1010"));
+        assert_debug_snapshot!("number-underscores", expr("1_000_000"));
+        assert_debug_snapshot!("string-lit-escapes", expr(r#""a\tb\n\"c\" \\ \u{1F600}""#));
+    }
+
+    #[test]
+    fn test_string_interpolation() {
+        assert_debug_snapshot!("string-interpolation", expr(r#""x = ${x}, y = ${y + 1}""#));
+    }
+
+    #[test]
+    fn test_invalid_interpolated_expr() {
+        let source = r#""${1 +}""#.into();
+        Parser::new(&source).parse_expr();
+        // the error is reported in the interpolated expression, the string itself is terminated
+        let errors = source.to_string();
+        assert!(errors.contains("unexpected token"), "{}", errors);
+        assert!(
+            !errors.contains("unterminated string literal"),
+            "{}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_invalid_number_literal() {
+        for literal in [
+            "1.2.3", "1e", "1e+", "0x", "0xg", "0b102", "12abc", "1.5e3.2",
+        ] {
+            let source = literal.into();
+            let ast = Parser::new(&source).parse_expr();
+            assert!(!source.has_no_errors(), "{} should be invalid", literal);
+            // the whole literal is a single token
+            assert_eq!(ast.span, 0..literal.len());
+        }
+    }
+
+    #[test]
+    fn test_invalid_string_escape() {
+        let source = r#""a\qb""#.into();
+        let ast = Parser::new(&source).parse_expr();
+        assert!(!source.has_no_errors());
+        // invalid escapes are skipped
+        assert_eq!(ast.kind, ExprKind::StringLit("ab".to_string()));
     }
 
     #[test]
@@ -248,6 +706,17 @@ mod tests {
         assert_debug_snapshot!("binary-associativity", expr("2 * 2 * 2")); // should be (2 * 2) * 2
         assert_debug_snapshot!("binary-associativity-2", expr("a = b = c")); // should be a = (b = c)
         assert_debug_snapshot!("binary-paren", expr("(a + b) * c")); // should be (a + b) * c
+        assert_debug_snapshot!("binary-logical", expr("a || b && c")); // should be a || (b && c)
+        assert_debug_snapshot!("binary-logical-cmp", expr("a < b && c == d")); // should be (a < b) && (c == d)
+        assert_debug_snapshot!("binary-bitwise", expr("a | b ^ c & d << e + f")); // should be a | (b ^ (c & (d << (e + f))))
+        assert_debug_snapshot!("binary-pow", expr("-a ** b ** c")); // should be -(a ** (b ** c))
+    }
+
+    #[test]
+    fn test_unary_expr() {
+        assert_debug_snapshot!("unary-minus", expr("-a + b")); // should be (-a) + b
+        assert_debug_snapshot!("unary-not", expr("!a && b")); // should be (!a) && b
+        assert_debug_snapshot!("unary-fn-call", expr("-foo()")); // should be -(foo())
     }
 
     #[test]
@@ -262,11 +731,72 @@ mod tests {
         assert_debug_snapshot!("fn-call-with-nested-args", expr("foo(1, bar, baz())"));
         assert_debug_snapshot!("fn-call-chained", expr("foo(1, 2)(3)(4)"));
         assert_debug_snapshot!("fn-call-fib", expr("fib(x - 1) + fib(x - 2)"));
+        assert_debug_snapshot!(
+            "fn-call-with-named-args",
+            expr("connect(\"x\", port: 9000, secure: true)")
+        );
+    }
+
+    #[test]
+    fn test_fn_call_positional_after_named() {
+        let source = "foo(a: 1, 2)".into();
+        Parser::new(&source).parse_expr();
+        assert!(!source.has_no_errors());
+    }
+
+    #[test]
+    fn test_list() {
+        assert_debug_snapshot!("list-lit", expr("[1, a, \"b\"]"));
+        assert_debug_snapshot!("list-lit-empty", expr("[]"));
+        assert_debug_snapshot!("list-lit-nested", expr("[[1], []]"));
+        assert_debug_snapshot!("index", expr("foo[1]"));
+        assert_debug_snapshot!("index-chained", expr("foo[1][bar(2)]"));
+        assert_debug_snapshot!("index-assign", expr("foo[i] = 1"));
+    }
+
+    #[test]
+    fn test_tuple() {
+        assert_debug_snapshot!("tuple-lit", expr("(1, a, \"b\")"));
+        assert_debug_snapshot!("tuple-lit-single", expr("(1,)"));
+        assert_debug_snapshot!("tuple-lit-nested", expr("((1, 2), 3)"));
+        assert_debug_snapshot!("paren", expr("(1)"));
+    }
+
+    #[test]
+    fn test_field_access() {
+        assert_debug_snapshot!("field-access", expr("foo.bar"));
+        assert_debug_snapshot!("field-access-chained", expr("foo.bar[0].baz()"));
+        assert_debug_snapshot!("field-access-assign", expr("foo.bar = 1.5"));
+    }
+
+    #[test]
+    fn test_map() {
+        assert_debug_snapshot!("map-lit", expr(r#"{ "a": 1, b: 2 + 3 }"#));
+        assert_debug_snapshot!("map-lit-empty", expr("{}"));
+        assert_debug_snapshot!("map-index", expr(r#"foo["a"]"#));
+    }
+
+    #[test]
+    fn test_block_expr() {
+        assert_debug_snapshot!("block-expr", expr("{ let x = 1; x + 1 }"));
+        assert_debug_snapshot!("block-expr-no-trailing-expr", expr("{ foo(); }"));
+        assert_debug_snapshot!("block-expr-block-like-stmt", expr("{ if a { b } c }"));
+    }
+
+    #[test]
+    fn test_if_expr() {
+        assert_debug_snapshot!("if-expr", expr("if a { 1 } else { 2 }"));
+        assert_debug_snapshot!("if-expr-no-else", expr("if a { foo() }"));
+        assert_debug_snapshot!(
+            "if-expr-else-if",
+            expr("if a { 1 } else if b { 2 } else { 3 }")
+        );
     }
 
     #[test]
     fn test_lambda() {
         assert_debug_snapshot!("lambda", expr("fn () {}"));
         assert_debug_snapshot!("lambda-with-params", expr("fn (x) {}"));
+        assert_debug_snapshot!("lambda-with-types", expr("fn (x: number) -> string {}"));
     }
 }
@@ -1,4 +1,7 @@
-use crate::ast::{StmtKind, TypePath};
+use std::path::Path;
+
+use crate::ast::{EnumVariant, StmtKind, StructField, Type, TypeKind};
+use crate::lexer::StringSegment;
 
 use super::*;
 
@@ -6,8 +9,13 @@ impl<'a> Parser<'a> {
     /// Parses a declaration (or statement).
     pub fn parse_declaration(&mut self) -> Stmt {
         match self.current_token {
-            Token::Let => self.parse_let_declaration(),
+            Token::Let | Token::Const => self.parse_let_declaration(),
             Token::Fn => self.parse_fn_declaration(),
+            Token::Struct => self.parse_struct_declaration(),
+            Token::Class => self.parse_class_declaration(),
+            Token::Enum => self.parse_enum_declaration(),
+            Token::Import => self.parse_import_declaration(),
+            Token::Export => self.parse_export_declaration(),
             _ => self.parse_stmt(),
         }
     }
@@ -16,9 +24,14 @@ impl<'a> Parser<'a> {
     pub fn parse_stmt(&mut self) -> Stmt {
         match self.current_token {
             Token::Return => self.parse_return_stmt(),
+            Token::Break => self.parse_break_stmt(),
+            Token::Continue => self.parse_continue_stmt(),
+            Token::Try => self.parse_try_stmt(),
+            Token::Throw => self.parse_throw_stmt(),
             Token::OpenBrace => self.parse_block_stmt(),
             Token::If => self.parse_if_else_stmt(),
             Token::While => self.parse_while_stmt(),
+            Token::For => self.parse_for_stmt(),
             _ => {
                 // expression statement
                 let lo = self.node_start();
@@ -123,31 +136,197 @@ impl<'a> Parser<'a> {
         StmtKind::WhileStmt { condition, body }.with_span(lo..self.node_end())
     }
 
+    pub fn parse_for_stmt(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::For);
+
+        // initializer (let declaration eats the trailing semi)
+        let initializer = match self.current_token {
+            Token::Semi => {
+                self.next();
+                None
+            }
+            Token::Let => Some(Box::new(self.parse_let_declaration())),
+            _ => {
+                let expr_lo = self.node_start();
+                let expr = self.parse_expr();
+                self.expect(Token::Semi);
+                Some(Box::new(
+                    StmtKind::ExprStmt(expr).with_span(expr_lo..self.node_end()),
+                ))
+            }
+        };
+
+        let condition = if self.current_token == Token::Semi {
+            None
+        } else {
+            Some(self.parse_expr())
+        };
+        self.expect(Token::Semi);
+
+        let step = if self.current_token == Token::OpenBrace {
+            None
+        } else {
+            Some(self.parse_expr())
+        };
+
+        let mut body = Vec::new();
+        self.expect(Token::OpenBrace);
+        if !self.eat(Token::CloseBrace) {
+            loop {
+                body.push(self.parse_declaration());
+
+                if self.eat(Token::CloseBrace) {
+                    break;
+                } else if self.current_token == Token::Eof {
+                    self.unexpected();
+                    break;
+                }
+            }
+        }
+
+        StmtKind::ForStmt {
+            initializer,
+            condition,
+            step,
+            body,
+        }
+        .with_span(lo..self.node_end())
+    }
+
     /// Parses an optional type annotation. A type annotation is always preceded by a `:` colon token.
-    fn parse_optional_type_annotation(&mut self) -> Option<TypePath> {
+    fn parse_optional_type_annotation(&mut self) -> Option<Type> {
         if self.eat(Token::Colon) {
-            let lo = self.node_start();
-            let ident = if let Token::Identifier(ref ident) = self.current_token {
+            self.parse_type()
+        } else {
+            None
+        }
+    }
+
+    /// Parses a type (e.g. `number`, `List<number>`, `util.Point`, `(number, string)` or `fn(number) -> bool`).
+    /// Returns `None` if there is no type.
+    fn parse_type(&mut self) -> Option<Type> {
+        let lo = self.node_start();
+        let kind = match self.current_token {
+            Token::Identifier(ref ident) => {
                 let ident = ident.clone();
                 self.next();
-                ident
-            } else {
+                // types exported by a module are qualified with the module name (e.g. `util.Point`)
+                let (module, ident) = if self.eat(Token::Dot) {
+                    if let Token::Identifier(ref member) = self.current_token {
+                        let member = member.clone();
+                        self.next();
+                        (Some(ident), member)
+                    } else {
+                        self.next();
+                        self.unexpected();
+                        return None;
+                    }
+                } else {
+                    (None, ident)
+                };
+
+                let mut args = Vec::new();
+                if self.eat(Token::LessThan) {
+                    loop {
+                        args.push(self.parse_type()?);
+
+                        if self.eat_closing_angle() {
+                            break;
+                        } else if !self.eat(Token::Comma) {
+                            self.next();
+                            self.unexpected();
+                            return None;
+                        }
+                    }
+                }
+                TypeKind::Path {
+                    module,
+                    ident,
+                    args,
+                }
+            }
+            Token::Fn => {
+                self.next();
+
+                self.expect(Token::OpenParen);
+                let mut params = Vec::new();
+                let mut variadic = false;
+                if !self.eat(Token::CloseParen) {
+                    loop {
+                        variadic = self.eat(Token::Ellipsis);
+                        params.push(self.parse_type()?);
+
+                        if self.eat(Token::CloseParen) {
+                            break;
+                        } else if variadic {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "rest parameter must be the last parameter",
+                                params.last().unwrap().span.clone(),
+                            ));
+                        }
+                        if !self.eat(Token::Comma) {
+                            self.next();
+                            self.unexpected();
+                            return None;
+                        }
+                    }
+                }
+                let ret = self.parse_optional_return_type().map(Box::new);
+                TypeKind::Fn {
+                    params,
+                    variadic,
+                    ret,
+                }
+            }
+            Token::OpenParen => {
+                self.next();
+
+                let first = self.parse_type()?;
+                if !self.eat(Token::Comma) {
+                    // parenthesized type
+                    self.expect(Token::CloseParen);
+                    return Some(first);
+                }
+                let mut elems = vec![first];
+                // allow trailing comma
+                while !self.eat(Token::CloseParen) {
+                    elems.push(self.parse_type()?);
+
+                    if self.eat(Token::CloseParen) {
+                        break;
+                    } else if !self.eat(Token::Comma) {
+                        self.next();
+                        self.unexpected();
+                        return None;
+                    }
+                }
+                TypeKind::Tuple(elems)
+            }
+            _ => {
                 self.unexpected();
                 return None;
-            };
-            Some(TypePath {
-                ident,
-                span: lo..self.node_end(),
-            })
-        } else {
-            None
-        }
+            }
+        };
+        Some(Type {
+            kind,
+            span: lo..self.node_end(),
+        })
     }
 
+    /// Parses a `let` or `const` declaration.
     fn parse_let_declaration(&mut self) -> Stmt {
         let lo = self.node_start();
+        let doc = self.current_doc.take();
 
-        self.expect(Token::Let);
+        let is_const = self.eat(Token::Const);
+        if !is_const {
+            self.expect(Token::Let);
+        }
+        if self.current_token == Token::OpenParen {
+            return self.parse_tuple_declaration_rest(lo, is_const, doc);
+        }
         let ident = if let Token::Identifier(ref ident) = self.current_token {
             let ident = ident.clone();
             self.next();
@@ -166,12 +345,63 @@ impl<'a> Parser<'a> {
             ident,
             initializer,
             ty,
+            is_const,
+            doc,
+        }
+        .with_span(lo..self.node_end())
+    }
+
+    /// Parses the rest of a destructuring declaration (e.g. `(q, r) = divmod(7, 2);`) after the `let` or `const` keyword.
+    fn parse_tuple_declaration_rest(
+        &mut self,
+        lo: usize,
+        is_const: bool,
+        doc: Option<String>,
+    ) -> Stmt {
+        self.expect(Token::OpenParen);
+        let mut bindings = Vec::new();
+        // allow trailing comma
+        while !self.eat(Token::CloseParen) {
+            let binding_lo = self.node_start();
+            if let Token::Identifier(ref ident) = self.current_token {
+                let ident = ident.clone();
+                self.next();
+                let binding = StmtKind::Binding { ident }.with_span(binding_lo..self.node_end());
+                self.check_duplicate_binding(&bindings, &binding);
+                bindings.push(binding);
+            } else {
+                self.next();
+                self.unexpected();
+                return StmtKind::Error.with_span(lo..self.node_end());
+            }
+
+            if self.eat(Token::CloseParen) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.next();
+                self.unexpected();
+                return StmtKind::Error.with_span(lo..self.node_end());
+            }
+        }
+
+        let ty = self.parse_optional_type_annotation();
+
+        self.expect(Token::Equals);
+        let initializer = self.parse_expr();
+        self.expect(Token::Semi);
+        StmtKind::TupleDeclaration {
+            bindings,
+            initializer,
+            ty,
+            is_const,
+            doc,
         }
         .with_span(lo..self.node_end())
     }
 
     fn parse_fn_declaration(&mut self) -> Stmt {
         let lo = self.node_start();
+        let doc = self.current_doc.take();
 
         self.expect(Token::Fn);
         let ident = if let Token::Identifier(ref ident) = self.current_token {
@@ -183,56 +413,484 @@ impl<'a> Parser<'a> {
             self.unexpected();
             return StmtKind::Error.with_span(lo..self.node_end());
         };
+        let type_params = match self.parse_optional_type_params() {
+            Some(type_params) => type_params,
+            None => return StmtKind::Error.with_span(lo..self.node_end()),
+        };
+        let params = match self.parse_fn_params() {
+            Some(params) => params,
+            None => return StmtKind::Error.with_span(lo..self.node_end()),
+        };
+        let ret_ty = self.parse_optional_return_type();
+
+        self.expect(Token::OpenBrace);
+        let mut body = Vec::new();
+        if !self.eat(Token::CloseBrace) {
+            loop {
+                body.push(self.parse_declaration());
+
+                if self.eat(Token::CloseBrace) {
+                    break;
+                }
+            }
+        }
+
+        StmtKind::FnDeclaration {
+            body,
+            ident,
+            type_params,
+            params,
+            ret_ty,
+            doc,
+        }
+        .with_span(lo..self.node_end())
+    }
+
+    /// Parses the type parameters of a generic function (e.g. `<T, U>`).
+    /// Returns an empty `Vec` if there are no type parameters and `None` if there is a syntax error.
+    fn parse_optional_type_params(&mut self) -> Option<Vec<String>> {
+        let mut type_params = Vec::new();
+        if self.eat(Token::LessThan) {
+            loop {
+                if let Token::Identifier(ref ident) = self.current_token {
+                    let ident = ident.clone();
+                    let ident_lo = self.node_start();
+                    self.next();
+                    if type_params.contains(&ident) {
+                        self.source.errors.add_error(SyntaxError::new(
+                            format!("duplicate type parameter \"{}\"", ident),
+                            ident_lo..self.node_end(),
+                        ));
+                    }
+                    type_params.push(ident);
+                } else {
+                    self.next();
+                    self.unexpected();
+                    return None;
+                }
+
+                if self.eat_closing_angle() {
+                    break;
+                } else if !self.eat(Token::Comma) {
+                    self.next();
+                    self.unexpected();
+                    return None;
+                }
+            }
+        }
+        Some(type_params)
+    }
+
+    /// Parses a parenthesized list of function parameters with optional type annotations and default values
+    /// and an optional rest parameter (e.g. `(a: number, b, c = 1, ...d)`).
+    /// Returns `None` if a parameter is not an identifier.
+    pub(super) fn parse_fn_params(&mut self) -> Option<Vec<Stmt>> {
         self.expect(Token::OpenParen);
         let mut params = Vec::new();
         if !self.eat(Token::CloseParen) {
             loop {
+                let ident_lo = self.node_start();
+                let is_rest = self.eat(Token::Ellipsis);
                 params.push(if let Token::Identifier(ref ident) = self.current_token {
-                    let ident_lo = self.node_start();
                     let ident = ident.clone();
                     self.next();
-                    StmtKind::FnParam { ident }.with_span(ident_lo..self.node_end())
+                    let ty = self.parse_optional_type_annotation();
+                    let default = if self.eat(Token::Equals) {
+                        Some(self.parse_expr())
+                    } else {
+                        None
+                    };
+                    if let (true, Some(default)) = (is_rest, &default) {
+                        self.source.errors.add_error(SyntaxError::new(
+                            "rest parameter can not have a default value",
+                            default.span.clone(),
+                        ));
+                    } else if default.is_none()
+                        && !is_rest
+                        && params.iter().any(|param: &Stmt| {
+                            matches!(param.kind, StmtKind::FnParam { default: Some(_), .. })
+                        })
+                    {
+                        self.source.errors.add_error(
+                            SyntaxError::new(
+                                format!("parameter \"{}\" must have a default value", ident),
+                                ident_lo..self.node_end(),
+                            )
+                            .with_help(
+                                "parameters with a default value must come after the parameters without one",
+                            ),
+                        );
+                    }
+                    StmtKind::FnParam {
+                        ident,
+                        ty,
+                        default,
+                        is_rest,
+                    }
+                    .with_span(ident_lo..self.node_end())
                 } else {
                     self.unexpected();
-                    return StmtKind::Error.with_span(lo..self.node_end());
+                    return None;
                 });
+                let (param, others) = params.split_last().unwrap();
+                self.check_duplicate_binding(others, param);
 
                 if self.eat(Token::CloseParen) {
                     break;
-                } else if !self.eat(Token::Comma) {
+                } else if is_rest {
+                    self.source.errors.add_error(SyntaxError::new(
+                        "rest parameter must be the last parameter",
+                        params.last().unwrap().span.clone(),
+                    ));
+                }
+                if !self.eat(Token::Comma) {
                     self.unexpected();
                     break;
                 }
             }
         }
+        Some(params)
+    }
+
+    /// Reports an error if `binding` (a [`StmtKind::FnParam`] or [`StmtKind::Binding`]) declares the same variable
+    /// as one of `others`.
+    fn check_duplicate_binding(&mut self, others: &[Stmt], binding: &Stmt) {
+        fn ident(stmt: &Stmt) -> Option<&String> {
+            match &stmt.kind {
+                StmtKind::FnParam { ident, .. } | StmtKind::Binding { ident } => Some(ident),
+                _ => None,
+            }
+        }
+
+        if let Some(binding_ident) = ident(binding) {
+            if others
+                .iter()
+                .any(|other| ident(other) == Some(binding_ident))
+            {
+                self.source.errors.add_error(SyntaxError::new(
+                    format!("duplicate binding \"{}\"", binding_ident),
+                    binding.span.clone(),
+                ));
+            }
+        }
+    }
+
+    /// Parses an optional return type annotation. A return type annotation is always preceded by a `->` arrow token.
+    pub(super) fn parse_optional_return_type(&mut self) -> Option<Type> {
+        if self.current_token == Token::Arrow {
+            self.next();
+            self.parse_type()
+        } else {
+            None
+        }
+    }
+
+    fn parse_struct_declaration(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Struct);
+        let ident = if let Token::Identifier(ref ident) = self.current_token {
+            let ident = ident.clone();
+            self.next();
+            ident
+        } else {
+            self.next();
+            self.unexpected();
+            return StmtKind::Error.with_span(lo..self.node_end());
+        };
+
+        self.expect(Token::OpenBrace);
+        let fields = self.parse_field_list(Token::CloseBrace);
+
+        StmtKind::StructDeclaration { ident, fields }.with_span(lo..self.node_end())
+    }
+
+    /// Parses a comma separated list of fields with optional type annotations up to and including `close`.
+    /// A trailing comma is allowed.
+    fn parse_field_list(&mut self, close: Token) -> Vec<StructField> {
+        let mut fields = Vec::new();
+        while !self.eat(close.clone()) {
+            let field_lo = self.node_start();
+            let field_ident = if let Token::Identifier(ref ident) = self.current_token {
+                let ident = ident.clone();
+                self.next();
+                ident
+            } else {
+                self.next();
+                self.unexpected();
+                break;
+            };
+            let ty = self.parse_optional_type_annotation();
+            fields.push(StructField {
+                ident: field_ident,
+                ty,
+                span: field_lo..self.node_end(),
+            });
+
+            if self.eat(close.clone()) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.unexpected();
+                break;
+            }
+        }
+        fields
+    }
+
+    /// Parses an import declaration (e.g. `import util;` or `import "lib/util.ella";`).
+    fn parse_import_declaration(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Import);
+        let path = match &self.current_token {
+            Token::Identifier(ident) => format!("{}.ella", ident),
+            Token::StringLit(segments) => {
+                let mut path = String::new();
+                for segment in segments {
+                    match segment {
+                        StringSegment::Text(text, _) => path += text,
+                        StringSegment::Hole(_) => {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "module path can not be interpolated",
+                                self.current_span.clone(),
+                            ));
+                        }
+                    }
+                }
+                path
+            }
+            _ => {
+                self.next();
+                self.unexpected();
+                return StmtKind::Error.with_span(lo..self.node_end());
+            }
+        };
+        self.next();
+
+        // the module is accessed through its file name
+        let ident = Path::new(&path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let is_valid_ident = ident.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+            && ident
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        if !is_valid_ident {
+            self.source.errors.add_error(
+                SyntaxError::new(
+                    format!("module name \"{}\" is not a valid identifier", ident),
+                    lo..self.node_end(),
+                )
+                .with_help("the module is accessed through its file name without the extension"),
+            );
+        }
+        self.expect(Token::Semi);
+
+        StmtKind::ImportDeclaration { ident, path }.with_span(lo..self.node_end())
+    }
+
+    /// Parses an exported declaration (e.g. `export fn foo() {}`).
+    fn parse_export_declaration(&mut self) -> Stmt {
+        let lo = self.node_start();
+        // doc comments before `export` belong to the declaration
+        let doc = self.current_doc.take();
+
+        self.expect(Token::Export);
+        if self.current_doc.is_none() {
+            self.current_doc = doc;
+        }
+        match self.current_token {
+            Token::Let | Token::Const | Token::Fn | Token::Struct | Token::Class | Token::Enum => {
+                let declaration = self.parse_declaration();
+                StmtKind::Export(Box::new(declaration)).with_span(lo..self.node_end())
+            }
+            _ => {
+                self.source.errors.add_error(
+                    SyntaxError::new(
+                        "expected a declaration after \"export\"",
+                        self.current_span.clone(),
+                    )
+                    .with_help(
+                        "only let, const, fn, struct, class and enum declarations can be exported",
+                    ),
+                );
+                StmtKind::Error.with_span(lo..self.node_end())
+            }
+        }
+    }
+
+    fn parse_enum_declaration(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Enum);
+        let ident = if let Token::Identifier(ref ident) = self.current_token {
+            let ident = ident.clone();
+            self.next();
+            ident
+        } else {
+            self.next();
+            self.unexpected();
+            return StmtKind::Error.with_span(lo..self.node_end());
+        };
+
+        self.expect(Token::OpenBrace);
+        let mut variants = Vec::new();
+        // allow trailing comma
+        while !self.eat(Token::CloseBrace) {
+            let variant_lo = self.node_start();
+            let variant_ident = if let Token::Identifier(ref ident) = self.current_token {
+                let ident = ident.clone();
+                self.next();
+                ident
+            } else {
+                self.next();
+                self.unexpected();
+                return StmtKind::Error.with_span(lo..self.node_end());
+            };
+            let fields = if self.eat(Token::OpenParen) {
+                self.parse_field_list(Token::CloseParen)
+            } else {
+                Vec::new()
+            };
+            variants.push(EnumVariant {
+                ident: variant_ident,
+                fields,
+                span: variant_lo..self.node_end(),
+            });
+
+            if self.eat(Token::CloseBrace) {
+                break;
+            } else if !self.eat(Token::Comma) {
+                self.unexpected();
+                break;
+            }
+        }
+
+        StmtKind::EnumDeclaration { ident, variants }.with_span(lo..self.node_end())
+    }
+
+    fn parse_class_declaration(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Class);
+        let ident = if let Token::Identifier(ref ident) = self.current_token {
+            let ident = ident.clone();
+            self.next();
+            ident
+        } else {
+            self.next();
+            self.unexpected();
+            return StmtKind::Error.with_span(lo..self.node_end());
+        };
 
         self.expect(Token::OpenBrace);
+        let mut methods = Vec::new();
+        while !self.eat(Token::CloseBrace) {
+            if self.current_token == Token::Fn {
+                methods.push(self.parse_fn_declaration());
+            } else {
+                self.next();
+                self.unexpected();
+                return StmtKind::Error.with_span(lo..self.node_end());
+            }
+        }
+
+        StmtKind::ClassDeclaration { ident, methods }.with_span(lo..self.node_end())
+    }
+
+    fn parse_return_stmt(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Return);
+        let expr = if self.eat(Token::Semi) {
+            None
+        } else {
+            let expr = self.parse_expr();
+            self.expect(Token::Semi);
+            Some(expr)
+        };
+        StmtKind::ReturnStmt(expr).with_span(lo..self.node_end())
+    }
+
+    fn parse_break_stmt(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Break);
+        self.expect(Token::Semi);
+        StmtKind::BreakStmt.with_span(lo..self.node_end())
+    }
+
+    fn parse_continue_stmt(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Continue);
+        self.expect(Token::Semi);
+        StmtKind::ContinueStmt.with_span(lo..self.node_end())
+    }
+
+    /// Parses a try statement (e.g. `try { ... } catch e { ... }`).
+    fn parse_try_stmt(&mut self) -> Stmt {
+        let lo = self.node_start();
+
+        self.expect(Token::Try);
+        let try_block = self.parse_stmt_list();
+
+        self.expect(Token::Catch);
+        let binding_lo = self.node_start();
+        let binding = if let Token::Identifier(ref ident) = self.current_token {
+            let ident = ident.clone();
+            self.next();
+            StmtKind::Binding { ident }.with_span(binding_lo..self.node_end())
+        } else {
+            self.source.errors.add_error(
+                SyntaxError::new(
+                    "expected an identifier after \"catch\"",
+                    self.current_span.clone(),
+                )
+                .with_help("the thrown value is bound to a variable (e.g. \"catch e { ... }\")"),
+            );
+            self.next();
+            return StmtKind::Error.with_span(lo..self.node_end());
+        };
+        let catch_block = self.parse_stmt_list();
+
+        StmtKind::TryStmt {
+            try_block,
+            binding: Box::new(binding),
+            catch_block,
+        }
+        .with_span(lo..self.node_end())
+    }
+
+    /// Parses a list of declarations surrounded by braces.
+    fn parse_stmt_list(&mut self) -> Vec<Stmt> {
         let mut body = Vec::new();
+
+        self.expect(Token::OpenBrace);
         if !self.eat(Token::CloseBrace) {
             loop {
                 body.push(self.parse_declaration());
 
                 if self.eat(Token::CloseBrace) {
                     break;
+                } else if self.current_token == Token::Eof {
+                    self.unexpected();
+                    break;
                 }
             }
         }
-
-        StmtKind::FnDeclaration {
-            body,
-            ident,
-            params,
-        }
-        .with_span(lo..self.node_end())
+        body
     }
 
-    fn parse_return_stmt(&mut self) -> Stmt {
+    fn parse_throw_stmt(&mut self) -> Stmt {
         let lo = self.node_start();
 
-        self.expect(Token::Return);
+        self.expect(Token::Throw);
         let expr = self.parse_expr();
         self.expect(Token::Semi);
-        StmtKind::ReturnStmt(expr).with_span(lo..self.node_end())
+        StmtKind::ThrowStmt(expr).with_span(lo..self.node_end())
     }
 }
 
@@ -286,6 +944,15 @@ mod tests {
         assert_debug_snapshot!("while-stmt-empty", stmt(r#"while true {}"#));
     }
 
+    #[test]
+    fn test_for_stmt() {
+        assert_debug_snapshot!(
+            "for-stmt",
+            stmt(r#"for let i = 0; i < 10; i += 1 { for_block(); }"#)
+        );
+        assert_debug_snapshot!("for-stmt-empty", stmt(r#"for ;; {}"#));
+    }
+
     #[test]
     fn test_let_declaration() {
         assert_debug_snapshot!("let-declaration", stmt("let x = 2;"));
@@ -294,6 +961,41 @@ mod tests {
             "let-declaration-with-type-annotation",
             stmt("let x: u32 = 2;")
         );
+        assert_debug_snapshot!("const-declaration", stmt("const x = 2;"));
+    }
+
+    #[test]
+    fn test_tuple_declaration() {
+        assert_debug_snapshot!("tuple-declaration", stmt("let (q, r) = divmod(7, 2);"));
+        assert_debug_snapshot!(
+            "tuple-declaration-with-type-annotation",
+            stmt("const (a, b,): (number, string) = t;")
+        );
+    }
+
+    #[test]
+    fn test_type_annotation() {
+        assert_debug_snapshot!(
+            "type-annotation-generic",
+            stmt("let x: Map<string, List<number>> = {};")
+        );
+        assert_debug_snapshot!(
+            "type-annotation-fn",
+            stmt("let f: fn(number, string) -> bool = g;")
+        );
+        assert_debug_snapshot!(
+            "type-annotation-fn-no-return-type",
+            stmt("let f: fn() = g;")
+        );
+        assert_debug_snapshot!(
+            "type-annotation-fn-rest-param",
+            stmt("let f: fn(string, ...List<number>) -> number = g;")
+        );
+        assert_debug_snapshot!(
+            "type-annotation-tuple",
+            stmt("let t: (number, (bool,)) = t;")
+        );
+        assert_debug_snapshot!("type-annotation-module", stmt("let p: util.Point = p;"));
     }
 
     #[test]
@@ -304,11 +1006,148 @@ mod tests {
             "fn-declaration-with-params-and-body",
             stmt("fn foo(a, b, c) { a + b + c; }")
         );
+        assert_debug_snapshot!(
+            "fn-declaration-with-types",
+            stmt("fn add(a: number, b) -> number {}")
+        );
+        assert_debug_snapshot!(
+            "fn-declaration-generic",
+            stmt("fn map<T, U>(xs: List<T>, f: fn(T) -> U) -> List<U> {}")
+        );
+        assert_debug_snapshot!(
+            "fn-declaration-with-defaults",
+            stmt("fn connect(host: string, port = 8080) {}")
+        );
+        assert_debug_snapshot!(
+            "fn-declaration-with-rest-param",
+            stmt("fn sum(first, ...rest: List<number>) {}")
+        );
+    }
+
+    #[test]
+    fn test_fn_declaration_required_param_after_default() {
+        let source = "fn foo(a = 1, b) {}".into();
+        Parser::new(&source).parse_declaration();
+        assert!(!source.has_no_errors());
+    }
+
+    #[test]
+    fn test_fn_declaration_rest_param_not_last() {
+        let source = "fn foo(...a, b) {}".into();
+        Parser::new(&source).parse_declaration();
+        assert!(!source.has_no_errors());
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        assert_debug_snapshot!(
+            "fn-declaration-doc-comment",
+            stmt("/// Adds two numbers.\n///\n/// Returns the sum.\nfn add(a, b) {}")
+        );
+        assert_debug_snapshot!(
+            "let-declaration-doc-comment",
+            stmt("/// The answer.\n//// Not a doc comment.\nconst x = 42;")
+        );
+        // doc comments that do not precede a declaration are ignored
+        assert_debug_snapshot!("expr-stmt-doc-comment", stmt("/// Not attached.\nfoo();"));
+    }
+
+    #[test]
+    fn test_block_comment() {
+        assert_debug_snapshot!(
+            "block-comment-nested",
+            stmt("fn /* name */ foo(/* outer /* inner */ still outer */ a) {}")
+        );
+
+        let source = "let x = 1; /* unterminated /* nested */".into();
+        Parser::new(&source).parse_declaration();
+        assert!(!source.has_no_errors());
+    }
+
+    #[test]
+    fn test_import_declaration() {
+        assert_debug_snapshot!("import-declaration", stmt("import util;"));
+        assert_debug_snapshot!(
+            "import-declaration-path",
+            stmt(r#"import "lib/util.ella";"#)
+        );
+    }
+
+    #[test]
+    fn test_export_declaration() {
+        assert_debug_snapshot!(
+            "export-declaration",
+            stmt("/// Adds two numbers.\nexport fn add(a, b) {}")
+        );
+        assert_debug_snapshot!("export-declaration-const", stmt("export const x = 1;"));
+    }
+
+    #[test]
+    fn test_struct_declaration() {
+        assert_debug_snapshot!("struct-declaration", stmt("struct Point { x, y }"));
+        assert_debug_snapshot!(
+            "struct-declaration-with-types",
+            stmt("struct Point { x: number, y: number, }")
+        );
+        assert_debug_snapshot!("struct-declaration-empty", stmt("struct Empty {}"));
+    }
+
+    #[test]
+    fn test_class_declaration() {
+        assert_debug_snapshot!(
+            "class-declaration",
+            stmt("class Counter { fn init(self) { self.count = 0; } fn inc(self) { self.count += 1; } }")
+        );
+        assert_debug_snapshot!("class-declaration-empty", stmt("class Empty {}"));
+    }
+
+    #[test]
+    fn test_enum_declaration() {
+        assert_debug_snapshot!(
+            "enum-declaration",
+            stmt("enum Shape { Circle(r: number), Rect(w, h,), Empty, }")
+        );
+        assert_debug_snapshot!("enum-declaration-empty", stmt("enum Never {}"));
     }
 
     #[test]
     fn test_return_stmt() {
         assert_debug_snapshot!("return-stmt", stmt("return 1;"));
         assert_debug_snapshot!("return-stmt-with-expr", stmt("return 1 + 2;"));
+        assert_debug_snapshot!("return-stmt-empty", stmt("return;"));
+    }
+
+    #[test]
+    fn test_break_continue_stmt() {
+        assert_debug_snapshot!("break-stmt", stmt("break;"));
+        assert_debug_snapshot!("continue-stmt", stmt("continue;"));
+    }
+
+    #[test]
+    fn test_try_stmt() {
+        assert_debug_snapshot!(
+            "try-stmt",
+            stmt(
+                r#"
+                try {
+                    try_block();
+                } catch e {
+                    catch_block(e);
+                }"#
+            )
+        );
+        assert_debug_snapshot!("try-stmt-empty", stmt("try {} catch e {}"));
+    }
+
+    #[test]
+    fn test_throw_stmt() {
+        assert_debug_snapshot!("throw-stmt", stmt(r#"throw "error";"#));
+    }
+
+    #[test]
+    fn test_try_stmt_without_binding() {
+        let source = "try {} catch {}".into();
+        Parser::new(&source).parse_declaration();
+        assert!(!source.has_no_errors());
     }
 }
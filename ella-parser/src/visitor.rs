@@ -1,6 +1,6 @@
 //! Visitor pattern for AST nodes.
 
-use crate::ast::{Expr, ExprKind, Stmt, StmtKind};
+use crate::ast::{Expr, ExprKind, PatternKind, Stmt, StmtKind};
 
 /// Visitor trait for AST walking logic.
 /// Implement this trait by overriding the hooks (`visit_*` methods).
@@ -24,13 +24,41 @@ pub fn walk_expr<'ast>(visitor: &mut impl Visitor<'ast>, expr: &'ast Expr) {
         ExprKind::NumberLit(_) => {}
         ExprKind::BoolLit(_) => {}
         ExprKind::StringLit(_) => {}
+        ExprKind::Interpolation(segments) => {
+            for segment in segments {
+                visitor.visit_expr(segment);
+            }
+        }
         ExprKind::Identifier(_) => {}
-        ExprKind::FnCall { callee, args } => {
+        ExprKind::FnCall {
+            callee,
+            args,
+            named_args,
+        } => {
             visitor.visit_expr(callee);
             for arg in args {
                 visitor.visit_expr(arg);
             }
+            for named_arg in named_args {
+                visitor.visit_expr(&named_arg.value);
+            }
+        }
+        ExprKind::ListLit(elements) | ExprKind::TupleLit(elements) => {
+            for element in elements {
+                visitor.visit_expr(element);
+            }
         }
+        ExprKind::MapLit(entries) => {
+            for (key, value) in entries {
+                visitor.visit_expr(key);
+                visitor.visit_expr(value);
+            }
+        }
+        ExprKind::Index { target, index } => {
+            visitor.visit_expr(target);
+            visitor.visit_expr(index);
+        }
+        ExprKind::FieldAccess { target, ident: _ } => visitor.visit_expr(target),
         ExprKind::Binary { lhs, op: _, rhs } => {
             visitor.visit_expr(lhs);
             visitor.visit_expr(rhs);
@@ -39,6 +67,7 @@ pub fn walk_expr<'ast>(visitor: &mut impl Visitor<'ast>, expr: &'ast Expr) {
         ExprKind::Lambda {
             inner_stmt: _,
             params,
+            ret_ty: _,
             body,
         } => {
             for param in params {
@@ -48,6 +77,46 @@ pub fn walk_expr<'ast>(visitor: &mut impl Visitor<'ast>, expr: &'ast Expr) {
                 visitor.visit_stmt(stmt);
             }
         }
+        ExprKind::Block { stmts, expr } => {
+            for stmt in stmts {
+                visitor.visit_stmt(stmt);
+            }
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        ExprKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            visitor.visit_expr(condition);
+            visitor.visit_expr(then_branch);
+            if let Some(else_branch) = else_branch {
+                visitor.visit_expr(else_branch);
+            }
+        }
+        ExprKind::Match {
+            scrutinee,
+            inner_stmt: _,
+            arms,
+        } => {
+            visitor.visit_expr(scrutinee);
+            for arm in arms {
+                if let PatternKind::Variant {
+                    enum_ident,
+                    variant: _,
+                    bindings,
+                } = &arm.pattern.kind
+                {
+                    visitor.visit_expr(enum_ident);
+                    for binding in bindings {
+                        visitor.visit_stmt(binding);
+                    }
+                }
+                visitor.visit_expr(&arm.body);
+            }
+        }
         ExprKind::Error => {}
     }
 }
@@ -68,16 +137,46 @@ pub fn walk_stmt<'ast>(visitor: &mut impl Visitor<'ast>, stmt: &'ast Stmt) {
             ident: _,
             initializer,
             ty: _,
+            is_const: _,
+            doc: _,
         } => visitor.visit_expr(initializer),
-        StmtKind::FnParam { ident: _ } => {}
+        StmtKind::TupleDeclaration {
+            bindings,
+            initializer,
+            ty: _,
+            is_const: _,
+            doc: _,
+        } => {
+            visitor.visit_expr(initializer);
+            visit_stmt_list!(visitor, bindings);
+        }
+        StmtKind::FnParam {
+            ident: _,
+            ty: _,
+            default,
+            is_rest: _,
+        } => {
+            if let Some(default) = default {
+                visitor.visit_expr(default);
+            }
+        }
+        StmtKind::Binding { ident: _ } => {}
         StmtKind::FnDeclaration {
             ident: _,
+            type_params: _,
             params,
+            ret_ty: _,
             body,
+            doc: _,
         } => {
             visit_stmt_list!(visitor, params);
             visit_stmt_list!(visitor, body);
-        },
+        }
+        StmtKind::StructDeclaration { .. } => {}
+        StmtKind::ClassDeclaration { ident: _, methods } => visit_stmt_list!(visitor, methods),
+        StmtKind::EnumDeclaration { .. } => {}
+        StmtKind::ImportDeclaration { .. } => {}
+        StmtKind::Export(declaration) => visitor.visit_stmt(declaration),
         StmtKind::Block(body) => visit_stmt_list!(visitor, body),
         StmtKind::IfElseStmt {
             condition,
@@ -94,8 +193,40 @@ pub fn walk_stmt<'ast>(visitor: &mut impl Visitor<'ast>, stmt: &'ast Stmt) {
             visitor.visit_expr(condition);
             visit_stmt_list!(visitor, body);
         }
+        StmtKind::ForStmt {
+            initializer,
+            condition,
+            step,
+            body,
+        } => {
+            if let Some(initializer) = initializer {
+                visitor.visit_stmt(initializer);
+            }
+            if let Some(condition) = condition {
+                visitor.visit_expr(condition);
+            }
+            if let Some(step) = step {
+                visitor.visit_expr(step);
+            }
+            visit_stmt_list!(visitor, body);
+        }
         StmtKind::ExprStmt(expr) => visitor.visit_expr(expr),
-        StmtKind::ReturnStmt(expr) => visitor.visit_expr(expr),
+        StmtKind::ReturnStmt(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        StmtKind::BreakStmt | StmtKind::ContinueStmt => {}
+        StmtKind::TryStmt {
+            try_block,
+            binding,
+            catch_block,
+        } => {
+            visit_stmt_list!(visitor, try_block);
+            visitor.visit_stmt(binding);
+            visit_stmt_list!(visitor, catch_block);
+        }
+        StmtKind::ThrowStmt(expr) => visitor.visit_expr(expr),
         StmtKind::Lambda => unreachable!(),
         StmtKind::Error => {}
     }
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::ops::Range;
 use std::rc::Rc;
 
-use ella_parser::ast::{Expr, ExprKind, Stmt, StmtKind};
+use ella_parser::ast::{Expr, ExprKind, NamedArg, PatternKind, Stmt, StmtKind, Type, TypeKind};
 use ella_parser::lexer::Token;
 use ella_parser::visitor::{walk_expr, Visitor};
 use ella_source::{Source, SyntaxError};
@@ -17,7 +17,12 @@ use ella_value::BuiltinVars;
 pub struct ResolveResult {
     symbol_table: SymbolTable,
     resolved_symbol_table: ResolvedSymbolTable,
-    accessible_symbols: Vec<Rc<RefCell<Symbol>>>
+    accessible_symbols: Vec<Rc<RefCell<Symbol>>>,
+    hoisted_symbols: HoistedSymbolTable,
+    resolved_bindings: ResolvedBindingTable,
+    enum_variants: EnumVariantTable,
+    fn_params: FnParamTable,
+    resolved_types: ResolvedTypeTable,
 }
 
 impl ResolveResult {
@@ -31,15 +36,168 @@ impl ResolveResult {
         self.resolved_symbol_table.get(&(expr as *const Expr))
     }
 
+    /// Lookup the symbols that are hoisted to a [`Stmt`] (by reference).
+    /// A stack slot should be allocated for each of these symbols before the statement is executed.
+    pub fn lookup_hoisted_symbols(&self, stmt: &Stmt) -> &[Rc<RefCell<Symbol>>] {
+        self.hoisted_symbols
+            .get(&(stmt as *const Stmt))
+            .map_or(&[], |symbols| symbols.as_slice())
+    }
+
+    /// Lookup a hoisted declaration (e.g. [`StmtKind::Binding`]) to get the location of the variable.
+    pub fn lookup_binding(&self, stmt: &Stmt) -> Option<&ResolvedSymbol> {
+        self.resolved_bindings.get(&(stmt as *const Stmt))
+    }
+
+    /// Lookup the tag (index in declaration order) of `variant` in the enum that `enum_ident` refers to.
+    pub fn lookup_variant_tag(&self, enum_ident: &Expr, variant: &str) -> Option<usize> {
+        let resolved_symbol = self.lookup_identifier(enum_ident)?;
+        let variants = self
+            .enum_variants
+            .get(&resolved_symbol.symbol.borrow().stmt)?;
+        variants.iter().position(|(ident, _)| ident == variant)
+    }
+
+    /// Lookup the parameters of the function declaration that `callee` refers to (e.g. `foo` or `util.foo`).
+    /// Returns `None` if the callee is not statically known to be a function declaration.
+    pub fn lookup_fn_params(&self, callee: &Expr) -> Option<&FnParams> {
+        let resolved_symbol = match &callee.kind {
+            ExprKind::Identifier(_) => self.lookup_identifier(callee)?.clone(),
+            ExprKind::FieldAccess { target, ident } => self.lookup_module_member(target, ident)?,
+            _ => return None,
+        };
+        let stmt = resolved_symbol.symbol.borrow().stmt;
+        self.fn_params.get(&stmt)
+    }
+
+    /// Lookup the declaration (e.g. a [`StmtKind::StructDeclaration`]) that a named type in a type annotation refers to.
+    /// Returns `None` if the name is not declared in scope (e.g. builtin types and type parameters).
+    pub fn lookup_type(&self, ty: &Type) -> Option<*const Stmt> {
+        self.resolved_types.get(&(ty as *const Type)).copied()
+    }
+
+    /// Lookup the members exported by the module that `target` refers to.
+    /// Returns `None` if `target` is not an identifier that refers to an imported module.
+    pub fn lookup_module(&self, target: &Expr) -> Option<Rc<ModuleExports>> {
+        if !matches!(target.kind, ExprKind::Identifier(_)) {
+            return None;
+        }
+        self.lookup_identifier(target)?
+            .symbol
+            .borrow()
+            .module
+            .clone()
+    }
+
+    /// Lookup the member `ident` of the module that `target` refers to (e.g. `util.foo`).
+    /// Returns `None` if `target` is not an imported module or if the module does not export `ident`.
+    pub fn lookup_module_member(&self, target: &Expr, ident: &str) -> Option<ResolvedSymbol> {
+        self.lookup_module(target)?.get(ident).cloned()
+    }
+
     /// Lookup an identifier in the current `accessible_symbols` list.
     pub fn lookup_in_accessible_symbols(&self, ident: &str) -> Option<&Rc<RefCell<Symbol>>> {
-        for symbol in self.accessible_symbols.iter().rev() {
-            if symbol.borrow().ident == ident {
-                return Some(symbol);
+        self.accessible_symbols
+            .iter()
+            .rev()
+            .find(|&symbol| symbol.borrow().is_accessible_as(ident))
+            .map(|v| v as _)
+    }
+}
+
+/// An argument of a function call after matching the arguments to the parameters of the function.
+/// See [`match_call_args`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallArg<'a> {
+    /// The argument is given at the call site.
+    Given(&'a Expr),
+    /// The argument is omitted and the default value of the parameter is used.
+    Default(&'a ExprKind),
+    /// The extra positional arguments that are collected into the rest parameter.
+    Rest(&'a [Expr]),
+}
+
+/// Matches the positional and named arguments of a function call to the parameters of the function.
+/// Returns the arguments in parameter order.
+/// If the function has a rest parameter, the last argument is always a [`CallArg::Rest`].
+/// `span` is the location of the function call and is used for error reporting.
+pub fn match_call_args<'a>(
+    fn_params: &'a FnParams,
+    args: &'a [Expr],
+    named_args: &'a [NamedArg],
+    span: Range<usize>,
+) -> Result<Vec<CallArg<'a>>, SyntaxError> {
+    let params = &fn_params.params;
+    if fn_params.rest.is_none() && args.len() > params.len() {
+        return Err(SyntaxError::new(
+            format!(
+                "expected {} argument(s), found {}",
+                params.len(),
+                args.len() + named_args.len()
+            ),
+            span,
+        ));
+    }
+    let (args, rest_args) = args.split_at(args.len().min(params.len()));
+
+    let mut matched: Vec<Option<&Expr>> = args.iter().map(Some).collect();
+    matched.resize(params.len(), None);
+    for named_arg in named_args {
+        if fn_params.rest.as_ref() == Some(&named_arg.ident) {
+            return Err(SyntaxError::new(
+                format!(
+                    "rest parameter \"{}\" can not be passed by name",
+                    named_arg.ident
+                ),
+                named_arg.span.clone(),
+            ));
+        }
+        match params
+            .iter()
+            .position(|(ident, _)| *ident == named_arg.ident)
+        {
+            Some(i) if matched[i].is_some() => {
+                return Err(SyntaxError::new(
+                    format!(
+                        "argument for parameter \"{}\" is given more than once",
+                        named_arg.ident
+                    ),
+                    named_arg.span.clone(),
+                ))
+            }
+            Some(i) => matched[i] = Some(&named_arg.value),
+            None => {
+                return Err(SyntaxError::new(
+                    format!("no parameter named \"{}\"", named_arg.ident),
+                    named_arg.span.clone(),
+                ))
             }
         }
-        None
     }
+
+    let mut call_args = matched
+        .into_iter()
+        .zip(params)
+        .map(|(arg, (ident, default))| match (arg, default) {
+            (Some(arg), _) => Ok(CallArg::Given(arg)),
+            (None, Some(default)) => Ok(CallArg::Default(default)),
+            (None, None) => Err(SyntaxError::new(
+                format!("missing argument for parameter \"{}\"", ident),
+                span.clone(),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if fn_params.rest.is_some() {
+        call_args.push(CallArg::Rest(rest_args));
+    }
+    Ok(call_args)
+}
+
+/// Returns `true` if the symbols declared at `scope_depth` are global variables.
+/// Only the declarations at the top level of the program are globals. Declarations inside blocks (e.g. the body of a loop)
+/// of the top-level function are local variables that can be captured by closures like in any other function.
+fn is_global_scope(scope_depth: u32) -> bool {
+    scope_depth == 0
 }
 
 /// Represents a symbol (created using `let`, `fn` declaration statement or lambda expression).
@@ -47,9 +205,25 @@ impl ResolveResult {
 pub struct Symbol {
     ident: String,
     scope_depth: u32,
+    /// `true` if the symbol is out of scope but still occupies a stack slot (e.g. bindings of a match arm).
+    /// Hidden symbols can not be referenced by name.
+    is_hidden: bool,
     pub is_captured: bool,
+    /// `false` if the symbol is a constant (declared with `const`).
+    pub is_mutable: bool,
+    /// The initializer of a constant if it is a literal. Loads of the constant are replaced with the literal.
+    pub const_literal: Option<ExprKind>,
     pub upvalues: Vec<ResolvedUpValue>,
     pub stmt: *const Stmt,
+    /// The members exported by the module if the symbol is created by an import declaration.
+    pub module: Option<Rc<ModuleExports>>,
+}
+
+impl Symbol {
+    /// Returns `true` if `ident` refers to this symbol when the symbol is in `accessible_symbols`.
+    fn is_accessible_as(&self, ident: &str) -> bool {
+        !self.is_hidden && self.ident == ident
+    }
 }
 
 /// Represents a resolved upvalue (captured variable).
@@ -74,6 +248,37 @@ pub struct ResolvedSymbol {
 pub type SymbolTable = HashMap<*const Stmt, Rc<RefCell<Symbol>>>;
 /// A [`HashMap`] mapping [`Expr`] to [`ResolvedSymbol`]s.
 pub type ResolvedSymbolTable = HashMap<*const Expr, ResolvedSymbol>;
+/// A [`HashMap`] mapping [`Stmt`]s to the [`Symbol`]s that are hoisted to them.
+pub type HoistedSymbolTable = HashMap<*const Stmt, Vec<Rc<RefCell<Symbol>>>>;
+/// A [`HashMap`] mapping hoisted declarations to [`ResolvedSymbol`]s.
+pub type ResolvedBindingTable = HashMap<*const Stmt, ResolvedSymbol>;
+/// A [`HashMap`] mapping enum declarations to their variants (identifier and number of fields) in declaration order.
+pub type EnumVariantTable = HashMap<*const Stmt, Vec<(String, usize)>>;
+/// A [`HashMap`] mapping function declarations to their parameters.
+pub type FnParamTable = HashMap<*const Stmt, FnParams>;
+/// A [`HashMap`] mapping named types in type annotations to the declarations they refer to.
+pub type ResolvedTypeTable = HashMap<*const Type, *const Stmt>;
+/// A [`HashMap`] mapping the identifiers of the members exported by a module to [`ResolvedSymbol`]s.
+/// See [`Resolver::resolve_module`].
+pub type ModuleExports = HashMap<String, ResolvedSymbol>;
+
+/// The parameters of a function declaration. See [`ResolveResult::lookup_fn_params`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnParams {
+    /// The identifier and default value of every parameter except the rest parameter, in declaration order.
+    pub params: Vec<(String, Option<ExprKind>)>,
+    /// The identifier of the rest parameter if the function has one.
+    pub rest: Option<String>,
+}
+
+/// Bookkeeping for a block expression that is currently being resolved.
+struct BlockExprContext {
+    /// The index in `hoist_stack` of the statement that declarations inside the block expression are hoisted to.
+    hoist_target: usize,
+    /// The loop depth when entering the block expression.
+    /// `break` and `continue` statements can not jump out of the block expression.
+    loop_depth: u32,
+}
 
 /// Variable resolution pass.
 pub struct Resolver<'a> {
@@ -85,11 +290,33 @@ pub struct Resolver<'a> {
     accessible_symbols: Vec<Rc<RefCell<Symbol>>>,
     /// A stack of current function scope depths. `0` is global scope.
     function_scope_depths: Vec<u32>,
-    /// Every time a new function scope is created, `current_func_offset` should be set to `self.resolved_symbols.len()`.
-    /// When exiting a function scope, the value should be reverted to previous value.
-    current_func_offset: i32,
+    /// A stack of function offsets. Every time a new function scope is created, `self.accessible_symbols.len()` should be pushed.
+    /// When exiting a function scope, the value should be popped.
+    /// The last value is the current function's offset (see [`Self::current_func_offset`]).
+    function_offsets: Vec<i32>,
     /// A stack of current function upvalues.
     function_upvalues: Vec<Vec<ResolvedUpValue>>,
+    /// A stack of current function loop depths. `0` means not inside a loop.
+    /// Used to reject `break` and `continue` statements outside of loops.
+    function_loop_depths: Vec<u32>,
+    /// A stack of symbols hoisted to the statements that are currently being resolved.
+    /// Every time a statement is visited, a new value is pushed onto the stack.
+    hoist_stack: Vec<Vec<Rc<RefCell<Symbol>>>>,
+    /// A [`HashMap`] mapping statements to the symbols hoisted to them (see [`Self::add_hoisted_symbol`]).
+    hoisted_symbols: HoistedSymbolTable,
+    /// A [`HashMap`] mapping all hoisted declarations to [`ResolvedSymbol`]s.
+    resolved_bindings: ResolvedBindingTable,
+    /// A [`HashMap`] mapping all enum declarations to their variants.
+    enum_variants: EnumVariantTable,
+    /// A [`HashMap`] mapping all function declarations to their parameters.
+    fn_params: FnParamTable,
+    /// A [`HashMap`] mapping all named types in type annotations to their declarations.
+    resolved_types: ResolvedTypeTable,
+    /// A stack of block expressions in the current function that are currently being resolved.
+    /// Declarations inside block expressions are hoisted to the statement containing the outermost block expression.
+    block_exprs: Vec<BlockExprContext>,
+    /// A [`HashMap`] mapping the paths of the modules that can be imported to their exported members.
+    modules: HashMap<String, Rc<ModuleExports>>,
     source: Source<'a>,
 }
 
@@ -101,8 +328,17 @@ impl<'a> Resolver<'a> {
             resolved_symbol_table: ResolvedSymbolTable::new(),
             accessible_symbols: Vec::new(),
             function_scope_depths: vec![0],
-            current_func_offset: 0,
+            function_offsets: vec![0],
             function_upvalues: vec![Vec::new()],
+            function_loop_depths: vec![0],
+            hoist_stack: Vec::new(),
+            hoisted_symbols: HoistedSymbolTable::new(),
+            resolved_bindings: ResolvedBindingTable::new(),
+            enum_variants: EnumVariantTable::new(),
+            fn_params: FnParamTable::new(),
+            resolved_types: ResolvedTypeTable::new(),
+            block_exprs: Vec::new(),
+            modules: HashMap::new(),
             source,
         }
     }
@@ -118,6 +354,11 @@ impl<'a> Resolver<'a> {
             symbol_table: resolve_result.symbol_table,
             resolved_symbol_table: resolve_result.resolved_symbol_table,
             accessible_symbols: resolve_result.accessible_symbols,
+            hoisted_symbols: resolve_result.hoisted_symbols,
+            resolved_bindings: resolve_result.resolved_bindings,
+            enum_variants: resolve_result.enum_variants,
+            fn_params: resolve_result.fn_params,
+            resolved_types: resolve_result.resolved_types,
             ..Self::new(source)
         }
     }
@@ -129,6 +370,11 @@ impl<'a> Resolver<'a> {
             symbol_table: self.symbol_table,
             resolved_symbol_table: self.resolved_symbol_table,
             accessible_symbols: self.accessible_symbols,
+            hoisted_symbols: self.hoisted_symbols,
+            resolved_bindings: self.resolved_bindings,
+            enum_variants: self.enum_variants,
+            fn_params: self.fn_params,
+            resolved_types: self.resolved_types,
         }
     }
 
@@ -138,32 +384,60 @@ impl<'a> Resolver<'a> {
     }
 
     /// Exit a scope. Removes all declarations introduced in previous scope.
+    /// Inside a block expression, declarations are hoisted out of the scope and are only hidden instead.
     fn exit_scope(&mut self) {
         *self.function_scope_depths.last_mut().unwrap() -= 1;
+        let scope_depth = *self.function_scope_depths.last().unwrap();
+        let in_block_expr = !self.block_exprs.is_empty();
 
         // Remove all symbols in current scope.
-        self.accessible_symbols = self
-            .accessible_symbols
-            .iter()
-            .filter(|symbol| {
-                symbol.borrow().scope_depth <= *self.function_scope_depths.last().unwrap()
-            })
-            .cloned()
-            .collect();
+        self.accessible_symbols.retain(|symbol| {
+            let mut symbol = symbol.borrow_mut();
+            if symbol.scope_depth <= scope_depth {
+                true
+            } else if in_block_expr {
+                // The symbol still occupies a hoisted stack slot until the end of the enclosing scope.
+                symbol.scope_depth = scope_depth;
+                symbol.is_hidden = true;
+                true
+            } else {
+                false
+            }
+        });
     }
 
     /// Adds a symbol to `self.accessible_symbols` and `self.symbol_table`.
     fn add_symbol(&mut self, ident: String, stmt: Option<&Stmt>) {
+        let (is_mutable, const_literal) = match stmt.map(|stmt| &stmt.kind) {
+            Some(StmtKind::LetDeclaration {
+                initializer,
+                is_const: true,
+                ..
+            }) => {
+                let const_literal = match &initializer.kind {
+                    ExprKind::NumberLit(_) | ExprKind::BoolLit(_) | ExprKind::StringLit(_) => {
+                        Some(initializer.kind.clone())
+                    }
+                    _ => None,
+                };
+                (false, const_literal)
+            }
+            _ => (true, None),
+        };
         let symbol = Rc::new(RefCell::new(Symbol {
             ident,
             scope_depth: *self.function_scope_depths.last().unwrap(),
+            is_hidden: false,
             is_captured: false, // not captured by default
+            is_mutable,
+            const_literal,
             upvalues: Vec::new(),
             stmt: if let Some(stmt) = stmt {
                 stmt as *const Stmt
             } else {
                 std::ptr::null()
             },
+            module: None,
         }));
         self.accessible_symbols.push(Rc::clone(&symbol));
         if let Some(stmt) = stmt {
@@ -171,6 +445,103 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Resolves the named types in a type annotation to the declarations in scope (see [`ResolveResult::lookup_type`]).
+    /// Unknown names are reported by the type checker as they can also refer to builtin types or type parameters.
+    /// Names qualified with a module (e.g. `util.Point`) are resolved to the declarations exported by the module.
+    fn resolve_type(&mut self, ty: &Type) {
+        match &ty.kind {
+            TypeKind::Path {
+                module: None,
+                ident,
+                args,
+            } => {
+                if let Some(symbol) = self
+                    .accessible_symbols
+                    .iter()
+                    .rev()
+                    .find(|symbol| symbol.borrow().is_accessible_as(ident))
+                {
+                    self.resolved_types
+                        .insert(ty as *const Type, symbol.borrow().stmt);
+                }
+                for arg in args {
+                    self.resolve_type(arg);
+                }
+            }
+            TypeKind::Path {
+                module: Some(module),
+                ident,
+                args,
+            } => {
+                let member = self
+                    .accessible_symbols
+                    .iter()
+                    .rev()
+                    .find(|symbol| symbol.borrow().is_accessible_as(module))
+                    .and_then(|symbol| symbol.borrow().module.as_ref()?.get(ident).cloned());
+                if let Some(member) = member {
+                    self.resolved_types
+                        .insert(ty as *const Type, member.symbol.borrow().stmt);
+                }
+                for arg in args {
+                    self.resolve_type(arg);
+                }
+            }
+            TypeKind::Fn { params, ret, .. } => {
+                for param in params {
+                    self.resolve_type(param);
+                }
+                if let Some(ret) = ret {
+                    self.resolve_type(ret);
+                }
+            }
+            TypeKind::Tuple(elems) => {
+                for elem in elems {
+                    self.resolve_type(elem);
+                }
+            }
+        }
+    }
+
+    /// Adds a symbol that is hoisted to the innermost statement (or the statement containing the outermost block expression).
+    /// Stack slots for hoisted symbols are allocated before the statement is executed which allows expressions to declare variables
+    /// without conflicting with temporary values on the stack.
+    fn add_hoisted_symbol(&mut self, ident: String, stmt: &'a Stmt) -> Rc<RefCell<Symbol>> {
+        self.add_symbol(ident, Some(stmt));
+        let symbol = Rc::clone(self.accessible_symbols.last().unwrap());
+
+        let index = self.accessible_symbols.len() as i32 - 1;
+        let is_global = is_global_scope(symbol.borrow().scope_depth);
+        self.resolved_bindings.insert(
+            stmt as *const Stmt,
+            ResolvedSymbol {
+                offset: if is_global {
+                    index
+                } else {
+                    index - self.current_func_offset()
+                },
+                is_global,
+                is_upvalue: false,
+                symbol: Rc::clone(&symbol),
+            },
+        );
+        let hoist_target = match self.block_exprs.first() {
+            Some(block_expr) => block_expr.hoist_target,
+            None => self.hoist_stack.len() - 1,
+        };
+        self.hoist_stack[hoist_target].push(Rc::clone(&symbol));
+        symbol
+    }
+
+    /// Adds the symbol for a declaration. Declarations inside block expressions are hoisted (see [`Self::add_hoisted_symbol`]).
+    fn declare_symbol(&mut self, ident: String, stmt: &'a Stmt) {
+        if self.block_exprs.is_empty() {
+            self.add_symbol(ident, Some(stmt));
+        } else {
+            self.add_hoisted_symbol(ident, stmt);
+        }
+    }
+
     /// Returns the function scope depth of the specified `scope_depth`.
     fn find_function_scope_depth(&self, scope_depth: u32) -> usize {
         for (i, function_scope_depth) in self.function_scope_depths.iter().enumerate().rev() {
@@ -178,7 +549,12 @@ impl<'a> Resolver<'a> {
                 return i + 1;
             }
         }
-        return 0;
+        0
+    }
+
+    /// Returns the offset of the current function.
+    fn current_func_offset(&self) -> i32 {
+        *self.function_offsets.last().unwrap()
     }
 
     /// Returns `true` if both scope depths are in the same function (e.g. using block statements). Returns `false` otherwise.
@@ -198,40 +574,42 @@ impl<'a> Resolver<'a> {
         span: Range<usize>,
     ) -> Option<(usize, Rc<RefCell<Symbol>>)> {
         for (i, symbol) in self.accessible_symbols.iter().enumerate().rev() {
-            if symbol.borrow().ident == ident {
-                if self.find_function_scope_depth(symbol.borrow().scope_depth) == 0 {
+            if symbol.borrow().is_accessible_as(ident) {
+                if is_global_scope(symbol.borrow().scope_depth) {
                     return Some((i, symbol.clone()));
                 } else if self.in_same_function_scope(
                     symbol.borrow().scope_depth,
                     *self.function_scope_depths.last().unwrap(),
                 ) {
-                    return Some((i - self.current_func_offset as usize, symbol.clone()));
+                    return Some((i - self.current_func_offset() as usize, symbol.clone()));
                 } else {
                     // capture outer variable
                     symbol.borrow_mut().is_captured = true;
 
                     // thread upvalue in enclosing functions
+                    let symbol_function =
+                        self.find_function_scope_depth(symbol.borrow().scope_depth);
                     let mut prev_upvalue_index = 0;
-                    for scope_depth in self.find_function_scope_depth(symbol.borrow().scope_depth)
-                        + 1
+                    for scope_depth in symbol_function + 1
                         ..=self
                             .find_function_scope_depth(*self.function_scope_depths.last().unwrap())
                     {
-                        let is_local = scope_depth == symbol.borrow().scope_depth as usize + 1;
-                        self.function_upvalues[scope_depth as usize].push(ResolvedUpValue {
+                        // The upvalue is captured directly from the stack frame of the enclosing function.
+                        let is_local = scope_depth == symbol_function + 1;
+                        self.function_upvalues[scope_depth].push(ResolvedUpValue {
                             is_local,
                             index: if is_local {
-                                i as i32
+                                i as i32 - self.function_offsets[symbol_function]
                             } else {
                                 prev_upvalue_index as i32
                             },
                         });
 
-                        prev_upvalue_index = self.function_upvalues[scope_depth as usize].len() - 1;
+                        prev_upvalue_index = self.function_upvalues[scope_depth].len() - 1;
                     }
 
                     return Some((
-                        (self.function_upvalues.last().unwrap().len() - 1) as usize,
+                        self.function_upvalues.last().unwrap().len() - 1,
                         symbol.clone(),
                     ));
                 }
@@ -256,6 +634,152 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Resolve the top-level function of a module (see [`Self::resolve_top_level`]) and returns the members exported by the module.
+    /// The top-level declarations of the module are not accessible by name afterwards. Other modules can only access the exported
+    /// members through an import declaration (see [`Self::add_module`]).
+    pub fn resolve_module(&mut self, func: &'a Stmt) -> ModuleExports {
+        let symbols_len = self.accessible_symbols.len();
+        self.resolve_top_level(func);
+
+        let mut exports = ModuleExports::new();
+        if let StmtKind::FnDeclaration { body, .. } = &func.kind {
+            for stmt in body {
+                let declarations = match &stmt.kind {
+                    StmtKind::Export(declaration) => match &declaration.kind {
+                        // every variable of a destructuring declaration is exported
+                        StmtKind::TupleDeclaration { bindings, .. } => bindings.iter().collect(),
+                        _ => vec![declaration.as_ref()],
+                    },
+                    _ => Vec::new(),
+                };
+                for declaration in declarations {
+                    if let Some(symbol) = self.symbol_table.get(&(declaration as *const Stmt)) {
+                        let offset = self
+                            .accessible_symbols
+                            .iter()
+                            .position(|accessible| Rc::ptr_eq(accessible, symbol))
+                            .unwrap();
+                        exports.insert(
+                            symbol.borrow().ident.clone(),
+                            ResolvedSymbol {
+                                offset: offset as i32,
+                                is_global: true,
+                                is_upvalue: false,
+                                symbol: Rc::clone(symbol),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        // The symbols can not be removed because they still occupy a global stack slot.
+        for symbol in &self.accessible_symbols[symbols_len..] {
+            symbol.borrow_mut().is_hidden = true;
+        }
+        exports
+    }
+
+    /// Makes the module at `path` available to import declarations with the same path.
+    pub fn add_module(&mut self, path: String, exports: Rc<ModuleExports>) {
+        self.modules.insert(path, exports);
+    }
+
+    /// Returns `true` if the resolver is at the top level of the program (not inside a function, block or block expression).
+    fn is_top_level(&self) -> bool {
+        self.function_scope_depths.as_slice() == [0] && self.block_exprs.is_empty()
+    }
+
+    /// Resolves the params and body of a function declaration. The [`Symbol`] for `stmt` must already be in `self.symbol_table`.
+    fn resolve_function(&mut self, stmt: &'a Stmt, params: &'a [Stmt], body: &'a [Stmt]) {
+        self.function_offsets
+            .push(self.accessible_symbols.len() as i32);
+        self.function_upvalues.push(Vec::new());
+        self.function_scope_depths
+            .push(*self.function_scope_depths.last().unwrap());
+        self.function_loop_depths.push(0);
+        let block_exprs = std::mem::take(&mut self.block_exprs);
+
+        self.enter_scope();
+        // add arguments
+        for param in params {
+            self.visit_stmt(param);
+        }
+
+        for stmt in body {
+            self.visit_stmt(stmt);
+        }
+        self.exit_scope();
+        self.block_exprs = block_exprs;
+
+        // patch self.symbol_table with upvalues
+        self.symbol_table
+            .get(&(stmt as *const Stmt))
+            .unwrap()
+            .borrow_mut()
+            .upvalues = self.function_upvalues.pop().unwrap();
+        self.function_scope_depths.pop();
+
+        self.function_offsets.pop();
+        self.function_loop_depths.pop();
+    }
+
+    /// Reports an error if a variant pattern does not refer to a variant of an enum or has the wrong number of bindings.
+    /// `enum_ident` should already be resolved.
+    fn check_variant_pattern(
+        &mut self,
+        enum_ident: &Expr,
+        variant: &str,
+        bindings_len: usize,
+        span: Range<usize>,
+    ) {
+        let symbol = match self.resolved_symbol_table.get(&(enum_ident as *const Expr)) {
+            Some(resolved_symbol) => Rc::clone(&resolved_symbol.symbol),
+            None => return, // error already reported
+        };
+        let symbol = symbol.borrow();
+
+        match self.enum_variants.get(&symbol.stmt) {
+            Some(variants) => match variants.iter().find(|(ident, _)| ident == variant) {
+                Some((_, arity)) if *arity != bindings_len => {
+                    self.source.errors.add_error(SyntaxError::new(
+                        format!(
+                            "expected {} field(s) for variant \"{}\", found {}",
+                            arity, variant, bindings_len
+                        ),
+                        span,
+                    ))
+                }
+                Some(_) => {}
+                None => self.source.errors.add_error(SyntaxError::new(
+                    format!("no variant \"{}\" on enum \"{}\"", variant, symbol.ident),
+                    span,
+                )),
+            },
+            None => self.source.errors.add_error(SyntaxError::new(
+                format!("\"{}\" is not an enum", symbol.ident),
+                enum_ident.span.clone(),
+            )),
+        }
+    }
+
+    /// Resolves an identifier expression and returns the symbol it refers to.
+    fn resolve_identifier(&mut self, expr: &'a Expr, ident: &str) -> Option<Rc<RefCell<Symbol>>> {
+        let (offset, symbol) = self.resolve_symbol(ident, expr.span.clone())?;
+        self.resolved_symbol_table.insert(
+            expr as *const Expr,
+            ResolvedSymbol {
+                offset: offset as i32,
+                is_global: is_global_scope(symbol.borrow().scope_depth),
+                is_upvalue: self
+                    .find_function_scope_depth(*self.function_scope_depths.last().unwrap())
+                    > self.find_function_scope_depth(symbol.borrow().scope_depth),
+                symbol: symbol.clone(),
+            },
+        );
+        Some(symbol)
+    }
+
     /// Resolve builtin variables.
     pub fn resolve_builtin_vars(&mut self, builtin_vars: &BuiltinVars) {
         for (ident, _value, _ty) in &builtin_vars.values {
@@ -266,57 +790,148 @@ impl<'a> Resolver<'a> {
 
 impl<'a> Visitor<'a> for Resolver<'a> {
     fn visit_expr(&mut self, expr: &'a Expr) {
-        if let ExprKind::Lambda { .. } = &expr.kind {
-            // custom walking logic for lambda
-        } else {
-            walk_expr(self, expr);
+        match &expr.kind {
+            ExprKind::Lambda { .. } | ExprKind::Match { .. } | ExprKind::Block { .. } => {
+                // custom walking logic for lambda, match and block
+            }
+            ExprKind::FieldAccess { target, .. }
+                if matches!(target.kind, ExprKind::Identifier(_)) =>
+            {
+                // custom walking logic because the target can be a module
+            }
+            _ => walk_expr(self, expr),
         }
 
         match &expr.kind {
             ExprKind::Identifier(ident) => {
-                let symbol = self.resolve_symbol(ident, expr.span.clone());
-                if let Some((offset, symbol)) = symbol {
-                    self.resolved_symbol_table.insert(
-                        expr as *const Expr,
-                        ResolvedSymbol {
-                            offset: offset as i32,
-                            is_global: self.find_function_scope_depth(symbol.borrow().scope_depth)
-                                == 0,
-                            is_upvalue: self.find_function_scope_depth(
-                                *self.function_scope_depths.last().unwrap(),
-                            ) > self
-                                .find_function_scope_depth(symbol.borrow().scope_depth),
-                            symbol: symbol.clone(),
-                        },
-                    );
+                if let Some(symbol) = self.resolve_identifier(expr, ident) {
+                    if symbol.borrow().module.is_some() {
+                        self.source.errors.add_error(
+                            SyntaxError::new(
+                                format!("module \"{}\" can not be used as a value", ident),
+                                expr.span.clone(),
+                            )
+                            .with_help(format!(
+                                "access the members exported by the module with \"{}.member\"",
+                                ident
+                            )),
+                        );
+                    }
+                }
+            }
+            ExprKind::FieldAccess { target, ident } => {
+                if let ExprKind::Identifier(target_ident) = &target.kind {
+                    let module = self
+                        .resolve_identifier(target, target_ident)
+                        .and_then(|symbol| symbol.borrow().module.clone());
+                    if let Some(module) = module {
+                        if !module.contains_key(ident) {
+                            self.source.errors.add_error(
+                                SyntaxError::new(
+                                    format!(
+                                        "module \"{}\" has no exported member \"{}\"",
+                                        target_ident, ident
+                                    ),
+                                    expr.span.clone(),
+                                )
+                                .with_help(format!(
+                                    "consider adding \"export\" to the declaration of \"{}\"",
+                                    ident
+                                )),
+                            );
+                        }
+                    }
                 }
             }
             ExprKind::Binary {
                 lhs,
-                op: Token::Equals,
+                op:
+                    Token::Equals
+                    | Token::PlusEquals
+                    | Token::MinusEquals
+                    | Token::AsteriskEquals
+                    | Token::SlashEquals
+                    | Token::PercentEquals
+                    | Token::DoubleAsteriskEquals
+                    | Token::AmpersandEquals
+                    | Token::PipeEquals
+                    | Token::CaretEquals
+                    | Token::ShiftLeftEquals
+                    | Token::ShiftRightEquals,
                 rhs: _,
             } => {
-                // make sure lhs is an identifier
+                // make sure lhs is an identifier, an index expression or a field access
                 match &lhs.kind {
-                    ExprKind::Identifier(_ident) => {}
+                    ExprKind::Identifier(ident) => {
+                        let is_mutable = match self.resolved_symbol_table.get(&(lhs.as_ref() as *const Expr)) {
+                            Some(resolved_symbol) => resolved_symbol.symbol.borrow().is_mutable,
+                            None => true, // error already reported
+                        };
+                        if !is_mutable {
+                            self.source.errors.add_error(
+                                SyntaxError::new(
+                                    format!("cannot assign to constant \"{}\"", ident),
+                                    expr.span.clone(),
+                                )
+                                .with_help(format!(
+                                    "consider declaring \"{}\" with \"let\" instead of \"const\"",
+                                    ident
+                                )),
+                            );
+                        }
+                    }
+                    ExprKind::Index { .. } => {}
+                    ExprKind::FieldAccess { target, ident } => {
+                        // members of other modules can only be assigned by the module itself
+                        let module_ident = match &target.kind {
+                            ExprKind::Identifier(module_ident) => module_ident.as_str(),
+                            _ => "",
+                        };
+                        let is_module = match self.resolved_symbol_table.get(&(target.as_ref() as *const Expr)) {
+                            Some(resolved_symbol) => resolved_symbol.symbol.borrow().module.is_some(),
+                            None => false,
+                        };
+                        if is_module {
+                            self.source.errors.add_error(
+                                SyntaxError::new(
+                                    format!(
+                                        "cannot assign to \"{}.{}\" outside of module \"{}\"",
+                                        module_ident, ident, module_ident
+                                    ),
+                                    expr.span.clone(),
+                                )
+                                .with_help(format!(
+                                    "consider exporting a function from module \"{}\" that assigns to \"{}\"",
+                                    module_ident, ident
+                                )),
+                            );
+                        }
+                    }
                     _ => self.source.errors.add_error(
                         SyntaxError::new("invalid left-hand side of assignment", lhs.span.clone())
-                            .with_help("left-hand side of an assignment must be an identifier"),
+                            .with_help(
+                                "left-hand side of an assignment must be an identifier, an index expression or a field access",
+                            ),
                     ),
                 };
             }
             ExprKind::Lambda {
                 inner_stmt,
                 params,
+                ret_ty,
                 body,
             } => {
+                if let Some(ret_ty) = ret_ty {
+                    self.resolve_type(ret_ty);
+                }
                 let ident = "lambda".to_string();
-                let old_func_offset = self.current_func_offset;
-
-                self.current_func_offset = self.accessible_symbols.len() as i32;
+                self.function_offsets
+                    .push(self.accessible_symbols.len() as i32);
                 self.function_upvalues.push(Vec::new());
                 self.function_scope_depths
                     .push(*self.function_scope_depths.last().unwrap());
+                self.function_loop_depths.push(0);
+                let block_exprs = std::mem::take(&mut self.block_exprs);
 
                 self.enter_scope();
                 // add arguments
@@ -328,6 +943,7 @@ impl<'a> Visitor<'a> for Resolver<'a> {
                     self.visit_stmt(stmt);
                 }
                 self.exit_scope();
+                self.block_exprs = block_exprs;
 
                 // patch self.symbol_table with upvalues
                 self.function_scope_depths.pop();
@@ -335,14 +951,85 @@ impl<'a> Visitor<'a> for Resolver<'a> {
                     inner_stmt.as_ref() as *const Stmt,
                     Rc::new(RefCell::new(Symbol {
                         ident,
+                        is_hidden: false,
                         is_captured: false,
+                        is_mutable: true,
+                        const_literal: None,
                         scope_depth: *self.function_scope_depths.last().unwrap(),
                         upvalues: self.function_upvalues.pop().unwrap(),
                         stmt: inner_stmt.as_ref() as *const Stmt,
+                        module: None,
                     })),
                 );
 
-                self.current_func_offset = old_func_offset;
+                self.function_offsets.pop();
+                self.function_loop_depths.pop();
+            }
+            ExprKind::Match {
+                scrutinee,
+                inner_stmt,
+                arms,
+            } => {
+                self.visit_expr(scrutinee);
+                // temporary for storing the matched value
+                self.add_hoisted_symbol("match".to_string(), inner_stmt);
+
+                for arm in arms {
+                    let mut binding_symbols = Vec::new();
+                    if let PatternKind::Variant {
+                        enum_ident,
+                        variant,
+                        bindings,
+                    } = &arm.pattern.kind
+                    {
+                        self.visit_expr(enum_ident);
+                        self.check_variant_pattern(
+                            enum_ident,
+                            variant,
+                            bindings.len(),
+                            arm.pattern.span.clone(),
+                        );
+
+                        for binding in bindings {
+                            if let StmtKind::Binding { ident } = &binding.kind {
+                                binding_symbols
+                                    .push(self.add_hoisted_symbol(ident.clone(), binding));
+                            }
+                        }
+                    }
+                    self.visit_expr(&arm.body);
+
+                    // Bindings are only accessible inside the arm.
+                    // The symbols can not be removed because they still occupy a stack slot until the end of the scope.
+                    for symbol in binding_symbols {
+                        symbol.borrow_mut().is_hidden = true;
+                    }
+                }
+            }
+            ExprKind::Block { stmts, expr } => {
+                let hoist_target = match self.block_exprs.first() {
+                    Some(block_expr) => block_expr.hoist_target,
+                    None => self.hoist_stack.len() - 1,
+                };
+                self.block_exprs.push(BlockExprContext {
+                    hoist_target,
+                    loop_depth: *self.function_loop_depths.last().unwrap(),
+                });
+                let symbols_len = self.accessible_symbols.len();
+
+                for stmt in stmts {
+                    self.visit_stmt(stmt);
+                }
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+
+                self.block_exprs.pop();
+                // Declarations are only accessible inside the block.
+                // The symbols can not be removed because they still occupy a hoisted stack slot until the end of the scope.
+                for symbol in &self.accessible_symbols[symbols_len..] {
+                    symbol.borrow_mut().is_hidden = true;
+                }
             }
             _ => {}
         }
@@ -351,54 +1038,253 @@ impl<'a> Visitor<'a> for Resolver<'a> {
     fn visit_stmt(&mut self, stmt: &'a Stmt) {
         // Do not use default walking logic.
 
+        self.hoist_stack.push(Vec::new());
+        self.resolve_stmt(stmt);
+        let hoisted = self.hoist_stack.pop().unwrap();
+        if !hoisted.is_empty() {
+            self.hoisted_symbols.insert(stmt as *const Stmt, hoisted);
+        }
+    }
+}
+
+impl<'a> Resolver<'a> {
+    /// Resolves a [`Stmt`]. Should only be called by [`Visitor::visit_stmt`].
+    fn resolve_stmt(&mut self, stmt: &'a Stmt) {
         match &stmt.kind {
             StmtKind::LetDeclaration {
                 ident,
                 initializer,
-                ty: _,
+                ty,
+                is_const: _,
+                doc: _,
             } => {
+                if let Some(ty) = ty {
+                    self.resolve_type(ty);
+                }
                 self.visit_expr(initializer);
-                self.add_symbol(ident.clone(), Some(stmt));
+                self.declare_symbol(ident.clone(), stmt);
+            }
+            StmtKind::TupleDeclaration {
+                bindings,
+                initializer,
+                ty,
+                is_const,
+                doc: _,
+            } => {
+                if let Some(ty) = ty {
+                    self.resolve_type(ty);
+                }
+                self.visit_expr(initializer);
+                for binding in bindings {
+                    if let StmtKind::Binding { ident } = &binding.kind {
+                        self.declare_symbol(ident.clone(), binding);
+                        if *is_const {
+                            self.symbol_table[&(binding as *const Stmt)]
+                                .borrow_mut()
+                                .is_mutable = false;
+                        }
+                    }
+                }
             }
             StmtKind::FnParam {
-                ident
+                ident,
+                ty,
+                default,
+                is_rest: _,
             } => {
+                if let Some(ty) = ty {
+                    self.resolve_type(ty);
+                }
+                if let Some(default) = default {
+                    let is_literal = match &default.kind {
+                        ExprKind::NumberLit(_) | ExprKind::BoolLit(_) | ExprKind::StringLit(_) => {
+                            true
+                        }
+                        // negative numbers are parsed as unary minus
+                        ExprKind::Unary {
+                            op: Token::Minus,
+                            arg,
+                        } => matches!(arg.kind, ExprKind::NumberLit(_)),
+                        _ => false,
+                    };
+                    if !is_literal {
+                        self.source.errors.add_error(
+                            SyntaxError::new(
+                                "default value must be a literal",
+                                default.span.clone(),
+                            )
+                            .with_help(
+                                "only numbers, booleans and strings can be used as default values",
+                            ),
+                        );
+                    }
+                }
                 self.add_symbol(ident.clone(), Some(stmt));
             }
+            StmtKind::Binding { .. } => {
+                unreachable!("bindings are resolved by the enclosing expression")
+            }
             StmtKind::FnDeclaration {
                 ident,
+                type_params: _,
                 params,
+                ret_ty,
                 body,
+                doc: _,
             } => {
-                self.add_symbol(ident.clone(), Some(stmt)); // Add symbol first to allow for recursion.
-
-                let old_func_offset = self.current_func_offset;
-
-                self.current_func_offset = self.accessible_symbols.len() as i32;
-                self.function_upvalues.push(Vec::new());
-                self.function_scope_depths
-                    .push(*self.function_scope_depths.last().unwrap());
-
-                self.enter_scope();
-                // add arguments
-                for param in params {
-                    self.visit_stmt(param);
+                self.declare_symbol(ident.clone(), stmt); // Add symbol first to allow for recursion.
+                if let Some(ret_ty) = ret_ty {
+                    self.resolve_type(ret_ty);
                 }
-
-                for stmt in body {
-                    self.visit_stmt(stmt);
+                let mut fn_params = FnParams {
+                    params: Vec::new(),
+                    rest: None,
+                };
+                for param in params {
+                    match &param.kind {
+                        StmtKind::FnParam {
+                            ident,
+                            is_rest: true,
+                            ..
+                        } => fn_params.rest = Some(ident.clone()),
+                        StmtKind::FnParam { ident, default, .. } => fn_params.params.push((
+                            ident.clone(),
+                            default.as_ref().map(|default| default.kind.clone()),
+                        )),
+                        _ => {}
+                    }
                 }
-                self.exit_scope();
+                self.fn_params.insert(stmt as *const Stmt, fn_params);
+                self.resolve_function(stmt, params, body);
+            }
+            StmtKind::ClassDeclaration { ident, methods } => {
+                self.declare_symbol(ident.clone(), stmt); // Add symbol first to allow methods to reference the class.
 
-                // patch self.symbol_table with upvalues
-                self.symbol_table
-                    .get(&(stmt as *const Stmt))
-                    .unwrap()
-                    .borrow_mut()
-                    .upvalues = self.function_upvalues.pop().unwrap();
-                self.function_scope_depths.pop();
+                for method in methods {
+                    if let StmtKind::FnDeclaration {
+                        ident: method_ident,
+                        type_params: _,
+                        params,
+                        ret_ty,
+                        body,
+                        doc: _,
+                    } = &method.kind
+                    {
+                        if let Some(ret_ty) = ret_ty {
+                            self.resolve_type(ret_ty);
+                        }
+                        if !matches!(params.first().map(|param| &param.kind), Some(StmtKind::FnParam { ident, .. }) if ident == "self")
+                        {
+                            self.source.errors.add_error(
+                                SyntaxError::new(
+                                    format!(
+                                        "method \"{}\" must take \"self\" as its first parameter",
+                                        method_ident
+                                    ),
+                                    method.span.clone(),
+                                )
+                                .with_help(format!(
+                                    "consider changing it to \"fn {}(self, ...)\"",
+                                    method_ident
+                                )),
+                            );
+                        }
 
-                self.current_func_offset = old_func_offset;
+                        // Methods are not accessible by name. They are only accessed through instances.
+                        self.symbol_table.insert(
+                            method as *const Stmt,
+                            Rc::new(RefCell::new(Symbol {
+                                ident: method_ident.clone(),
+                                scope_depth: *self.function_scope_depths.last().unwrap(),
+                                is_hidden: false,
+                                is_captured: false,
+                                is_mutable: true,
+                                const_literal: None,
+                                upvalues: Vec::new(),
+                                stmt: method as *const Stmt,
+                                module: None,
+                            })),
+                        );
+                        self.resolve_function(method, params, body);
+                    } else {
+                        unreachable!("class methods are always function declarations");
+                    }
+                }
+            }
+            StmtKind::StructDeclaration { ident, fields } => {
+                self.declare_symbol(ident.clone(), stmt); // Add symbol first to allow for recursive field types.
+                for (i, field) in fields.iter().enumerate() {
+                    if fields[..i].iter().any(|other| other.ident == field.ident) {
+                        self.source.errors.add_error(SyntaxError::new(
+                            format!(
+                                "duplicate field \"{}\" in struct \"{}\"",
+                                field.ident, ident
+                            ),
+                            field.span.clone(),
+                        ));
+                    }
+                    if let Some(ty) = &field.ty {
+                        self.resolve_type(ty);
+                    }
+                }
+            }
+            StmtKind::EnumDeclaration { ident, variants } => {
+                for (i, variant) in variants.iter().enumerate() {
+                    if variants[..i]
+                        .iter()
+                        .any(|other| other.ident == variant.ident)
+                    {
+                        self.source.errors.add_error(SyntaxError::new(
+                            format!(
+                                "duplicate variant \"{}\" in enum \"{}\"",
+                                variant.ident, ident
+                            ),
+                            variant.span.clone(),
+                        ));
+                    }
+                    for field in &variant.fields {
+                        if let Some(ty) = &field.ty {
+                            self.resolve_type(ty);
+                        }
+                    }
+                }
+                self.declare_symbol(ident.clone(), stmt);
+                self.enum_variants.insert(
+                    stmt as *const Stmt,
+                    variants
+                        .iter()
+                        .map(|variant| (variant.ident.clone(), variant.fields.len()))
+                        .collect(),
+                );
+            }
+            StmtKind::ImportDeclaration { ident, path } => {
+                if !self.is_top_level() {
+                    self.source.errors.add_error(SyntaxError::new(
+                        "import declarations are only allowed at the top level",
+                        stmt.span.clone(),
+                    ));
+                    return;
+                }
+                match self.modules.get(path) {
+                    Some(module) => {
+                        let module = Rc::clone(module);
+                        self.add_symbol(ident.clone(), Some(stmt));
+                        self.accessible_symbols.last().unwrap().borrow_mut().module = Some(module);
+                    }
+                    None => self.source.errors.add_error(SyntaxError::new(
+                        format!("cannot find module \"{}\"", path),
+                        stmt.span.clone(),
+                    )),
+                }
+            }
+            StmtKind::Export(declaration) => {
+                if !self.is_top_level() {
+                    self.source.errors.add_error(SyntaxError::new(
+                        "export declarations are only allowed at the top level",
+                        stmt.span.clone(),
+                    ));
+                }
+                self.visit_stmt(declaration);
             }
             StmtKind::Block(body) => {
                 self.enter_scope();
@@ -429,13 +1315,99 @@ impl<'a> Visitor<'a> for Resolver<'a> {
             StmtKind::WhileStmt { condition, body } => {
                 self.visit_expr(condition);
                 self.enter_scope();
+                *self.function_loop_depths.last_mut().unwrap() += 1;
+                for stmt in body {
+                    self.visit_stmt(stmt);
+                }
+                *self.function_loop_depths.last_mut().unwrap() -= 1;
+                self.exit_scope();
+            }
+            StmtKind::ForStmt {
+                initializer,
+                condition,
+                step,
+                body,
+            } => {
+                // scope for initializer
+                self.enter_scope();
+                if let Some(initializer) = initializer {
+                    self.visit_stmt(initializer);
+                }
+                if let Some(condition) = condition {
+                    self.visit_expr(condition);
+                }
+                // Resolve step before body so that symbols hoisted to the for statement are declared before the loop body.
+                if let Some(step) = step {
+                    self.visit_expr(step);
+                }
+                self.enter_scope();
+                *self.function_loop_depths.last_mut().unwrap() += 1;
                 for stmt in body {
                     self.visit_stmt(stmt);
                 }
+                *self.function_loop_depths.last_mut().unwrap() -= 1;
+                self.exit_scope();
+                self.exit_scope();
+            }
+            StmtKind::TryStmt {
+                try_block,
+                binding,
+                catch_block,
+            } => {
+                self.enter_scope();
+                for stmt in try_block {
+                    self.visit_stmt(stmt);
+                }
+                self.exit_scope();
+
+                // The thrown value is only accessible inside the catch block.
+                self.enter_scope();
+                if let StmtKind::Binding { ident } = &binding.kind {
+                    self.declare_symbol(ident.clone(), binding);
+                }
+                for stmt in catch_block {
+                    self.visit_stmt(stmt);
+                }
                 self.exit_scope();
             }
+            StmtKind::ThrowStmt(expr) => self.visit_expr(expr),
             StmtKind::ExprStmt(expr) => self.visit_expr(expr),
-            StmtKind::ReturnStmt(expr) => self.visit_expr(expr),
+            StmtKind::ReturnStmt(expr) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            StmtKind::BreakStmt | StmtKind::ContinueStmt => {
+                let keyword = match &stmt.kind {
+                    StmtKind::BreakStmt => "break",
+                    _ => "continue",
+                };
+                let loop_depth = *self.function_loop_depths.last().unwrap();
+                if loop_depth == 0 {
+                    self.source.errors.add_error(
+                        SyntaxError::new(
+                            format!("\"{}\" outside of a loop", keyword),
+                            stmt.span.clone(),
+                        )
+                        .with_help(format!(
+                            "\"{}\" can only be used inside a while or for loop",
+                            keyword
+                        )),
+                    );
+                } else if matches!(self.block_exprs.last(), Some(block_expr) if block_expr.loop_depth == loop_depth)
+                {
+                    self.source.errors.add_error(
+                        SyntaxError::new(
+                            format!("\"{}\" can not jump out of a block expression", keyword),
+                            stmt.span.clone(),
+                        )
+                        .with_help(format!(
+                            "\"{}\" can only be used inside a block expression if the loop is inside the block expression too",
+                            keyword
+                        )),
+                    );
+                }
+            }
             StmtKind::Lambda => unreachable!(),
             StmtKind::Error => {}
         }
@@ -1,22 +1,56 @@
 //! Type checking and type inference pass.
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use ella_parser::ast::{Expr, ExprKind, Stmt, StmtKind};
+use ella_parser::ast::{Expr, ExprKind, MatchArm, PatternKind, Stmt, StmtKind, Type, TypeKind};
 use ella_parser::lexer::Token;
 use ella_parser::visitor::{walk_expr, walk_stmt, Visitor};
 use ella_source::{Source, SyntaxError};
-use ella_value::{BuiltinType, BuiltinVars, UniqueType};
+use ella_value::{BuiltinType, BuiltinVars, ClassType, EnumType, StructType, UniqueType};
 
-use crate::resolve::{ResolveResult, Symbol};
+use crate::resolve::{match_call_args, CallArg, ResolveResult, Symbol};
 
 pub type SymbolTypeTable = HashMap<*const Symbol, UniqueType>;
 pub type ExprTypeTable = HashMap<*const Expr, UniqueType>;
+pub type NamedTypeTable = HashMap<*const Stmt, UniqueType>;
+
+/// The level of type variables that have been generalized.
+/// Generalized type variables are replaced with fresh type variables every time the symbol is referenced.
+const GENERIC_LEVEL: usize = usize::MAX;
+
+/// The state of a type variable (see [`UniqueType::Var`]).
+#[derive(Debug, Clone)]
+enum TypeVar {
+    /// The type has not been inferred yet.
+    /// The `level` is the depth of the outermost function that can constrain the type variable.
+    Unbound { level: usize },
+    /// A type parameter of a generic function (e.g. `T` in `fn identity<T>(x: T) -> T`).
+    /// Inside the function, the type is unknown and can not be inferred to be a specific type.
+    Param { ident: String, level: usize },
+    /// The type variable has been inferred to be the type.
+    Bound(UniqueType),
+}
+
+/// The state of a function that is currently being type checked.
+struct FunctionContext {
+    /// The declared or inferred return type.
+    ret: UniqueType,
+    /// Whether the return type is inferred from the body rather than declared or expected.
+    is_inferred: bool,
+    /// Whether a `return` statement with a value has been encountered.
+    has_return_value: bool,
+    /// Whether a `return` statement without a value has been encountered.
+    has_bare_return: bool,
+}
 
 #[derive(Debug, Clone)]
 pub struct TypeCheckResult {
     symbol_type_table: SymbolTypeTable,
     expr_type_table: ExprTypeTable,
+    named_types: NamedTypeTable,
+    type_vars: Vec<TypeVar>,
 }
 
 /// Type checking and type inference pass.
@@ -24,6 +58,18 @@ pub struct TypeChecker<'a> {
     resolve_result: &'a ResolveResult,
     symbol_type_table: SymbolTypeTable,
     expr_type_table: ExprTypeTable,
+    /// Types declared by the user (e.g. structs), keyed by their declaration.
+    named_types: NamedTypeTable,
+    /// A stack of the functions that are currently being type checked.
+    functions: Vec<FunctionContext>,
+    /// All the type variables. Indexed by [`UniqueType::Var`].
+    type_vars: Vec<TypeVar>,
+    /// The depth of nested function declarations. Used for generalizing the types of functions.
+    level: usize,
+    /// The type parameters of the generic functions that are currently being type checked.
+    type_params: Vec<(String, UniqueType)>,
+    /// The types that the type parameters of generic functions are instantiated with at every reference.
+    type_args: HashMap<*const Expr, Vec<(String, UniqueType)>>,
     source: Source<'a>,
 }
 
@@ -34,6 +80,12 @@ impl<'a> TypeChecker<'a> {
             resolve_result,
             symbol_type_table: SymbolTypeTable::new(),
             expr_type_table: ExprTypeTable::new(),
+            named_types: NamedTypeTable::new(),
+            functions: Vec::new(),
+            type_vars: Vec::new(),
+            level: 0,
+            type_params: Vec::new(),
+            type_args: HashMap::new(),
             source,
         }
     }
@@ -50,6 +102,12 @@ impl<'a> TypeChecker<'a> {
             resolve_result,
             symbol_type_table: type_check_result.symbol_type_table.clone(),
             expr_type_table: type_check_result.expr_type_table.clone(),
+            named_types: type_check_result.named_types.clone(),
+            functions: Vec::new(),
+            type_vars: type_check_result.type_vars.clone(),
+            level: 0,
+            type_params: Vec::new(),
+            type_args: HashMap::new(),
             source,
         }
     }
@@ -61,9 +119,26 @@ impl<'a> TypeChecker<'a> {
                 .lookup_in_accessible_symbols(ident)
                 .unwrap(); // get Symbol for builtin value
 
+            // replace the type parameters of generic builtins with generalized type variables
+            let mut type_params = HashMap::new();
+            for (i, ident) in builtin_vars
+                .type_params
+                .get(ident)
+                .into_iter()
+                .flatten()
+                .enumerate()
+            {
+                self.type_vars.push(TypeVar::Param {
+                    ident: ident.clone(),
+                    level: GENERIC_LEVEL,
+                });
+                type_params.insert(i, UniqueType::Var(self.type_vars.len() - 1));
+            }
+            let ty = self.replace_type_vars(ty, &type_params);
+
             assert!(self
                 .symbol_type_table
-                .insert(symbol.as_ptr() as *const Symbol, ty.clone())
+                .insert(symbol.as_ptr() as *const Symbol, ty)
                 .is_none());
         }
     }
@@ -79,72 +154,887 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Returns the type of `expr` with all the inferred type variables substituted.
+    fn expr_type(&self, expr: &Expr) -> UniqueType {
+        self.substitute(self.expr_type_table.get(&(expr as *const Expr)).unwrap())
+    }
+
+    /// Creates a new type variable at the current level.
+    fn fresh_type_var(&mut self) -> UniqueType {
+        self.type_vars.push(TypeVar::Unbound { level: self.level });
+        UniqueType::Var(self.type_vars.len() - 1)
+    }
+
+    /// Replaces all the bound type variables in `ty` with their inferred types.
+    fn substitute(&self, ty: &UniqueType) -> UniqueType {
+        match ty {
+            UniqueType::Var(var) => match &self.type_vars[*var] {
+                TypeVar::Bound(bound) => self.substitute(bound),
+                TypeVar::Unbound { .. } | TypeVar::Param { .. } => ty.clone(),
+            },
+            UniqueType::Builtin(BuiltinType::List(elem)) => {
+                UniqueType::Builtin(BuiltinType::List(Box::new(self.substitute(elem))))
+            }
+            UniqueType::Builtin(BuiltinType::Map(key, value)) => {
+                UniqueType::Builtin(BuiltinType::Map(
+                    Box::new(self.substitute(key)),
+                    Box::new(self.substitute(value)),
+                ))
+            }
+            UniqueType::Builtin(BuiltinType::Tuple(elems)) => UniqueType::Builtin(
+                BuiltinType::Tuple(elems.iter().map(|elem| self.substitute(elem)).collect()),
+            ),
+            UniqueType::Builtin(BuiltinType::Fn {
+                params,
+                ret,
+                variadic,
+                optional,
+            }) => UniqueType::Builtin(BuiltinType::Fn {
+                params: params.iter().map(|param| self.substitute(param)).collect(),
+                ret: Box::new(self.substitute(ret)),
+                variadic: *variadic,
+                optional: *optional,
+            }),
+            _ => ty.clone(),
+        }
+    }
+
+    /// Calls `f` with every unbound type variable in `ty`.
+    fn for_each_type_var(&self, ty: &UniqueType, f: &mut impl FnMut(usize)) {
+        match self.substitute(ty) {
+            UniqueType::Var(var) => f(var),
+            UniqueType::Builtin(BuiltinType::List(elem)) => self.for_each_type_var(&elem, f),
+            UniqueType::Builtin(BuiltinType::Map(key, value)) => {
+                self.for_each_type_var(&key, f);
+                self.for_each_type_var(&value, f);
+            }
+            UniqueType::Builtin(BuiltinType::Tuple(elems)) => {
+                for elem in &elems {
+                    self.for_each_type_var(elem, f);
+                }
+            }
+            UniqueType::Builtin(BuiltinType::Fn { params, ret, .. }) => {
+                for param in &params {
+                    self.for_each_type_var(param, f);
+                }
+                self.for_each_type_var(&ret, f);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if `ty` is a type variable that can still be inferred.
+    fn is_unbound_type_var(&self, ty: &UniqueType) -> bool {
+        match self.substitute(ty) {
+            UniqueType::Var(var) => matches!(self.type_vars[var], TypeVar::Unbound { .. }),
+            _ => false,
+        }
+    }
+
+    /// Infers the type variable `var` to be `ty`.
+    /// Returns `false` if `ty` contains `var` (an infinite type) or if `var` is a type parameter.
+    fn bind_type_var(&mut self, var: usize, ty: &UniqueType) -> bool {
+        let level = match self.type_vars[var] {
+            TypeVar::Unbound { level } => level,
+            TypeVar::Param { .. } => {
+                // only other type variables can be inferred to be the type parameter
+                return match ty {
+                    UniqueType::Var(other) if self.is_unbound_type_var(ty) => {
+                        self.bind_type_var(*other, &UniqueType::Var(var))
+                    }
+                    _ => false,
+                };
+            }
+            TypeVar::Bound(_) => unreachable!("type variable is already bound"),
+        };
+        let mut vars = Vec::new();
+        self.for_each_type_var(ty, &mut |var| vars.push(var));
+        if vars.contains(&var) {
+            return false;
+        }
+        // the type variables in ty can now be constrained by the same functions as var
+        for other in vars {
+            if let TypeVar::Unbound { level: other_level }
+            | TypeVar::Param {
+                level: other_level, ..
+            } = &mut self.type_vars[other]
+            {
+                *other_level = (*other_level).min(level);
+            }
+        }
+        self.type_vars[var] = TypeVar::Bound(ty.clone());
+        true
+    }
+
+    /// Checks if a value of type `from` can be used where a value of type `to` is expected.
+    /// Unbound type variables are inferred to make the types compatible.
+    /// [`UniqueType::Any`] is compatible with every type and type variables are never inferred to be `Any`.
+    fn unify(&mut self, from: &UniqueType, to: &UniqueType) -> bool {
+        let from = self.substitute(from);
+        let to = self.substitute(to);
+        match (&from, &to) {
+            (UniqueType::Var(from_var), UniqueType::Var(to_var)) if from_var == to_var => true,
+            (_, UniqueType::Any | UniqueType::Unknown)
+            | (UniqueType::Any | UniqueType::Unknown, _) => true,
+            (UniqueType::Var(var), ty) | (ty, UniqueType::Var(var)) => self.bind_type_var(*var, ty),
+            (
+                UniqueType::Builtin(BuiltinType::List(from_elem)),
+                UniqueType::Builtin(BuiltinType::List(to_elem)),
+            ) => self.unify(from_elem, to_elem),
+            (
+                UniqueType::Builtin(BuiltinType::Map(from_key, from_value)),
+                UniqueType::Builtin(BuiltinType::Map(to_key, to_value)),
+            ) => self.unify(from_key, to_key) && self.unify(from_value, to_value),
+            (
+                UniqueType::Builtin(BuiltinType::Tuple(from_elems)),
+                UniqueType::Builtin(BuiltinType::Tuple(to_elems)),
+            ) => {
+                from_elems.len() == to_elems.len()
+                    && from_elems
+                        .iter()
+                        .zip(to_elems)
+                        .all(|(from_elem, to_elem)| self.unify(from_elem, to_elem))
+            }
+            (
+                UniqueType::Builtin(BuiltinType::Fn {
+                    params: from_params,
+                    ret: from_ret,
+                    variadic: from_variadic,
+                    optional: from_optional,
+                }),
+                UniqueType::Builtin(BuiltinType::Fn {
+                    params: to_params,
+                    ret: to_ret,
+                    variadic: to_variadic,
+                    optional: to_optional,
+                }),
+            ) => {
+                from_variadic == to_variadic
+                    && from_optional >= to_optional
+                    && from_params.len() == to_params.len()
+                    && from_params
+                        .iter()
+                        .zip(to_params)
+                        .all(|(from_param, to_param)| self.unify(to_param, from_param))
+                    && self.unify(from_ret, to_ret)
+            }
+            _ => from.can_implicit_cast_to(&to),
+        }
+    }
+
+    /// Generalizes the type variables in `ty` that can not be constrained anymore
+    /// (because they were introduced in a function which has been type checked completely).
+    fn generalize(&mut self, ty: &UniqueType) {
+        let mut vars = Vec::new();
+        self.for_each_type_var(ty, &mut |var| vars.push(var));
+        for var in vars {
+            if let TypeVar::Unbound { level } | TypeVar::Param { level, .. } =
+                &mut self.type_vars[var]
+            {
+                if *level > self.level {
+                    *level = GENERIC_LEVEL;
+                }
+            }
+        }
+    }
+
+    /// Replaces the generalized type variables in `ty` with fresh type variables.
+    /// Also returns the fresh type variables of the type parameters (see [`TypeVar::Param`]).
+    fn instantiate(&mut self, ty: &UniqueType) -> (UniqueType, Vec<(String, UniqueType)>) {
+        let mut generic_vars = Vec::new();
+        self.for_each_type_var(ty, &mut |var| {
+            if !generic_vars.contains(&var) {
+                generic_vars.push(var);
+            }
+        });
+        generic_vars.retain(|var| {
+            matches!(
+                self.type_vars[*var],
+                TypeVar::Unbound { level } | TypeVar::Param { level, .. } if level == GENERIC_LEVEL
+            )
+        });
+        if generic_vars.is_empty() {
+            return (ty.clone(), Vec::new());
+        }
+
+        let mut fresh_vars = HashMap::new();
+        let mut type_args = Vec::new();
+        for var in generic_vars {
+            let fresh_var = self.fresh_type_var();
+            if let TypeVar::Param { ident, .. } = &self.type_vars[var] {
+                type_args.push((ident.clone(), fresh_var.clone()));
+            }
+            fresh_vars.insert(var, fresh_var);
+        }
+        (
+            self.replace_type_vars(&self.substitute(ty), &fresh_vars),
+            type_args,
+        )
+    }
+
+    /// Replaces the type variables in `ty` according to `replacements`.
+    fn replace_type_vars(
+        &self,
+        ty: &UniqueType,
+        replacements: &HashMap<usize, UniqueType>,
+    ) -> UniqueType {
+        match ty {
+            UniqueType::Var(var) => replacements
+                .get(var)
+                .cloned()
+                .unwrap_or(UniqueType::Var(*var)),
+            UniqueType::Builtin(BuiltinType::List(elem)) => UniqueType::Builtin(BuiltinType::List(
+                Box::new(self.replace_type_vars(elem, replacements)),
+            )),
+            UniqueType::Builtin(BuiltinType::Map(key, value)) => {
+                UniqueType::Builtin(BuiltinType::Map(
+                    Box::new(self.replace_type_vars(key, replacements)),
+                    Box::new(self.replace_type_vars(value, replacements)),
+                ))
+            }
+            UniqueType::Builtin(BuiltinType::Tuple(elems)) => {
+                UniqueType::Builtin(BuiltinType::Tuple(
+                    elems
+                        .iter()
+                        .map(|elem| self.replace_type_vars(elem, replacements))
+                        .collect(),
+                ))
+            }
+            UniqueType::Builtin(BuiltinType::Fn {
+                params,
+                ret,
+                variadic,
+                optional,
+            }) => UniqueType::Builtin(BuiltinType::Fn {
+                params: params
+                    .iter()
+                    .map(|param| self.replace_type_vars(param, replacements))
+                    .collect(),
+                ret: Box::new(self.replace_type_vars(ret, replacements)),
+                variadic: *variadic,
+                optional: *optional,
+            }),
+            _ => ty.clone(),
+        }
+    }
+
+    /// Returns a help message naming the type parameter of the generic function `callee`
+    /// that is inferred to have a different type from argument `i` than from the previous arguments.
+    /// `type_args` are the type variables of the type parameters of `callee` (see [`Self::instantiate`]).
+    fn type_arg_conflict(
+        &mut self,
+        callee: &Expr,
+        i: usize,
+        arg_ty: &UniqueType,
+        type_args: &[(String, UniqueType)],
+    ) -> Option<String> {
+        let param_ty = match self.expr_type_table.get(&(callee as *const Expr)) {
+            Some(UniqueType::Builtin(BuiltinType::Fn { params, .. })) => params[i].clone(),
+            _ => return None,
+        };
+        let inferred_tys: Vec<UniqueType> = type_args
+            .iter()
+            .map(|(_, var)| self.substitute(var))
+            .collect();
+
+        // infer the type parameters from the argument alone
+        let type_vars = self.type_vars.clone();
+        let mut fresh_vars = HashMap::new();
+        for (_, var) in type_args {
+            if let UniqueType::Var(var) = var {
+                let fresh_var = self.fresh_type_var();
+                fresh_vars.insert(*var, fresh_var);
+            }
+        }
+        let param_ty = self.replace_type_vars(&param_ty, &fresh_vars);
+        self.unify(arg_ty, &param_ty);
+
+        let mut conflict = None;
+        for ((ident, var), inferred_ty) in type_args.iter().zip(inferred_tys) {
+            let arg_inferred_ty = match var {
+                UniqueType::Var(var) => self.substitute(&fresh_vars[var]),
+                _ => continue,
+            };
+            if !matches!(inferred_ty, UniqueType::Var(_))
+                && !matches!(arg_inferred_ty, UniqueType::Var(_))
+                && inferred_ty != arg_inferred_ty
+            {
+                conflict = Some(format!(
+                    "type parameter \"{}\" can not be both {} and {}",
+                    ident, inferred_ty, arg_inferred_ty
+                ));
+                break;
+            }
+        }
+        self.type_vars = type_vars;
+        conflict
+    }
+
+    /// Resolves a type annotation. Reports an error and returns [`UniqueType::Unknown`] if the type does not exist.
+    fn resolve_type(&mut self, ty: &Type) -> UniqueType {
+        match &ty.kind {
+            TypeKind::Path {
+                module: Some(module),
+                ident,
+                args,
+            } => {
+                let path = format!("{}.{}", module, ident);
+                if !args.is_empty() {
+                    self.source.errors.add_error(SyntaxError::new(
+                        format!(
+                            "expected 0 type argument(s) for \"{}\", found {}",
+                            path,
+                            args.len()
+                        ),
+                        ty.span.clone(),
+                    ));
+                    return UniqueType::Unknown;
+                }
+                self.resolve_named_type(ty, &path)
+            }
+            TypeKind::Path {
+                module: None,
+                ident,
+                args,
+            } => {
+                // type parameters shadow other types
+                let type_param = self
+                    .type_params
+                    .iter()
+                    .rev()
+                    .find(|(type_param, _)| type_param == ident);
+                if let (Some((_, var)), true) = (type_param, args.is_empty()) {
+                    return var.clone();
+                }
+
+                let expected_args = match ident.as_str() {
+                    "List" => 1,
+                    "Map" => 2,
+                    _ => 0,
+                };
+                if args.len() != expected_args {
+                    self.source.errors.add_error(SyntaxError::new(
+                        format!(
+                            "expected {} type argument(s) for \"{}\", found {}",
+                            expected_args,
+                            ident,
+                            args.len()
+                        ),
+                        ty.span.clone(),
+                    ));
+                    return UniqueType::Unknown;
+                }
+                let mut args: Vec<UniqueType> =
+                    args.iter().map(|arg| self.resolve_type(arg)).collect();
+
+                match ident.as_str() {
+                    "any" => UniqueType::Any,
+                    "bool" => UniqueType::Builtin(BuiltinType::Bool),
+                    "number" => UniqueType::Builtin(BuiltinType::Number),
+                    "string" => UniqueType::Builtin(BuiltinType::String),
+                    "List" => UniqueType::Builtin(BuiltinType::List(Box::new(args.remove(0)))),
+                    "Map" => {
+                        let key = args.remove(0);
+                        let value = args.remove(0);
+                        UniqueType::Builtin(BuiltinType::Map(Box::new(key), Box::new(value)))
+                    }
+                    ident => self.resolve_named_type(ty, ident),
+                }
+            }
+            TypeKind::Fn {
+                params,
+                variadic,
+                ret,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| self.resolve_type(param))
+                    .collect();
+                let ret = match ret {
+                    Some(ret) => self.resolve_type(ret),
+                    None => UniqueType::Any,
+                };
+                UniqueType::Builtin(BuiltinType::Fn {
+                    params,
+                    ret: Box::new(ret),
+                    variadic: *variadic,
+                    optional: 0,
+                })
+            }
+            TypeKind::Tuple(elems) => UniqueType::Builtin(BuiltinType::Tuple(
+                elems.iter().map(|elem| self.resolve_type(elem)).collect(),
+            )),
+        }
+    }
+
+    /// Returns the type declared by the user that the named type `ty` refers to.
+    /// Reports an error with the name `path` and returns [`UniqueType::Unknown`] if there is no such type.
+    fn resolve_named_type(&mut self, ty: &Type, path: &str) -> UniqueType {
+        match self
+            .resolve_result
+            .lookup_type(ty)
+            .and_then(|decl| self.named_types.get(&decl))
+        {
+            Some(ty) => ty.clone(),
+            None => {
+                self.source.errors.add_error(SyntaxError::new(
+                    format!("unknown type \"{}\"", path),
+                    ty.span.clone(),
+                ));
+                UniqueType::Unknown
+            }
+        }
+    }
+
+    /// Type checks the params of a function and returns the type of the function.
+    /// Params and the return value without a type annotation get a fresh type variable which is inferred from the body.
+    fn function_type(&mut self, params: &'a [Stmt], ret_ty: &Option<Type>) -> UniqueType {
+        let mut param_tys = Vec::new();
+        for param in params {
+            self.visit_stmt(param);
+            let symbol = self.resolve_result.lookup_declaration(param).unwrap();
+            param_tys.push(
+                self.symbol_type_table
+                    .get(&(symbol.as_ptr() as *const Symbol))
+                    .unwrap()
+                    .clone(),
+            );
+        }
+        let ret = match ret_ty {
+            Some(ty) => self.resolve_type(ty),
+            None => self.fresh_type_var(),
+        };
+        UniqueType::Builtin(BuiltinType::Fn {
+            params: param_tys,
+            ret: Box::new(ret),
+            variadic: matches!(
+                params.last().map(|param| &param.kind),
+                Some(StmtKind::FnParam { is_rest: true, .. })
+            ),
+            optional: params
+                .iter()
+                .filter(|param| {
+                    matches!(
+                        param.kind,
+                        StmtKind::FnParam {
+                            default: Some(_),
+                            ..
+                        }
+                    )
+                })
+                .count(),
+        })
+    }
+
+    /// Type checks the body of a function with the return type of `fn_ty`.
+    /// If the return type was declared (`ret_ty`), a value must be returned on every path.
+    fn type_check_function_body(
+        &mut self,
+        fn_ty: &UniqueType,
+        ret_ty: &Option<Type>,
+        body: &'a [Stmt],
+    ) {
+        let ret = match fn_ty {
+            UniqueType::Builtin(BuiltinType::Fn { ret, .. }) => ret.as_ref().clone(),
+            _ => unreachable!("function type is always a BuiltinType::Fn"),
+        };
+        let is_inferred = ret_ty.is_none() && self.is_unbound_type_var(&ret);
+        self.functions.push(FunctionContext {
+            ret,
+            is_inferred,
+            has_return_value: false,
+            has_bare_return: false,
+        });
+        for stmt in body {
+            self.visit_stmt(stmt);
+        }
+        let function = self.functions.pop().unwrap();
+        if let Some(ret_ty) = ret_ty {
+            let ret = self.substitute(&function.ret);
+            let returns_value = !matches!(
+                ret,
+                UniqueType::Builtin(BuiltinType::Unit) | UniqueType::Any | UniqueType::Unknown
+            );
+            if returns_value && !always_returns(body) {
+                self.source.errors.add_error(
+                    SyntaxError::new("missing return", ret_ty.span.clone()).with_help(format!(
+                        "the function must return a value of type {} on every path",
+                        ret
+                    )),
+                );
+            }
+        }
+        if !function.has_return_value {
+            // the function always returns the unit value
+            self.unify(&UniqueType::Builtin(BuiltinType::Unit), &function.ret);
+        }
+    }
+
+    /// Returns the common type of `exprs` or a fresh type variable if there are no expressions.
+    /// An error with `message` is reported for every expression that does not have the common type.
+    fn common_type<'b>(
+        &mut self,
+        exprs: impl Iterator<Item = &'b Expr>,
+        message: &str,
+    ) -> UniqueType {
+        let mut common_ty: Option<UniqueType> = None;
+        for expr in exprs {
+            let ty = self.expr_type(expr);
+            common_ty = match common_ty {
+                None => Some(ty.clone()),
+                Some(common_ty) => {
+                    if ty == UniqueType::Any || common_ty == UniqueType::Any {
+                        Some(UniqueType::Any) // propagate any
+                    } else {
+                        if !self.unify(&ty, &common_ty) {
+                            self.source
+                                .errors
+                                .add_error(SyntaxError::new(message, expr.span.clone()));
+                        }
+                        Some(common_ty)
+                    }
+                }
+            };
+        }
+        common_ty.unwrap_or_else(|| self.fresh_type_var())
+    }
+
+    /// Type checks a match expression and returns the type of the expression (the common type of all arms).
+    /// Reports an error if the match is not exhaustive.
+    fn type_check_match(&mut self, scrutinee: &'a Expr, arms: &'a [MatchArm]) -> UniqueType {
+        self.visit_expr(scrutinee);
+        let scrutinee_ty = self.expr_type(scrutinee);
+        if !matches!(
+            scrutinee_ty,
+            UniqueType::Enum(_) | UniqueType::Var(_) | UniqueType::Any | UniqueType::Unknown
+        ) {
+            self.source.errors.add_error(SyntaxError::new(
+                "value is not an enum",
+                scrutinee.span.clone(),
+            ));
+        }
+
+        let mut matched_enum = match &scrutinee_ty {
+            UniqueType::Enum(enum_ty) => Some(Rc::clone(enum_ty)),
+            _ => None,
+        };
+        let mut covered_variants = Vec::new();
+        let mut has_wildcard = false;
+        for arm in arms {
+            match &arm.pattern.kind {
+                PatternKind::Wildcard => has_wildcard = true,
+                PatternKind::Variant {
+                    enum_ident,
+                    variant,
+                    bindings,
+                } => {
+                    self.visit_expr(enum_ident);
+                    let enum_ty = match self
+                        .expr_type_table
+                        .get(&(enum_ident.as_ref() as *const Expr))
+                    {
+                        Some(UniqueType::Type(ty)) => match ty.as_ref() {
+                            UniqueType::Enum(enum_ty) => Some(Rc::clone(enum_ty)),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+
+                    let field_tys = enum_ty
+                        .as_ref()
+                        .and_then(|enum_ty| enum_ty.variant_fields(variant))
+                        .map(|field_tys| field_tys.to_vec())
+                        .unwrap_or_default();
+                    for (i, binding) in bindings.iter().enumerate() {
+                        // invalid patterns are reported by the resolver
+                        let ty = field_tys.get(i).cloned().unwrap_or(UniqueType::Any);
+                        let symbol = &self.resolve_result.lookup_binding(binding).unwrap().symbol;
+                        self.symbol_type_table
+                            .insert(symbol.as_ptr() as *const Symbol, ty);
+                    }
+
+                    if let Some(enum_ty) = enum_ty {
+                        match &matched_enum {
+                            Some(matched_ty) if matched_ty != &enum_ty => {
+                                self.source.errors.add_error(SyntaxError::new(
+                                    "pattern has wrong type",
+                                    arm.pattern.span.clone(),
+                                ))
+                            }
+                            _ => {
+                                matched_enum = Some(enum_ty);
+                                covered_variants.push(variant.as_str());
+                            }
+                        }
+                    }
+                }
+            }
+            self.visit_expr(&arm.body);
+        }
+
+        if let (UniqueType::Var(_), Some(enum_ty)) = (&scrutinee_ty, &matched_enum) {
+            // infer the type of the scrutinee from the patterns
+            self.unify(&UniqueType::Enum(Rc::clone(enum_ty)), &scrutinee_ty);
+        }
+        if let (false, Some(enum_ty)) = (has_wildcard, matched_enum) {
+            let missing_variants: Vec<_> = enum_ty
+                .variants
+                .iter()
+                .filter(|(variant, _)| !covered_variants.contains(&variant.as_str()))
+                .map(|(variant, _)| format!("{}.{}", enum_ty.ident, variant))
+                .collect();
+            if !missing_variants.is_empty() {
+                self.source.errors.add_error(
+                    SyntaxError::new(
+                        format!(
+                            "non-exhaustive match, missing {}",
+                            missing_variants.join(", ")
+                        ),
+                        scrutinee.span.clone(),
+                    )
+                    .with_help(
+                        "add a match arm for every missing variant or a wildcard arm (\"_\")",
+                    ),
+                );
+            }
+        }
+
+        self.common_type(
+            arms.iter().map(|arm| &arm.body),
+            "match arms must have the same type",
+        )
+    }
+
     pub fn into_type_check_result(self) -> TypeCheckResult {
         TypeCheckResult {
             symbol_type_table: self.symbol_type_table,
             expr_type_table: self.expr_type_table,
+            named_types: self.named_types,
+            type_vars: self.type_vars,
+        }
+    }
+}
+
+/// Returns `true` if executing `stmts` never reaches the end of the block,
+/// i.e. every path returns or throws.
+fn always_returns(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.kind {
+        StmtKind::ReturnStmt(_) | StmtKind::ThrowStmt(_) => true,
+        StmtKind::Block(stmts) => always_returns(stmts),
+        StmtKind::IfElseStmt {
+            condition: _,
+            if_block,
+            else_block: Some(else_block),
+        } => always_returns(if_block) && always_returns(else_block),
+        StmtKind::TryStmt {
+            try_block,
+            binding: _,
+            catch_block,
+        } => always_returns(try_block) && always_returns(catch_block),
+        _ => false,
+    })
+}
+
+/// Collects the values of the `return` statements in a function body.
+/// Nested functions, lambdas and classes are not visited as their `return` statements return from themselves.
+#[derive(Default)]
+struct ReturnValueCollector<'a> {
+    return_values: Vec<&'a Expr>,
+}
+
+impl<'a> Visitor<'a> for ReturnValueCollector<'a> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if !matches!(expr.kind, ExprKind::Lambda { .. }) {
+            walk_expr(self, expr);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match &stmt.kind {
+            StmtKind::FnDeclaration { .. } | StmtKind::ClassDeclaration { .. } => {}
+            StmtKind::ReturnStmt(Some(expr)) => self.return_values.push(expr),
+            _ => walk_stmt(self, stmt),
         }
     }
 }
 
 impl<'a> Visitor<'a> for TypeChecker<'a> {
     fn visit_expr(&mut self, expr: &'a Expr) {
-        walk_expr(self, expr);
+        if !matches!(&expr.kind, ExprKind::Match { .. } | ExprKind::Lambda { .. }) {
+            // match expressions must set the types of the bindings before visiting the arms
+            // lambdas must set the return type before visiting the body
+            walk_expr(self, expr);
+        }
 
         let ty = match &expr.kind {
             ExprKind::BoolLit(_) => UniqueType::Builtin(BuiltinType::Bool),
             ExprKind::NumberLit(_) => UniqueType::Builtin(BuiltinType::Number),
             ExprKind::StringLit(_) => UniqueType::Builtin(BuiltinType::String),
+            // interpolated expressions can have any type
+            ExprKind::Interpolation(_) => UniqueType::Builtin(BuiltinType::String),
             ExprKind::Identifier(ident) => match self.resolve_result.lookup_identifier(expr) {
-                Some(resolved_symbol) => self
-                    .symbol_type_table
-                    .get(&(resolved_symbol.symbol.as_ptr() as *const Symbol))
-                    .expect(&format!(
-                        "type of identifier \"{}\" at {:?}",
-                        ident, expr.span
-                    ))
-                    .clone(),
+                Some(resolved_symbol) => {
+                    let ty = self
+                        .symbol_type_table
+                        .get(&(resolved_symbol.symbol.as_ptr() as *const Symbol))
+                        .unwrap_or_else(|| {
+                            panic!("type of identifier \"{}\" at {:?}", ident, expr.span)
+                        })
+                        .clone();
+                    // generic functions can be used with different types every time they are referenced
+                    let (ty, type_args) = self.instantiate(&ty);
+                    if !type_args.is_empty() {
+                        self.type_args.insert(expr as *const Expr, type_args);
+                    }
+                    ty
+                }
                 None => UniqueType::Unknown,
             },
-            ExprKind::FnCall { callee, args } => {
-                let callee_ty = self
-                    .expr_type_table
-                    .get(&(callee.as_ref() as *const Expr))
-                    .unwrap();
-                if let UniqueType::Builtin(BuiltinType::Fn { params, ret }) = callee_ty {
-                    // check arity
-                    if args.len() != params.len() {
-                        self.source.errors.add_error(SyntaxError::new(
-                            format!(
-                                "expected {} argument(s), found {}",
-                                params.len(),
-                                args.len()
-                            ),
-                            expr.span.clone(),
-                        ));
-                    } else {
-                    }
+            ExprKind::FnCall {
+                callee,
+                args,
+                named_args,
+            } => {
+                let callee_ty = self.expr_type(callee.as_ref());
+                let fn_params = self.resolve_result.lookup_fn_params(callee);
+                if fn_params.is_none() && !named_args.is_empty() {
+                    self.source.errors.add_error(
+                        SyntaxError::new(
+                            "named arguments can only be used when calling a function declaration",
+                            named_args[0].span.clone(),
+                        )
+                        .with_help("pass the arguments by position instead"),
+                    );
+                }
+                if let UniqueType::Builtin(BuiltinType::Fn {
+                    params,
+                    ret,
+                    variadic,
+                    optional,
+                }) = callee_ty
+                {
+                    // the number of params that are not the rest param
+                    let fixed_len = params.len() - variadic as usize;
+                    // the number of params without a default value
+                    let required_len = fixed_len - optional;
+                    // match arguments to params and check arity
+                    // (position of the argument, index of the param, argument)
+                    let args: Vec<(usize, usize, &Expr)> = match fn_params {
+                        Some(fn_params) => {
+                            match match_call_args(fn_params, args, named_args, expr.span.clone()) {
+                                Ok(call_args) => {
+                                    let mut matched = Vec::new();
+                                    for (i, arg) in call_args.into_iter().enumerate() {
+                                        match arg {
+                                            CallArg::Given(arg) => matched.push((i, i, arg)),
+                                            CallArg::Default(_) => {}
+                                            CallArg::Rest(rest_args) => matched.extend(
+                                                rest_args
+                                                    .iter()
+                                                    .enumerate()
+                                                    .map(|(j, arg)| (i + j, i, arg)),
+                                            ),
+                                        }
+                                    }
+                                    matched
+                                }
+                                Err(err) => {
+                                    self.source.errors.add_error(err);
+                                    Vec::new()
+                                }
+                            }
+                        }
+                        None => {
+                            // named arguments are already reported
+                            if named_args.is_empty()
+                                && (args.len() < required_len
+                                    || !variadic && args.len() > fixed_len)
+                            {
+                                let expected = if variadic {
+                                    format!("at least {}", required_len)
+                                } else if optional > 0 {
+                                    format!("{} to {}", required_len, fixed_len)
+                                } else {
+                                    fixed_len.to_string()
+                                };
+                                self.source.errors.add_error(SyntaxError::new(
+                                    format!(
+                                        "expected {} argument(s), found {}",
+                                        expected,
+                                        args.len()
+                                    ),
+                                    expr.span.clone(),
+                                ));
+                            }
+                            args.iter()
+                                .enumerate()
+                                .map(|(i, arg)| (i, i.min(fixed_len), arg))
+                                .filter(|(_, param, _)| *param < params.len())
+                                .collect()
+                        }
+                    };
                     // check params type
-                    for i in 0..params.len().min(args.len()) {
-                        let param_ty = &params[i];
-                        let arg_ty = self
-                            .expr_type_table
-                            .get(&(&args[i] as *const Expr))
-                            .unwrap();
-
-                        if !arg_ty.can_implicit_cast_to(param_ty) {
-                            self.source.errors.add_error(SyntaxError::new(
+                    let type_args = self
+                        .type_args
+                        .get(&(callee.as_ref() as *const Expr))
+                        .cloned()
+                        .unwrap_or_default();
+                    for (position, i, arg) in args {
+                        let is_rest = variadic && i == fixed_len;
+                        let param_ty = if is_rest {
+                            // extra arguments are checked against the element type of the rest param
+                            match self.substitute(&params[i]) {
+                                UniqueType::Builtin(BuiltinType::List(elem)) => *elem,
+                                _ => UniqueType::Any,
+                            }
+                        } else {
+                            params[i].clone()
+                        };
+                        let arg_ty = self.expr_type(arg);
+
+                        let type_vars = if type_args.is_empty() {
+                            None
+                        } else {
+                            Some(self.type_vars.clone())
+                        };
+                        if !self.unify(&arg_ty, &param_ty) {
+                            let mut error = SyntaxError::new(
                                 format!(
                                     "wrong type in argument position {}",
-                                    i + 1, // +1 for 1-based index
+                                    position + 1, // +1 for 1-based index
                                 ),
-                                args[i].span.clone(),
-                            ));
+                                arg.span.clone(),
+                            );
+                            if let Some(type_vars) = type_vars {
+                                // undo the types that were inferred before the mismatch
+                                self.type_vars = type_vars;
+                                if !is_rest {
+                                    if let Some(help) =
+                                        self.type_arg_conflict(callee, i, &arg_ty, &type_args)
+                                    {
+                                        error = error.with_help(help);
+                                    }
+                                }
+                            }
+                            self.source.errors.add_error(error);
                         }
                     }
-                    ret.as_ref().clone()
-                } else if callee_ty == &UniqueType::Any {
+                    self.substitute(&ret)
+                } else if self.is_unbound_type_var(&callee_ty) {
+                    // infer the callee to be a function that accepts the arguments
+                    let ret = self.fresh_type_var();
+                    let fn_ty = UniqueType::Builtin(BuiltinType::Fn {
+                        params: args.iter().map(|arg| self.expr_type(arg)).collect(),
+                        ret: Box::new(ret.clone()),
+                        variadic: false,
+                        optional: 0,
+                    });
+                    self.unify(&fn_ty, &callee_ty);
+                    ret
+                } else if callee_ty == UniqueType::Any {
                     UniqueType::Any
+                } else if callee_ty == UniqueType::Unknown {
+                    UniqueType::Unknown // error already reported
                 } else {
                     self.source.errors.add_error(SyntaxError::new(
                         "value is not a function",
@@ -153,29 +1043,181 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                     UniqueType::Unknown
                 }
             }
+            ExprKind::ListLit(elements) => {
+                let elem_ty =
+                    self.common_type(elements.iter(), "list elements must have the same type");
+                UniqueType::Builtin(BuiltinType::List(Box::new(elem_ty)))
+            }
+            ExprKind::TupleLit(elements) => UniqueType::Builtin(BuiltinType::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.expr_type(element))
+                    .collect(),
+            )),
+            ExprKind::MapLit(entries) => {
+                let key_ty = self.common_type(
+                    entries.iter().map(|(key, _)| key),
+                    "map keys must have the same type",
+                );
+                let value_ty = self.common_type(
+                    entries.iter().map(|(_, value)| value),
+                    "map values must have the same type",
+                );
+                UniqueType::Builtin(BuiltinType::Map(Box::new(key_ty), Box::new(value_ty)))
+            }
+            ExprKind::Index { target, index } => {
+                let target_ty = self.expr_type(target.as_ref());
+                let index_ty = self.expr_type(index.as_ref());
+
+                match target_ty {
+                    UniqueType::Builtin(BuiltinType::List(elem_ty)) => {
+                        if !self.unify(&index_ty, &UniqueType::Builtin(BuiltinType::Number)) {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "list index must be a number",
+                                index.span.clone(),
+                            ));
+                        }
+                        elem_ty.as_ref().clone()
+                    }
+                    UniqueType::Builtin(BuiltinType::Map(key_ty, value_ty)) => {
+                        if !self.unify(&index_ty, &key_ty) {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "wrong type for map key",
+                                index.span.clone(),
+                            ));
+                        }
+                        value_ty.as_ref().clone()
+                    }
+                    // the type can not be inferred from the usage
+                    UniqueType::Var(_) if self.is_unbound_type_var(&target_ty) => UniqueType::Any,
+                    UniqueType::Any => UniqueType::Any,
+                    UniqueType::Unknown => UniqueType::Unknown,
+                    _ => {
+                        self.source.errors.add_error(SyntaxError::new(
+                            "value is not a list or a map",
+                            target.span.clone(),
+                        ));
+                        UniqueType::Unknown
+                    }
+                }
+            }
+            ExprKind::FieldAccess { target, ident }
+                if self.resolve_result.lookup_module(target).is_some() =>
+            {
+                match self.resolve_result.lookup_module_member(target, ident) {
+                    Some(member) => {
+                        let ty = self
+                            .symbol_type_table
+                            .get(&(member.symbol.as_ptr() as *const Symbol))
+                            .unwrap()
+                            .clone();
+                        // generic functions can be used with different types every time they are referenced
+                        let (ty, type_args) = self.instantiate(&ty);
+                        if !type_args.is_empty() {
+                            self.type_args.insert(expr as *const Expr, type_args);
+                        }
+                        ty
+                    }
+                    None => UniqueType::Unknown, // error already reported
+                }
+            }
+            ExprKind::FieldAccess { target, ident } => {
+                let target_ty = self.expr_type(target.as_ref());
+
+                match target_ty {
+                    UniqueType::Struct(struct_ty) => match struct_ty.field_type(ident) {
+                        Some(field_ty) => field_ty,
+                        None => {
+                            let message =
+                                format!("no field \"{}\" on type \"{}\"", ident, struct_ty.ident);
+                            self.source
+                                .errors
+                                .add_error(SyntaxError::new(message, expr.span.clone()));
+                            UniqueType::Unknown
+                        }
+                    },
+                    // fields and methods of class instances are only known at runtime
+                    UniqueType::Class(_) => UniqueType::Any,
+                    UniqueType::Type(ty) => match ty.as_ref() {
+                        UniqueType::Enum(enum_ty) => match enum_ty.variant_fields(ident) {
+                            // variants without a payload are values
+                            Some([]) => UniqueType::Enum(Rc::clone(enum_ty)),
+                            // variants with a payload are constructor functions
+                            Some(field_tys) => UniqueType::Builtin(BuiltinType::Fn {
+                                params: field_tys.to_vec(),
+                                ret: Box::new(UniqueType::Enum(Rc::clone(enum_ty))),
+                                variadic: false,
+                                optional: 0,
+                            }),
+                            None => {
+                                let message = format!(
+                                    "no variant \"{}\" on enum \"{}\"",
+                                    ident, enum_ty.ident
+                                );
+                                self.source
+                                    .errors
+                                    .add_error(SyntaxError::new(message, expr.span.clone()));
+                                UniqueType::Unknown
+                            }
+                        },
+                        _ => UniqueType::Unknown,
+                    },
+                    // the type can not be inferred from the usage
+                    UniqueType::Var(_) if self.is_unbound_type_var(&target_ty) => UniqueType::Any,
+                    UniqueType::Any => UniqueType::Any,
+                    UniqueType::Unknown => UniqueType::Unknown,
+                    _ => {
+                        // the type of a class name is its constructor function
+                        let class_ty = match &target.kind {
+                            ExprKind::Identifier(_) => self
+                                .resolve_result
+                                .lookup_identifier(target)
+                                .and_then(|resolved_symbol| {
+                                    self.named_types.get(&resolved_symbol.symbol.borrow().stmt)
+                                }),
+                            _ => None,
+                        };
+                        let error = match class_ty {
+                            Some(UniqueType::Class(class_ty)) => SyntaxError::new(
+                                format!(
+                                    "can not access \"{}\" on class \"{}\"",
+                                    ident, class_ty.ident
+                                ),
+                                target.span.clone(),
+                            )
+                            .with_help(format!(
+                                "fields and methods are accessed on instances (e.g. \"{}().{}\")",
+                                class_ty.ident, ident
+                            )),
+                            _ => SyntaxError::new("value is not a struct", target.span.clone()),
+                        };
+                        self.source.errors.add_error(error);
+                        UniqueType::Unknown
+                    }
+                }
+            }
             ExprKind::Binary { lhs, op, rhs } => {
-                let lhs_ty = self
-                    .expr_type_table
-                    .get(&(lhs.as_ref() as *const Expr))
-                    .unwrap();
-                let rhs_ty = self
-                    .expr_type_table
-                    .get(&(rhs.as_ref() as *const Expr))
-                    .unwrap();
-
-                if lhs_ty == &UniqueType::Any || rhs_ty == &UniqueType::Any {
+                let lhs_ty = self.expr_type(lhs.as_ref());
+                let rhs_ty = self.expr_type(rhs.as_ref());
+
+                if lhs_ty == UniqueType::Any || rhs_ty == UniqueType::Any {
                     UniqueType::Any // propagate any
                 } else {
                     match op {
                         Token::Plus => {
-                            if lhs_ty == &UniqueType::Builtin(BuiltinType::Number)
-                                && rhs_ty == &UniqueType::Builtin(BuiltinType::Number)
+                            if self.is_unbound_type_var(&lhs_ty)
+                                && self.is_unbound_type_var(&rhs_ty)
                             {
-                                UniqueType::Builtin(BuiltinType::Number)
-                            } else if lhs_ty == &UniqueType::Builtin(BuiltinType::String)
-                                && rhs_ty == &UniqueType::Builtin(BuiltinType::String)
+                                // can be either numbers or strings
+                                UniqueType::Any
+                            } else if self.unify(&lhs_ty, &rhs_ty)
+                                && self.unify(&rhs_ty, &lhs_ty)
+                                && matches!(
+                                    self.substitute(&lhs_ty),
+                                    UniqueType::Builtin(BuiltinType::Number | BuiltinType::String)
+                                )
                             {
-                                UniqueType::Builtin(BuiltinType::String)
+                                self.substitute(&lhs_ty)
                             } else {
                                 self.source.errors.add_error(SyntaxError::new(
                                     "expected numbers or strings for addition",
@@ -185,8 +1227,8 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                             }
                         }
                         Token::Minus => {
-                            if lhs_ty != &UniqueType::Builtin(BuiltinType::Number)
-                                || rhs_ty != &UniqueType::Builtin(BuiltinType::Number)
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
                             {
                                 self.source.errors.add_error(SyntaxError::new(
                                     "expected numbers for subtraction",
@@ -196,8 +1238,8 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                             UniqueType::Builtin(BuiltinType::Number)
                         }
                         Token::Asterisk => {
-                            if lhs_ty != &UniqueType::Builtin(BuiltinType::Number)
-                                || rhs_ty != &UniqueType::Builtin(BuiltinType::Number)
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
                             {
                                 self.source.errors.add_error(SyntaxError::new(
                                     "expected numbers for multiplication",
@@ -207,8 +1249,8 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                             UniqueType::Builtin(BuiltinType::Number)
                         }
                         Token::Slash => {
-                            if lhs_ty != &UniqueType::Builtin(BuiltinType::Number)
-                                || rhs_ty != &UniqueType::Builtin(BuiltinType::Number)
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
                             {
                                 self.source.errors.add_error(SyntaxError::new(
                                     "expected numbers for division",
@@ -217,19 +1259,63 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                             }
                             UniqueType::Builtin(BuiltinType::Number)
                         }
+                        Token::Percent => {
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                            {
+                                self.source.errors.add_error(SyntaxError::new(
+                                    "expected numbers for remainder",
+                                    expr.span.clone(),
+                                ));
+                            }
+                            UniqueType::Builtin(BuiltinType::Number)
+                        }
+                        Token::DoubleAsterisk => {
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                            {
+                                self.source.errors.add_error(SyntaxError::new(
+                                    "expected numbers for exponentiation",
+                                    expr.span.clone(),
+                                ));
+                            }
+                            UniqueType::Builtin(BuiltinType::Number)
+                        }
+                        Token::Ampersand
+                        | Token::Pipe
+                        | Token::Caret
+                        | Token::ShiftLeft
+                        | Token::ShiftRight => {
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Number))
+                            {
+                                self.source.errors.add_error(SyntaxError::new(
+                                    "expected numbers for bitwise operator",
+                                    expr.span.clone(),
+                                ));
+                            }
+                            UniqueType::Builtin(BuiltinType::Number)
+                        }
                         Token::Equals
                         | Token::PlusEquals
                         | Token::MinusEquals
                         | Token::AsteriskEquals
-                        | Token::SlashEquals => {
+                        | Token::SlashEquals
+                        | Token::PercentEquals
+                        | Token::DoubleAsteriskEquals
+                        | Token::AmpersandEquals
+                        | Token::PipeEquals
+                        | Token::CaretEquals
+                        | Token::ShiftLeftEquals
+                        | Token::ShiftRightEquals => {
                             // result of assignment is new value
-                            if !rhs_ty.can_implicit_cast_to(lhs_ty) {
+                            if !self.unify(&rhs_ty, &lhs_ty) {
                                 self.source.errors.add_error(SyntaxError::new(
                                     "wrong type in assignment",
                                     rhs.span.clone(),
                                 ));
                             }
-                            lhs_ty.clone()
+                            self.substitute(&lhs_ty)
                         }
                         Token::EqualsEquals
                         | Token::NotEquals
@@ -237,23 +1323,33 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                         | Token::LessThanEquals
                         | Token::GreaterThan
                         | Token::GreaterThanEquals => {
-                            if lhs_ty != rhs_ty {
+                            if !self.unify(&lhs_ty, &rhs_ty)
+                                || self.substitute(&lhs_ty) != self.substitute(&rhs_ty)
+                            {
                                 self.source.errors.add_error(SyntaxError::new("comparison operators can only be used on two values of the same type", expr.span.clone()));
                             }
                             UniqueType::Builtin(BuiltinType::Bool)
                         }
+                        Token::LogicalAnd | Token::LogicalOr => {
+                            if !self.unify(&lhs_ty, &UniqueType::Builtin(BuiltinType::Bool))
+                                || !self.unify(&rhs_ty, &UniqueType::Builtin(BuiltinType::Bool))
+                            {
+                                self.source.errors.add_error(SyntaxError::new(
+                                    "expected booleans for logical operator",
+                                    expr.span.clone(),
+                                ));
+                            }
+                            UniqueType::Builtin(BuiltinType::Bool)
+                        }
                         _ => unreachable!(),
                     }
                 }
             }
             ExprKind::Unary { op, arg } => {
-                let arg_ty = self
-                    .expr_type_table
-                    .get(&(arg.as_ref() as *const Expr))
-                    .unwrap();
+                let arg_ty = self.expr_type(arg.as_ref());
                 match op {
                     Token::LogicalNot => {
-                        if arg_ty != &UniqueType::Builtin(BuiltinType::Bool) {
+                        if !self.unify(&arg_ty, &UniqueType::Builtin(BuiltinType::Bool)) {
                             self.source.errors.add_error(SyntaxError::new(
                                 "logical not can only be used on a bool",
                                 expr.span.clone(),
@@ -262,7 +1358,7 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                         UniqueType::Builtin(BuiltinType::Bool)
                     }
                     Token::Minus => {
-                        if arg_ty != &UniqueType::Builtin(BuiltinType::Number) {
+                        if !self.unify(&arg_ty, &UniqueType::Builtin(BuiltinType::Number)) {
                             self.source.errors.add_error(SyntaxError::new(
                                 "unary minus can only be used on a number",
                                 expr.span.clone(),
@@ -270,29 +1366,83 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                         }
                         UniqueType::Builtin(BuiltinType::Number)
                     }
+                    Token::Tilde => {
+                        if !self.unify(&arg_ty, &UniqueType::Builtin(BuiltinType::Number)) {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "bitwise not can only be used on a number",
+                                expr.span.clone(),
+                            ));
+                        }
+                        UniqueType::Builtin(BuiltinType::Number)
+                    }
                     _ => unreachable!(),
                 }
             }
             ExprKind::Lambda {
                 inner_stmt: _,
                 params,
-                body: _,
+                ret_ty,
+                body,
             } => {
-                let ty = UniqueType::Builtin(BuiltinType::Fn {
-                    params: vec![UniqueType::Any; params.len()],
-                    ret: Box::new(UniqueType::Any),
-                });
-                // FIXME give function proper type
+                // NOTE: walking is not enabled for this case
+                let ty = self.function_type(params, ret_ty);
+                self.type_check_function_body(&ty, ret_ty, body);
                 ty
             }
+            ExprKind::Block { stmts: _, expr } => match expr {
+                Some(expr) => self.expr_type(expr.as_ref()),
+                None => UniqueType::Builtin(BuiltinType::Unit),
+            },
+            ExprKind::If {
+                condition: _,
+                then_branch,
+                else_branch,
+            } => match else_branch {
+                Some(else_branch) => self.common_type(
+                    [then_branch, else_branch]
+                        .iter()
+                        .map(|branch| branch.as_ref()),
+                    "if and else branches must have the same type",
+                ),
+                None => {
+                    // without an else branch, the value is () when the condition is false
+                    let then_ty = self.expr_type(then_branch);
+                    if !self.unify(&then_ty, &UniqueType::Builtin(BuiltinType::Unit)) {
+                        self.source.errors.add_error(
+                            SyntaxError::new(
+                                "if without else must have type ()",
+                                then_branch.span.clone(),
+                            )
+                            .with_help(format!(
+                                "add an else branch that also has type {}",
+                                then_ty
+                            )),
+                        );
+                    }
+                    UniqueType::Builtin(BuiltinType::Unit)
+                }
+            },
+            ExprKind::Match {
+                scrutinee,
+                inner_stmt: _,
+                arms,
+            } => {
+                // NOTE: walking is not enabled for this case
+                self.type_check_match(scrutinee, arms)
+            }
             ExprKind::Error => UniqueType::Unknown,
         };
         self.expr_type_table.insert(expr as *const Expr, ty);
     }
 
     fn visit_stmt(&mut self, stmt: &'a Stmt) {
-        if !matches!(&stmt.kind, StmtKind::FnDeclaration{..}) {
-            // function declaration must be type checked first before body to allow for recursion
+        if !matches!(
+            &stmt.kind,
+            StmtKind::FnDeclaration { .. }
+                | StmtKind::ClassDeclaration { .. }
+                | StmtKind::TryStmt { .. }
+        ) {
+            // function and class declarations must be type checked first before body to allow for recursion
             walk_stmt(self, stmt);
         }
 
@@ -301,27 +1451,15 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                 ident,
                 initializer,
                 ty,
+                is_const: _,
+                doc: _,
             } => {
                 let ty = match ty {
-                    Some(ty_path) => {
-                        let ty = match ty_path.ident.as_str() {
-                            "bool" => UniqueType::Builtin(BuiltinType::Bool),
-                            "number" => UniqueType::Builtin(BuiltinType::Number),
-                            "string" => UniqueType::Builtin(BuiltinType::String),
-                            _ => {
-                                self.source.errors.add_error(SyntaxError::new(
-                                    format!("unknown type \"{}\"", ty_path.ident),
-                                    ty_path.span.clone(),
-                                ));
-                                UniqueType::Unknown
-                            }
-                        };
+                    Some(ty) => {
+                        let ty = self.resolve_type(ty);
                         // make sure initializer has right type
-                        let initializer_ty = self
-                            .expr_type_table
-                            .get(&(initializer as *const Expr))
-                            .unwrap();
-                        if !initializer_ty.can_implicit_cast_to(&ty) {
+                        let initializer_ty = self.expr_type(initializer);
+                        if !self.unify(&initializer_ty, &ty) {
                             self.source.errors.add_error(SyntaxError::new(
                                 "initializer has wrong type",
                                 initializer.span.clone(),
@@ -354,36 +1492,327 @@ impl<'a> Visitor<'a> for TypeChecker<'a> {
                 self.symbol_type_table
                     .insert(symbol.as_ptr() as *const Symbol, ty);
             }
-            StmtKind::FnParam { ident: _ } => {
-                // FIXME: give type to param
+            StmtKind::TupleDeclaration {
+                bindings,
+                initializer,
+                ty,
+                is_const: _,
+                doc: _,
+            } => {
+                let initializer_ty = self.expr_type(initializer);
+                let tuple_ty = match ty {
+                    Some(ty) => {
+                        let ty = self.resolve_type(ty);
+                        if !self.unify(&initializer_ty, &ty) {
+                            self.source.errors.add_error(SyntaxError::new(
+                                "initializer has wrong type",
+                                initializer.span.clone(),
+                            ))
+                        }
+                        ty
+                    }
+                    None => initializer_ty,
+                };
+
+                let elem_tys = match self.substitute(&tuple_ty) {
+                    UniqueType::Builtin(BuiltinType::Tuple(elems))
+                        if elems.len() == bindings.len() =>
+                    {
+                        elems
+                    }
+                    // infer the type of the initializer from the number of bindings
+                    UniqueType::Var(_) if self.is_unbound_type_var(&tuple_ty) => {
+                        let elems: Vec<_> =
+                            bindings.iter().map(|_| self.fresh_type_var()).collect();
+                        self.unify(&tuple_ty, &BuiltinType::Tuple(elems.clone()).into());
+                        elems
+                    }
+                    UniqueType::Any => vec![UniqueType::Any; bindings.len()],
+                    UniqueType::Unknown => vec![UniqueType::Unknown; bindings.len()],
+                    tuple_ty => {
+                        self.source.errors.add_error(SyntaxError::new(
+                            format!(
+                                "expected a tuple with {} element(s), found {}",
+                                bindings.len(),
+                                tuple_ty
+                            ),
+                            initializer.span.clone(),
+                        ));
+                        vec![UniqueType::Unknown; bindings.len()]
+                    }
+                };
+                for (binding, ty) in bindings.iter().zip(elem_tys) {
+                    let symbol = self.resolve_result.lookup_declaration(binding).unwrap();
+                    self.symbol_type_table
+                        .insert(symbol.as_ptr() as *const Symbol, ty);
+                }
+            }
+            StmtKind::FnParam {
+                ident: _,
+                ty,
+                default,
+                is_rest,
+            } => {
+                let ty = match ty {
+                    Some(ty) => self.resolve_type(ty),
+                    // the extra arguments are collected into a list
+                    None if *is_rest => {
+                        UniqueType::Builtin(BuiltinType::List(Box::new(self.fresh_type_var())))
+                    }
+                    None => self.fresh_type_var(),
+                };
+                if *is_rest
+                    && !matches!(
+                        ty,
+                        UniqueType::Builtin(BuiltinType::List(_))
+                            | UniqueType::Any
+                            | UniqueType::Unknown
+                    )
+                {
+                    self.source.errors.add_error(
+                        SyntaxError::new("rest parameter must have a list type", stmt.span.clone())
+                            .with_help(format!("consider changing the type to \"List<{}>\"", ty)),
+                    );
+                }
+                if let Some(default) = default {
+                    let default_ty = self.expr_type(default);
+                    if !self.unify(&default_ty, &ty) {
+                        self.source.errors.add_error(SyntaxError::new(
+                            "default value has wrong type",
+                            default.span.clone(),
+                        ));
+                    }
+                }
                 let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
 
                 self.symbol_type_table
-                    .insert(symbol.as_ptr() as *const Symbol, UniqueType::Any);
+                    .insert(symbol.as_ptr() as *const Symbol, ty);
             }
             StmtKind::FnDeclaration {
                 ident: _,
+                type_params,
                 params,
+                ret_ty,
                 body,
+                doc: _,
             } => {
                 // NOTE: walking is not enabled for this case
 
+                self.level += 1;
+                let outer_type_params = self.type_params.len();
+                for ident in type_params {
+                    self.type_vars.push(TypeVar::Param {
+                        ident: ident.clone(),
+                        level: self.level,
+                    });
+                    let var = UniqueType::Var(self.type_vars.len() - 1);
+                    self.type_params.push((ident.clone(), var));
+                }
+                let ty = self.function_type(params, ret_ty);
+                // add type before checking body to allow for recursion
+                let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
+                self.symbol_type_table
+                    .insert(symbol.as_ptr() as *const Symbol, ty.clone());
+
+                self.type_check_function_body(&ty, ret_ty, body);
+                self.type_params.truncate(outer_type_params);
+                self.level -= 1;
+                // the types that are not constrained by the body can be different for every call
+                self.generalize(&ty);
+            }
+            StmtKind::StructDeclaration { ident, fields } => {
+                // register the struct before resolving the field types to allow for recursive structs
+                let struct_ty = Rc::new(StructType {
+                    ident: ident.clone(),
+                    fields: RefCell::new(Vec::new()),
+                });
+                self.named_types.insert(
+                    stmt as *const Stmt,
+                    UniqueType::Struct(Rc::clone(&struct_ty)),
+                );
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|field| {
+                        let ty = match &field.ty {
+                            Some(ty) => self.resolve_type(ty),
+                            None => UniqueType::Any,
+                        };
+                        (field.ident.clone(), ty)
+                    })
+                    .collect();
+                // the struct itself is a constructor function
+                let ctor_params = fields.iter().map(|(_, ty)| ty.clone()).collect();
+                *struct_ty.fields.borrow_mut() = fields;
+                let struct_ty = UniqueType::Struct(struct_ty);
+                let ctor_ty = UniqueType::Builtin(BuiltinType::Fn {
+                    params: ctor_params,
+                    ret: Box::new(struct_ty),
+                    variadic: false,
+                    optional: 0,
+                });
                 let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
+                self.symbol_type_table
+                    .insert(symbol.as_ptr() as *const Symbol, ctor_ty);
+            }
+            StmtKind::EnumDeclaration { ident, variants } => {
+                let variants = variants
+                    .iter()
+                    .map(|variant| {
+                        let field_tys = variant
+                            .fields
+                            .iter()
+                            .map(|field| match &field.ty {
+                                Some(ty) => self.resolve_type(ty),
+                                None => UniqueType::Any,
+                            })
+                            .collect();
+                        (variant.ident.clone(), field_tys)
+                    })
+                    .collect();
+                let enum_ty = UniqueType::Enum(Rc::new(EnumType {
+                    ident: ident.clone(),
+                    variants,
+                }));
+                self.named_types
+                    .insert(stmt as *const Stmt, enum_ty.clone());
+                // variants are accessed through the enum (e.g. `Shape.Circle`)
+                let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
+                self.symbol_type_table.insert(
+                    symbol.as_ptr() as *const Symbol,
+                    UniqueType::Type(Box::new(enum_ty)),
+                );
+            }
+            StmtKind::ClassDeclaration { ident, methods } => {
+                // NOTE: walking is not enabled for this case
 
-                let ty = UniqueType::Builtin(BuiltinType::Fn {
-                    params: vec![UniqueType::Any; params.len()],
-                    ret: Box::new(UniqueType::Any),
+                // the class itself is a constructor function with the same params as `init` (excluding `self`)
+                let init_params = methods
+                    .iter()
+                    .find_map(|method| match &method.kind {
+                        StmtKind::FnDeclaration { ident, params, .. } if ident == "init" => {
+                            Some(params.get(1..).unwrap_or_default())
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                // the types of the params are checked when `init` is called at runtime
+                let mut params = Vec::new();
+                let mut variadic = false;
+                let mut optional = 0;
+                for param in init_params {
+                    match &param.kind {
+                        StmtKind::FnParam { is_rest: true, .. } => {
+                            params.push(UniqueType::Builtin(BuiltinType::List(Box::new(
+                                UniqueType::Any,
+                            ))));
+                            variadic = true;
+                        }
+                        StmtKind::FnParam { default, .. } => {
+                            params.push(UniqueType::Any);
+                            if default.is_some() {
+                                optional += 1;
+                            }
+                        }
+                        _ => unreachable!("function params are always FnParam"),
+                    }
+                }
+                let class_ty = UniqueType::Class(Rc::new(ClassType {
+                    ident: ident.clone(),
+                }));
+                self.named_types
+                    .insert(stmt as *const Stmt, class_ty.clone());
+                let ctor_ty = UniqueType::Builtin(BuiltinType::Fn {
+                    params,
+                    ret: Box::new(class_ty),
+                    variadic,
+                    optional,
                 });
+                let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
                 self.symbol_type_table
-                    .insert(symbol.as_ptr() as *const Symbol, ty);
+                    .insert(symbol.as_ptr() as *const Symbol, ctor_ty);
 
-                for param in params {
-                    self.visit_stmt(param);
+                for method in methods {
+                    self.visit_stmt(method);
+                    match &method.kind {
+                        StmtKind::FnDeclaration { ident, body, .. } if ident == "init" => {
+                            let mut collector = ReturnValueCollector::default();
+                            for stmt in body {
+                                collector.visit_stmt(stmt);
+                            }
+                            for expr in collector.return_values {
+                                self.source.errors.add_error(
+                                    SyntaxError::new(
+                                        "can not return a value from init",
+                                        expr.span.clone(),
+                                    )
+                                    .with_help("init always returns the new instance of the class"),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
                 }
-                for stmt in body {
+            }
+            StmtKind::ImportDeclaration { .. } => {
+                // modules are not values, their members are accessed with field access expressions
+                if let Some(symbol) = self.resolve_result.lookup_declaration(stmt) {
+                    self.symbol_type_table.insert(
+                        symbol.as_ptr() as *const Symbol,
+                        UniqueType::Builtin(BuiltinType::Unit),
+                    );
+                } // otherwise the module could not be found
+            }
+            StmtKind::TryStmt {
+                try_block,
+                binding,
+                catch_block,
+            } => {
+                for stmt in try_block {
+                    self.visit_stmt(stmt);
+                }
+                // any value can be thrown so the type of the caught value is only known at runtime
+                let symbol = self.resolve_result.lookup_declaration(binding).unwrap();
+                self.symbol_type_table
+                    .insert(symbol.as_ptr() as *const Symbol, UniqueType::Any);
+                for stmt in catch_block {
                     self.visit_stmt(stmt);
                 }
-                // FIXME give function proper type
+            }
+            StmtKind::ReturnStmt(expr) => {
+                // top level return is a runtime error
+                if let Some(function) = self.functions.last_mut() {
+                    let return_ty = function.ret.clone();
+                    match expr {
+                        Some(_) => function.has_return_value = true,
+                        None => function.has_bare_return = true,
+                    }
+                    let is_mixed = function.is_inferred
+                        && function.has_return_value
+                        && function.has_bare_return;
+                    if let (Some(expr), UniqueType::Var(var)) = (expr, self.substitute(&return_ty))
+                    {
+                        if self.expr_type(expr) == UniqueType::Any {
+                            // returning a value with a runtime type makes the return type a runtime type
+                            self.type_vars[var] = TypeVar::Bound(UniqueType::Any);
+                        }
+                    }
+                    let (ty, span) = match expr {
+                        Some(expr) => (self.expr_type(expr), expr.span.clone()),
+                        None => (UniqueType::Builtin(BuiltinType::Unit), stmt.span.clone()),
+                    };
+                    if !self.unify(&ty, &return_ty) {
+                        if is_mixed {
+                            self.source.errors.add_error(
+                                SyntaxError::new("function returns both a value and no value", span)
+                                    .with_help("annotate the return type with `any` to mix `return;` and `return x;`"),
+                            );
+                        } else {
+                            self.source
+                                .errors
+                                .add_error(SyntaxError::new("return value has wrong type", span));
+                        }
+                    }
+                }
             }
             _ => {}
         }
@@ -12,6 +12,8 @@ use console::style;
 pub struct Source<'a> {
     /// Original source code.
     pub content: &'a str,
+    /// Name of the file that the source code was read from. Used for error reporting.
+    filename: &'a str,
     lines: Vec<usize>,
     /// Accumulated errors.
     pub errors: Rc<ErrorReporter>,
@@ -22,14 +24,21 @@ impl<'a> Source<'a> {
     pub fn new(content: &'a str) -> Self {
         Self {
             content,
+            filename: "unknown",
             lines: get_newline_pos(content),
             errors: Rc::new(ErrorReporter::new()),
         }
     }
 
+    /// Sets the name of the file that the source code was read from.
+    pub fn with_filename(mut self, filename: &'a str) -> Self {
+        self.filename = filename;
+        self
+    }
+
     /// Returns `true` if `Source` has no accumulated errors. Returns `false` otherwise.
     pub fn has_no_errors(&self) -> bool {
-        self.errors.errors.borrow().len() == 0
+        self.errors.errors.borrow().is_empty()
     }
 
     /// Returns the line which the `pos` is located at.
@@ -79,9 +88,9 @@ fn get_newline_pos(src: &str) -> Vec<usize> {
     pos
 }
 
-impl<'a> Into<Source<'a>> for &'a str {
-    fn into(self) -> Source<'a> {
-        Source::new(self)
+impl<'a> From<&'a str> for Source<'a> {
+    fn from(val: &'a str) -> Self {
+        Source::new(val)
     }
 }
 
@@ -146,19 +155,13 @@ impl<'a> fmt::Display for Source<'a> {
 
             writeln!(
                 f,
-                "{error}{message}\n{filename}",
+                "{error}{message}\n   {arrow} {filename}:{line}:{col}",
                 error = style("error").red().bright().bold(),
                 message = style(format!(": {errMessage}", errMessage = error.message,)).bold(),
-                filename = format!(
-                    "   {arrow} {filename}",
-                    arrow = style("-->").cyan().bright().bold(),
-                    filename = format!(
-                        "{filename}:{line}:{col}",
-                        filename = "unknown", // FIXME
-                        line = start.0 + 1,   // +1 for 1-based line position
-                        col = start.1 + 1,    // +1 for 1-based column position
-                    )
-                ),
+                arrow = style("-->").cyan().bright().bold(),
+                filename = self.filename,
+                line = start.0 + 1, // +1 for 1-based line position
+                col = start.1 + 1,  // +1 for 1-based column position
             )?;
             if start.0 == end.0 {
                 let is_0_width = start.1 == end.1;
@@ -44,16 +44,44 @@ pub enum OpCode {
     /// Closes an upvalue.
     /// *1 byte*
     CloseUpVal = 20,
+    /// Closes the upvalues that capture the local variable at the specified slot (operand) without popping it.
+    /// Used before a hoisted variable is declared again (e.g. in a loop) so that closures keep the previous value.
+    /// *2 bytes (1 operand)*
+    CloseUpValAt = 60,
     /// Negate the last value on the stack.
     /// *1 byte*
     Neg = 1,
     /// Logical not on a boolean value.
     /// *1 byte*
     Not = 2,
+    /// Bitwise not on an integer value.
+    /// *1 byte*
+    BitNot = 53,
     Add = 3,
     Sub = 4,
     Mul = 5,
     Div = 6,
+    /// Remainder of the division of two numbers. The result has the same sign as the dividend.
+    /// *1 byte*
+    Rem = 46,
+    /// Raises the first number to the power of the second number.
+    /// *1 byte*
+    Pow = 47,
+    /// Bitwise and. Operands must be integers.
+    /// *1 byte*
+    BitAnd = 48,
+    /// Bitwise or. Operands must be integers.
+    /// *1 byte*
+    BitOr = 49,
+    /// Bitwise exclusive or. Operands must be integers.
+    /// *1 byte*
+    BitXor = 50,
+    /// Shifts the first integer to the left by the second integer.
+    /// *1 byte*
+    Shl = 51,
+    /// Shifts the first integer to the right (arithmetic shift) by the second integer.
+    /// *1 byte*
+    Shr = 52,
     /// Returns the last value on the stack.
     /// *1 byte*
     Ret = 7,
@@ -63,6 +91,9 @@ pub enum OpCode {
     /// Return the constant 1.
     /// *1 byte*
     Ret1 = 30,
+    /// Loads the unit value onto the stack.
+    /// *1 byte*
+    LdUnit = 54,
     /// Loads `true` onto the stack.
     /// *1 byte*
     LdTrue = 8,
@@ -78,8 +109,13 @@ pub enum OpCode {
     /// Calls the function on the top of the stack.
     /// To load the function, use `ldc` to load a function object.
     /// Arity is the operand.
-    /// *2 bytes (1 operand)*
+    /// *3 bytes (1 u16 operand)*
     Calli = 14,
+    /// Reorders the top values on the stack. Used to evaluate named arguments in source order and pass them in parameter order.
+    /// The number of values is the first operand. It is followed by an operand for every value:
+    /// the index (from the lowest of the values) of the value that is moved to that position.
+    /// *2 + n bytes (1 + n operands)*
+    Reorder = 61,
     /// Creates a closure with a constant function and pushes it onto the stack.
     /// *Variable number of operands*
     Closure = 19,
@@ -94,6 +130,84 @@ pub enum OpCode {
     /// Jump backwards with the specified offset.
     /// *2 bytes (1 u16 operand)*
     Loop = 23,
+    /// Pops the specified number of values and pushes a new list containing them (in order).
+    /// The number of elements is the operand.
+    /// *3 bytes (1 u16 operand)*
+    BuildList = 31,
+    /// Pops an index (or key) and a list (or map) and pushes the element at the index.
+    /// *1 byte*
+    LdIndex = 32,
+    /// Pops a value, an index (or key) and a list (or map) and stores the value at the index.
+    /// The value is pushed back onto the stack (result of assignment).
+    /// *1 byte*
+    StIndex = 33,
+    /// Duplicates the top two values on the stack.
+    /// *1 byte*
+    Dup2 = 34,
+    /// Pops the specified number of key-value pairs and pushes a new map containing them.
+    /// Keys and values are interleaved with the key first.
+    /// The number of entries is the operand.
+    /// *3 bytes (1 u16 operand)*
+    BuildMap = 35,
+    /// Pops a struct instance and pushes the value of the field.
+    /// The name of the field is a constant (operand).
+    /// *2 bytes (1 operand)*
+    LdProp = 36,
+    /// Pops a value and a struct instance and stores the value in the field.
+    /// The name of the field is a constant (operand).
+    /// The value is pushed back onto the stack (result of assignment).
+    /// *2 bytes (1 operand)*
+    StProp = 37,
+    /// Duplicates the top value on the stack.
+    /// *1 byte*
+    Dup = 38,
+    /// Creates a new class without any methods and pushes it onto the stack.
+    /// The name of the class is a constant (operand).
+    /// *2 bytes (1 operand)*
+    Class = 39,
+    /// Pops a closure and adds it as a method to the class on the top of the stack.
+    /// The name of the method is a constant (operand).
+    /// *2 bytes (1 operand)*
+    Method = 40,
+    /// Calls a method (or a callable field) on a receiver.
+    /// The receiver is below the arguments on the stack.
+    /// The name of the method is a constant (first operand). The number of arguments is the second operand (u16).
+    /// *4 bytes (2 operands)*
+    Invoke = 41,
+    /// Pops an enum and a value and pushes `true` if the value is the variant of the enum with the specified tag (operand).
+    /// Pushes `false` otherwise.
+    /// *2 bytes (1 operand)*
+    TestVariant = 42,
+    /// Pops an enum value and pushes the value of the payload field at the specified index (operand).
+    /// *2 bytes (1 operand)*
+    LdVariantField = 43,
+    /// Stops execution with a runtime error.
+    /// The error message is a constant (operand).
+    /// *2 bytes (1 operand)*
+    Abort = 44,
+    /// Registers an exception handler in the current call frame.
+    /// If a value is thrown before the matching `pop_handler`, the stack is unwound to its current height,
+    /// the thrown value is pushed and execution continues at the specified offset.
+    /// *3 bytes (1 u16 operand)*
+    PushHandler = 55,
+    /// Removes the innermost exception handler of the current call frame.
+    /// *1 byte*
+    PopHandler = 56,
+    /// Pops a value and throws it (see [`OpCode::PushHandler`]).
+    /// *1 byte*
+    Throw = 57,
+    /// Pops the specified number of values and pushes a new tuple containing them (in order).
+    /// The number of elements is the operand.
+    /// *3 bytes (1 u16 operand)*
+    BuildTuple = 58,
+    /// Pops a tuple and pushes its elements (in order).
+    /// The expected number of elements is the operand. It is a runtime error if the tuple has a different number of elements.
+    /// *3 bytes (1 u16 operand)*
+    UnpackTuple = 59,
+    /// Pops the specified number of values and pushes a new string with the values converted to strings and concatenated.
+    /// The number of values is the operand.
+    /// *3 bytes (1 u16 operand)*
+    Concat = 45,
 }
 
 /// Represents a chunk of bytecode.
@@ -173,7 +287,7 @@ impl Chunk {
         self.code.push(opcode.to_byte_code());
         self.lines.push(line);
         debug_assert_eq!(self.code.len(), self.lines.len());
-        return self.code.len() - 1; // -1 to include the effect of adding the byte to self.code
+        self.code.len() - 1 // -1 to include the effect of adding the byte to self.code
     }
 
     /// Patches a `jmp` or `jmp_if_false` instruction to jump to current position.
@@ -181,7 +295,7 @@ impl Chunk {
         // -2 to adjust for the bytecode for the jump itself.
         let jump = self.code.len() - offset - 2;
 
-        if jump > std::u16::MAX as usize {
+        if jump > u16::MAX as usize {
             panic!("cannot jump more than std::u16::MAX");
         }
 
@@ -67,7 +67,7 @@ impl Chunk {
         Ok(offset + 9)
     }
 
-    /// Disassemble `calli` (2 bytes) instruction.
+    /// Disassemble `test_variant` and `ldvariant_field` (2 bytes) instruction.
     fn calli_instr(
         &self,
         f: &mut fmt::Formatter<'_>,
@@ -80,6 +80,25 @@ impl Chunk {
         Ok(offset + 2)
     }
 
+    /// Disassemble `invoke` (4 bytes) instruction.
+    fn invoke_instr(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        name: &str,
+        offset: usize,
+        msg: &str,
+    ) -> Result<usize, fmt::Error> {
+        let constant_index = self.code[offset + 1];
+        let constant = self.constants[constant_index as usize].clone();
+        let arity: u16 = (self.code[offset + 2] as u16) << 8 | self.code[offset + 3] as u16;
+        writeln!(
+            f,
+            "{:<10} {:<3} (value = {}) {} {}",
+            name, constant_index, constant, arity, msg
+        )?;
+        Ok(offset + 4)
+    }
+
     /// Disassemble `closure` (variable operands) instruction.
     fn closure_instr(
         &self,
@@ -122,6 +141,23 @@ impl Chunk {
         Ok(offset)
     }
 
+    /// Disassemble `reorder` (variable operands) instruction.
+    fn reorder_instr(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        name: &str,
+        offset: usize,
+        msg: &str,
+    ) -> Result<usize, fmt::Error> {
+        let len = self.code[offset + 1] as usize;
+        let indices: Vec<String> = self.code[offset + 2..offset + 2 + len]
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        writeln!(f, "{:<10} {} [{}] {}", name, len, indices.join(", "), msg)?;
+        Ok(offset + 2 + len)
+    }
+
     /// Disassembles `jmp` and `jmp_if_false` and `loop` (3 bytes) instruction.
     fn jmp_instr(
         &self,
@@ -135,6 +171,19 @@ impl Chunk {
         Ok(offset + 3)
     }
 
+    /// Disassembles `calli`, `build_list`, `build_map`, `build_tuple`, `unpack_tuple` and `concat` (3 bytes) instruction.
+    fn build_instr(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        name: &str,
+        offset: usize,
+        msg: &str,
+    ) -> Result<usize, fmt::Error> {
+        let len: u16 = (self.code[offset + 1] as u16) << 8 | self.code[offset + 2] as u16;
+        writeln!(f, "{:<10} {} {}", name, len, msg)?;
+        Ok(offset + 3)
+    }
+
     /// Disassembles the instruction at the given `offset`.
     fn disassemble_instr(
         &self,
@@ -176,26 +225,57 @@ impl Chunk {
             Some(OpCode::LdUpVal) => self.ld_or_st_instr(f, "ldupval", offset, msg),
             Some(OpCode::StUpVal) => self.ld_or_st_instr(f, "stupval", offset, msg),
             Some(OpCode::CloseUpVal) => self.simple_instr(f, "closeupval", offset, msg),
+            Some(OpCode::CloseUpValAt) => self.ld_or_st_instr(f, "closeupval_at", offset, msg),
             Some(OpCode::Neg) => self.simple_instr(f, "neg", offset, msg),
             Some(OpCode::Not) => self.simple_instr(f, "not", offset, msg),
             Some(OpCode::Add) => self.simple_instr(f, "add", offset, msg),
             Some(OpCode::Sub) => self.simple_instr(f, "sub", offset, msg),
             Some(OpCode::Mul) => self.simple_instr(f, "mul", offset, msg),
             Some(OpCode::Div) => self.simple_instr(f, "div", offset, msg),
+            Some(OpCode::Rem) => self.simple_instr(f, "rem", offset, msg),
+            Some(OpCode::Pow) => self.simple_instr(f, "pow", offset, msg),
+            Some(OpCode::BitAnd) => self.simple_instr(f, "bit_and", offset, msg),
+            Some(OpCode::BitOr) => self.simple_instr(f, "bit_or", offset, msg),
+            Some(OpCode::BitXor) => self.simple_instr(f, "bit_xor", offset, msg),
+            Some(OpCode::Shl) => self.simple_instr(f, "shl", offset, msg),
+            Some(OpCode::Shr) => self.simple_instr(f, "shr", offset, msg),
+            Some(OpCode::BitNot) => self.simple_instr(f, "bit_not", offset, msg),
             Some(OpCode::Ret) => self.simple_instr(f, "ret", offset, msg),
             Some(OpCode::Ret0) => self.simple_instr(f, "ret0", offset, msg),
             Some(OpCode::Ret1) => self.simple_instr(f, "ret1", offset, msg),
+            Some(OpCode::LdUnit) => self.simple_instr(f, "ld_unit", offset, msg),
             Some(OpCode::LdTrue) => self.simple_instr(f, "ld_true", offset, msg),
             Some(OpCode::LdFalse) => self.simple_instr(f, "ld_false", offset, msg),
             Some(OpCode::Eq) => self.simple_instr(f, "eq", offset, msg),
             Some(OpCode::Greater) => self.simple_instr(f, "greater", offset, msg),
             Some(OpCode::Less) => self.simple_instr(f, "less", offset, msg),
             Some(OpCode::Pop) => self.simple_instr(f, "pop", offset, msg),
-            Some(OpCode::Calli) => self.calli_instr(f, "calli", offset, msg),
+            Some(OpCode::Calli) => self.build_instr(f, "calli", offset, msg),
+            Some(OpCode::Reorder) => self.reorder_instr(f, "reorder", offset, msg),
             Some(OpCode::Closure) => self.closure_instr(f, "closure", offset, msg),
             Some(OpCode::Jmp) => self.jmp_instr(f, "jmp", offset, msg),
             Some(OpCode::JmpIfFalse) => self.jmp_instr(f, "jmp_if_false", offset, msg),
             Some(OpCode::Loop) => self.jmp_instr(f, "loop", offset, msg),
+            Some(OpCode::BuildList) => self.build_instr(f, "build_list", offset, msg),
+            Some(OpCode::LdIndex) => self.simple_instr(f, "ldindex", offset, msg),
+            Some(OpCode::StIndex) => self.simple_instr(f, "stindex", offset, msg),
+            Some(OpCode::Dup2) => self.simple_instr(f, "dup2", offset, msg),
+            Some(OpCode::BuildMap) => self.build_instr(f, "build_map", offset, msg),
+            Some(OpCode::LdProp) => self.constant_instr(f, "ldprop", offset, msg),
+            Some(OpCode::StProp) => self.constant_instr(f, "stprop", offset, msg),
+            Some(OpCode::Dup) => self.simple_instr(f, "dup", offset, msg),
+            Some(OpCode::Class) => self.constant_instr(f, "class", offset, msg),
+            Some(OpCode::Method) => self.constant_instr(f, "method", offset, msg),
+            Some(OpCode::Invoke) => self.invoke_instr(f, "invoke", offset, msg),
+            Some(OpCode::TestVariant) => self.calli_instr(f, "test_variant", offset, msg),
+            Some(OpCode::LdVariantField) => self.calli_instr(f, "ldvariant_field", offset, msg),
+            Some(OpCode::Abort) => self.constant_instr(f, "abort", offset, msg),
+            Some(OpCode::PushHandler) => self.jmp_instr(f, "push_handler", offset, msg),
+            Some(OpCode::PopHandler) => self.simple_instr(f, "pop_handler", offset, msg),
+            Some(OpCode::Throw) => self.simple_instr(f, "throw", offset, msg),
+            Some(OpCode::BuildTuple) => self.build_instr(f, "build_tuple", offset, msg),
+            Some(OpCode::UnpackTuple) => self.build_instr(f, "unpack_tuple", offset, msg),
+            Some(OpCode::Concat) => self.build_instr(f, "concat", offset, msg),
             None => self.simple_instr(f, "invalid", offset, msg), // skip bad instruction
         } // returns the next ip
     }
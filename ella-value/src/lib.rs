@@ -8,7 +8,10 @@ pub mod chunk;
 pub mod disassemble;
 pub mod object;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 use object::{Closure, Function, NativeFn, Obj, ObjKind};
@@ -17,6 +20,9 @@ use object::{Closure, Function, NativeFn, Obj, ObjKind};
 #[derive(Default)]
 pub struct BuiltinVars {
     pub values: Vec<(String, Value, UniqueType)>,
+    /// The type parameters of the generic builtin symbols, keyed by the identifier of the symbol.
+    /// [`UniqueType::Var`]`(i)` in the type of the symbol stands for the `i`-th type parameter.
+    pub type_params: HashMap<String, Vec<String>>,
 }
 
 impl BuiltinVars {
@@ -40,6 +46,44 @@ impl BuiltinVars {
         let obj = Value::Object(Rc::new(Obj {
             kind: ObjKind::NativeFn(NativeFn {
                 arity,
+                variadic: false,
+                func,
+                ident: ident.to_string(),
+            }),
+        }));
+        self.add_value(ident.to_string(), obj, ty);
+    }
+
+    /// Add a generic builtin native function. `ty` refers to the type parameters `type_params`
+    /// by their index (e.g. `UniqueType::Var(0)` for the first type parameter).
+    pub fn add_generic_native_fn(
+        &mut self,
+        ident: impl ToString,
+        func: &'static dyn Fn(&mut [Value]) -> Value,
+        arity: u32,
+        type_params: &[&str],
+        ty: UniqueType,
+    ) {
+        self.type_params.insert(
+            ident.to_string(),
+            type_params.iter().map(|param| param.to_string()).collect(),
+        );
+        self.add_native_fn(ident, func, arity, ty);
+    }
+
+    /// Add a builtin native function that accepts `min_arity` or more arguments.
+    /// All the arguments are passed to `func`. `ty` should be a variadic [`BuiltinType::Fn`].
+    pub fn add_variadic_native_fn(
+        &mut self,
+        ident: impl ToString,
+        func: &'static dyn Fn(&mut [Value]) -> Value,
+        min_arity: u32,
+        ty: UniqueType,
+    ) {
+        let obj = Value::Object(Rc::new(Obj {
+            kind: ObjKind::NativeFn(NativeFn {
+                arity: min_arity,
+                variadic: true,
                 func,
                 ident: ident.to_string(),
             }),
@@ -51,19 +95,42 @@ impl BuiltinVars {
 /// Represents a builtin type.
 #[derive(Debug, Clone, PartialEq)]
 pub enum BuiltinType {
+    /// The type of the unit value `()`. Returned by functions without a return value.
+    Unit,
     Bool,
     Number,
     String,
     Fn {
         params: Vec<UniqueType>,
         ret: Box<UniqueType>,
+        /// `true` if the last param is a rest param. The type of a rest param is a list of the extra arguments.
+        variadic: bool,
+        /// The number of params (before the rest param) that have a default value and can be omitted.
+        optional: usize,
     },
+    /// A list with elements of the inner type.
+    List(Box<UniqueType>),
+    /// A map from the first type (key) to the second type (value).
+    Map(Box<UniqueType>, Box<UniqueType>),
+    /// A tuple with elements of the inner types (e.g. `(number, string)`).
+    Tuple(Vec<UniqueType>),
 }
 
 /// Represents an unique type.
 #[derive(Debug, Clone, PartialEq)]
 pub enum UniqueType {
     Builtin(BuiltinType),
+    /// A user defined struct type.
+    Struct(Rc<StructType>),
+    /// A user defined enum type.
+    Enum(Rc<EnumType>),
+    /// The type of the instances of a user defined class.
+    Class(Rc<ClassType>),
+    /// The type of a type name used as a value (e.g. `Shape` in `Shape.Circle(1)`).
+    Type(Box<UniqueType>),
+    /// A type variable that stands for a type that is inferred by the type checker.
+    /// The `usize` is an index into the type checker's table of type variables.
+    Var(usize),
     /// Runtime type.
     Any,
     /// Error case.
@@ -77,20 +144,244 @@ impl UniqueType {
     /// use ella_value::{BuiltinType, UniqueType};
     /// // Can always cast to same type (string -> string).
     /// assert!(UniqueType::from(BuiltinType::String).can_implicit_cast_to(&BuiltinType::String.into()));
-    /// 
+    ///
     /// // Any and unknown can accept any type.
     /// assert!(UniqueType::from(BuiltinType::Number).can_implicit_cast_to(&UniqueType::Any));
     /// assert!(UniqueType::from(BuiltinType::Number).can_implicit_cast_to(&UniqueType::Unknown));
+    ///
+    /// // Lists can be cast if the element types can be cast.
+    /// let number_list = UniqueType::from(BuiltinType::List(Box::new(BuiltinType::Number.into())));
+    /// let any_list = UniqueType::from(BuiltinType::List(Box::new(UniqueType::Any)));
+    /// assert!(number_list.can_implicit_cast_to(&any_list));
+    /// assert!(!number_list.can_implicit_cast_to(&BuiltinType::List(Box::new(BuiltinType::Bool.into())).into()));
+    ///
+    /// // Same for maps (both keys and values).
+    /// let string_number_map = UniqueType::from(BuiltinType::Map(
+    ///     Box::new(BuiltinType::String.into()),
+    ///     Box::new(BuiltinType::Number.into()),
+    /// ));
+    /// let any_map = UniqueType::from(BuiltinType::Map(Box::new(UniqueType::Any), Box::new(UniqueType::Any)));
+    /// assert!(string_number_map.can_implicit_cast_to(&any_map));
+    /// assert!(!any_map.can_implicit_cast_to(&string_number_map));
+    ///
+    /// // Tuples can be cast if they have the same number of elements and every element can be cast.
+    /// let number_string_tuple = UniqueType::from(BuiltinType::Tuple(vec![
+    ///     BuiltinType::Number.into(),
+    ///     BuiltinType::String.into(),
+    /// ]));
+    /// let any_tuple = UniqueType::from(BuiltinType::Tuple(vec![UniqueType::Any, UniqueType::Any]));
+    /// assert!(number_string_tuple.can_implicit_cast_to(&any_tuple));
+    /// assert!(!number_string_tuple.can_implicit_cast_to(&BuiltinType::Tuple(vec![UniqueType::Any]).into()));
+    ///
+    /// // Functions can be cast if they accept the params and return a value that can be cast.
+    /// let any_to_number = UniqueType::from(BuiltinType::Fn {
+    ///     params: vec![UniqueType::Any],
+    ///     ret: Box::new(BuiltinType::Number.into()),
+    ///     variadic: false,
+    ///     optional: 0,
+    /// });
+    /// let number_to_any = UniqueType::from(BuiltinType::Fn {
+    ///     params: vec![BuiltinType::Number.into()],
+    ///     ret: Box::new(UniqueType::Any),
+    ///     variadic: false,
+    ///     optional: 0,
+    /// });
+    /// assert!(any_to_number.can_implicit_cast_to(&number_to_any));
+    /// assert!(!number_to_any.can_implicit_cast_to(&any_to_number));
     /// ```
     pub fn can_implicit_cast_to(&self, other: &Self) -> bool {
         match other {
-            Self::Builtin(_) => self == other,
+            Self::Builtin(BuiltinType::List(other_elem)) => match self {
+                Self::Builtin(BuiltinType::List(elem)) => elem.can_implicit_cast_to(other_elem),
+                _ => false,
+            },
+            Self::Builtin(BuiltinType::Map(other_key, other_value)) => match self {
+                Self::Builtin(BuiltinType::Map(key, value)) => {
+                    key.can_implicit_cast_to(other_key) && value.can_implicit_cast_to(other_value)
+                }
+                _ => false,
+            },
+            Self::Builtin(BuiltinType::Tuple(other_elems)) => match self {
+                Self::Builtin(BuiltinType::Tuple(elems)) => {
+                    elems.len() == other_elems.len()
+                        && elems
+                            .iter()
+                            .zip(other_elems)
+                            .all(|(elem, other_elem)| elem.can_implicit_cast_to(other_elem))
+                }
+                _ => false,
+            },
+            Self::Builtin(BuiltinType::Fn {
+                params: other_params,
+                ret: other_ret,
+                variadic: other_variadic,
+                optional: other_optional,
+            }) => match self {
+                Self::Builtin(BuiltinType::Fn {
+                    params,
+                    ret,
+                    variadic,
+                    optional,
+                }) => {
+                    // a function with default values can be called with all the arguments
+                    variadic == other_variadic
+                        && optional >= other_optional
+                        && params.len() == other_params.len()
+                        && other_params
+                            .iter()
+                            .zip(params)
+                            .all(|(other_param, param)| other_param.can_implicit_cast_to(param))
+                        && ret.can_implicit_cast_to(other_ret)
+                }
+                _ => false,
+            },
+            Self::Builtin(_)
+            | Self::Struct(_)
+            | Self::Enum(_)
+            | Self::Class(_)
+            | Self::Type(_)
+            | Self::Var(_) => self == other,
             Self::Any => true,
             Self::Unknown => true,
         }
     }
 }
 
+/// Formats types the same way as type annotations (e.g. `fn(number) -> List<string>`).
+impl fmt::Display for BuiltinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuiltinType::Unit => write!(f, "()"),
+            BuiltinType::Bool => write!(f, "bool"),
+            BuiltinType::Number => write!(f, "number"),
+            BuiltinType::String => write!(f, "string"),
+            BuiltinType::Fn {
+                params,
+                ret,
+                variadic,
+                optional: _,
+            } => {
+                write!(f, "fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if *variadic && i == params.len() - 1 {
+                        write!(f, "...")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", ret)
+            }
+            BuiltinType::List(elem) => write!(f, "List<{}>", elem),
+            BuiltinType::Map(key, value) => write!(f, "Map<{}, {}>", key, value),
+            BuiltinType::Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", elem)?;
+                }
+                if elems.len() == 1 {
+                    write!(f, ",")?; // distinguish from a parenthesized type
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for UniqueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniqueType::Builtin(builtin) => write!(f, "{}", builtin),
+            UniqueType::Struct(struct_ty) => write!(f, "{}", struct_ty.ident),
+            UniqueType::Enum(enum_ty) => write!(f, "{}", enum_ty.ident),
+            UniqueType::Class(class_ty) => write!(f, "{}", class_ty.ident),
+            UniqueType::Type(ty) => write!(f, "type {}", ty),
+            // the type has not been inferred yet
+            UniqueType::Var(_) => write!(f, "_"),
+            UniqueType::Any => write!(f, "any"),
+            UniqueType::Unknown => write!(f, "{{unknown}}"),
+        }
+    }
+}
+
+/// Represents a user defined struct type.
+/// Struct types are nominal: two struct types are only equal if they come from the same declaration.
+pub struct StructType {
+    pub ident: String,
+    /// The fields are set after the struct type is created so that their types can refer to the struct itself
+    /// (e.g. `struct Node { children: List<Node> }`).
+    pub fields: RefCell<Vec<(String, UniqueType)>>,
+}
+
+impl StructType {
+    /// Returns the type of the field named `ident` or `None` if there is no such field.
+    pub fn field_type(&self, ident: &str) -> Option<UniqueType> {
+        self.fields
+            .borrow()
+            .iter()
+            .find(|(field, _)| field == ident)
+            .map(|(_, ty)| ty.clone())
+    }
+}
+
+/// The fields are not printed as they can refer to the struct itself.
+impl fmt::Debug for StructType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructType")
+            .field("ident", &self.ident)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for StructType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// Represents a user defined enum type.
+/// Like struct types, enum types are nominal.
+#[derive(Debug)]
+pub struct EnumType {
+    pub ident: String,
+    /// The variants in declaration order with the types of their payload fields.
+    pub variants: Vec<(String, Vec<UniqueType>)>,
+}
+
+impl EnumType {
+    /// Returns the types of the payload fields of the variant named `ident` or `None` if there is no such variant.
+    pub fn variant_fields(&self, ident: &str) -> Option<&[UniqueType]> {
+        self.variants
+            .iter()
+            .find(|(variant, _)| variant == ident)
+            .map(|(_, fields)| fields.as_slice())
+    }
+}
+
+impl PartialEq for EnumType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// Represents the type of the instances of a user defined class.
+/// Like struct types, class types are nominal.
+/// Fields of class instances are created when first assigned so they are not part of the type.
+#[derive(Debug)]
+pub struct ClassType {
+    pub ident: String,
+}
+
+impl PartialEq for ClassType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
 impl From<BuiltinType> for UniqueType {
     fn from(ty: BuiltinType) -> Self {
         Self::Builtin(ty)
@@ -100,6 +391,8 @@ impl From<BuiltinType> for UniqueType {
 /// Represents a runtime value. The [`Value::Object`] variant holds a [`Rc`] to the heap.
 #[derive(Clone, PartialEq, PartialOrd)]
 pub enum Value {
+    /// The unit value. Functions without a `return` statement (or with a bare `return;`) produce this value.
+    Unit,
     Number(f64),
     Bool(bool),
     Object(Rc<object::Obj>),
@@ -110,13 +403,118 @@ impl Value {
     pub fn cast_to_str(&self) -> Option<&str> {
         match self {
             Self::Object(obj) => match &obj.kind {
-                object::ObjKind::Str(string) => Some(&string),
+                object::ObjKind::Str(string) => Some(string),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&RefCell<Vec<Value>>` or `None` if wrong type.
+    pub fn cast_to_list(&self) -> Option<&RefCell<Vec<Value>>> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::List(values) => Some(values),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&[Value]` (the elements of a tuple) or `None` if wrong type.
+    pub fn cast_to_tuple(&self) -> Option<&[Value]> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::Tuple(values) => Some(values),
                 _ => None,
             },
             _ => None,
         }
     }
 
+    /// Attempts to cast the [`Value`] into a `&RefCell<MapEntries>` or `None` if wrong type.
+    pub fn cast_to_map(&self) -> Option<&RefCell<object::MapEntries>> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::Map(entries) => Some(entries),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&Instance` or `None` if wrong type.
+    pub fn cast_to_instance(&self) -> Option<&object::Instance> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::Instance(instance) => Some(instance),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&ClassInstance` or `None` if wrong type.
+    pub fn cast_to_class_instance(&self) -> Option<&object::ClassInstance> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::ClassInstance(instance) => Some(instance),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&Rc<Enum>` or `None` if wrong type.
+    pub fn cast_to_enum(&self) -> Option<&Rc<object::Enum>> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::Enum(enum_def) => Some(enum_def),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Attempts to cast the [`Value`] into a `&EnumValue` or `None` if wrong type.
+    pub fn cast_to_enum_value(&self) -> Option<&object::EnumValue> {
+        match self {
+            Self::Object(obj) => match &obj.kind {
+                object::ObjKind::EnumValue(enum_value) => Some(enum_value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the value can be used as a map key.
+    /// Lists and maps are mutable and therefore not hashable.
+    /// `NaN` is not hashable because it is never equal to itself and could never be found again.
+    /// Enum values and tuples are hashable if all the values in their payload (or elements) are hashable.
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use ella_value::object::Obj;
+    /// use ella_value::Value;
+    ///
+    /// assert!(Value::Number(1.0).is_hashable());
+    /// assert!(!Value::Number(f64::NAN).is_hashable());
+    /// assert!(Value::Object(Rc::new(Obj::new_string("key".to_string()))).is_hashable());
+    /// assert!(!Value::Object(Rc::new(Obj::new_list(Vec::new()))).is_hashable());
+    /// ```
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Self::Unit | Self::Bool(_) => true,
+            Self::Number(val) => !val.is_nan(),
+            Self::Object(obj) => match &obj.kind {
+                ObjKind::List(_) | ObjKind::Map(_) => false,
+                ObjKind::EnumValue(enum_value) => enum_value.values.iter().all(Value::is_hashable),
+                ObjKind::Tuple(values) => values.iter().all(Value::is_hashable),
+                _ => true,
+            },
+        }
+    }
+
     /// Attempts to cast the [`Value`] into a `f64` or `None` if wrong type.
     pub fn cast_to_number(&self) -> Option<f64> {
         match self {
@@ -125,23 +523,174 @@ impl Value {
         }
     }
 
-    /// Prints the object with the specified [`fmt::Formatter`].
-    fn print_obj(f: &mut fmt::Formatter<'_>, obj: &object::Obj) -> fmt::Result {
+    /// Prints the value with the specified [`fmt::Formatter`].
+    /// `printing` contains the lists, maps and instances that are currently being printed.
+    /// They are printed as `[...]`, `{...}` or `Name {...}` when they contain themselves.
+    fn print(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        printing: &mut Vec<*const object::Obj>,
+    ) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Number(val) => write!(f, "{}", val),
+            Value::Bool(val) => write!(f, "{}", val),
+            Value::Object(val) => Self::print_obj(f, val, printing),
+        }
+    }
+
+    /// Prints the object with the specified [`fmt::Formatter`]. See [`Self::print`].
+    fn print_obj(
+        f: &mut fmt::Formatter<'_>,
+        obj: &object::Obj,
+        printing: &mut Vec<*const object::Obj>,
+    ) -> fmt::Result {
+        let is_container = matches!(
+            obj.kind,
+            ObjKind::List(_) | ObjKind::Map(_) | ObjKind::Instance(_)
+        );
+        if is_container && printing.contains(&(obj as *const object::Obj)) {
+            return match &obj.kind {
+                ObjKind::List(_) => write!(f, "[...]"),
+                ObjKind::Instance(instance) => write!(f, "{} {{...}}", instance.class.ident),
+                _ => write!(f, "{{...}}"),
+            };
+        }
+        if is_container {
+            printing.push(obj);
+        }
+        let result = Self::print_obj_contents(f, obj, printing);
+        if is_container {
+            printing.pop();
+        }
+        result
+    }
+
+    /// Prints the object and the values it contains. See [`Self::print_obj`].
+    fn print_obj_contents(
+        f: &mut fmt::Formatter<'_>,
+        obj: &object::Obj,
+        printing: &mut Vec<*const object::Obj>,
+    ) -> fmt::Result {
         match &obj.kind {
             ObjKind::Str(str) => write!(f, "{}", str),
             ObjKind::Fn(Function { ident, .. }) => write!(f, "<fn {}>", ident),
             ObjKind::Closure(Closure { func, .. }) => write!(f, "<fn closure {}>", func.ident),
             ObjKind::NativeFn(object::NativeFn { ident, .. }) => write!(f, "<native fn {}>", ident),
+            ObjKind::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    value.print(f, printing)?;
+                }
+                write!(f, "]")
+            }
+            ObjKind::Tuple(values) => {
+                write!(f, "(")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    value.print(f, printing)?;
+                }
+                if values.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            ObjKind::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    key.print(f, printing)?;
+                    write!(f, ": ")?;
+                    value.print(f, printing)?;
+                }
+                write!(f, "}}")
+            }
+            ObjKind::Struct(class) => write!(f, "<struct {}>", class.ident),
+            ObjKind::Instance(object::Instance { class, fields }) => {
+                write!(f, "{} {{", class.ident)?;
+                let fields = fields.borrow();
+                for (i, field) in class.fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}: ", field)?;
+                    fields[field].print(f, printing)?;
+                }
+                write!(f, " }}")
+            }
+            ObjKind::Class(class) => write!(f, "<class {}>", class.ident),
+            ObjKind::ClassInstance(object::ClassInstance { class, .. }) => {
+                write!(f, "<{} instance>", class.ident)
+            }
+            ObjKind::BoundMethod(object::BoundMethod { method, .. }) => {
+                Self::print_obj(f, method, printing)
+            }
+            ObjKind::Enum(enum_def) => write!(f, "<enum {}>", enum_def.ident),
+            ObjKind::EnumValue(enum_value) => {
+                write!(
+                    f,
+                    "{}.{}",
+                    enum_value.enum_def.ident,
+                    enum_value.variant().ident
+                )?;
+                if !enum_value.values.is_empty() {
+                    write!(f, "(")?;
+                    for (i, value) in enum_value.values.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        value.print(f, printing)?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            ObjKind::VariantCtor(object::VariantCtor { enum_def, tag }) => write!(
+                f,
+                "<variant {}.{}>",
+                enum_def.ident, enum_def.variants[*tag].ident
+            ),
         }
     }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.print(f, &mut Vec::new())
+    }
+}
+
+/// `NaN` is never equal to itself and is therefore not hashable (see [`Value::is_hashable`]).
+impl Eq for Value {}
+
+/// Strings and enum values are hashed by content. Other objects are hashed by reference.
+/// Only values that are [`Value::is_hashable`] should be hashed.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
         match self {
-            Value::Number(val) => write!(f, "{}", val),
-            Value::Bool(val) => write!(f, "{}", val),
-            Value::Object(val) => Self::print_obj(f, val),
+            Value::Unit => {}
+            // 0.0 and -0.0 are equal and must have the same hash
+            Value::Number(val) if *val == 0.0 => 0.0f64.to_bits().hash(state),
+            Value::Number(val) => val.to_bits().hash(state),
+            Value::Bool(val) => val.hash(state),
+            Value::Object(obj) => match &obj.kind {
+                ObjKind::Str(str) => str.hash(state),
+                ObjKind::EnumValue(enum_value) => {
+                    Rc::as_ptr(&enum_value.enum_def).hash(state);
+                    enum_value.tag.hash(state);
+                    enum_value.values.hash(state);
+                }
+                ObjKind::Tuple(values) => values.hash(state),
+                _ => Rc::as_ptr(obj).hash(state),
+            },
         }
     }
 }
@@ -1,8 +1,10 @@
 //! Object (heap allocated) values.
 
 use crate::chunk::Chunk;
+use indexmap::IndexMap;
 use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use super::Value;
@@ -13,7 +15,10 @@ pub struct NativeFn {
     /// The identifier of the native function.
     pub ident: String,
     /// The number of arguments the native function accepts.
+    /// If the native function is variadic, this is the minimum number of arguments.
     pub arity: u32,
+    /// `true` if the native function accepts any number of extra arguments.
+    pub variadic: bool,
     /// A function pointer to the Rust implementation.
     /// The function accepts a `&mut [Value]` which is a slice into the VM's stack where the function arguments are stored.
     /// The function returns a [`Value`] which is the return value for the function.
@@ -27,6 +32,11 @@ pub struct Function {
     pub ident: String,
     /// The number of arguments the function accepts.
     pub arity: u32,
+    /// The default values of the last `defaults.len()` parameters (not counting the rest parameter).
+    /// Omitted arguments are filled in with these values when the function is called.
+    pub defaults: Vec<Value>,
+    /// `true` if the last parameter is a rest parameter. The extra arguments are collected into a list when the function is called.
+    pub variadic: bool,
     /// The chunk of the function.
     pub chunk: Rc<Chunk>,
     /// The number of upvalues this function captures.
@@ -44,6 +54,94 @@ pub struct Closure {
     pub upvalues: Rc<RefCell<Vec<Rc<RefCell<UpValue>>>>>,
 }
 
+/// Represents a struct definition. Calling a struct creates a new [`Instance`].
+#[derive(Debug, Clone)]
+pub struct Struct {
+    /// The identifier of the struct.
+    pub ident: String,
+    /// The names of the fields, in declaration order.
+    /// Calling the struct assigns the arguments to the fields in this order.
+    pub fields: Vec<String>,
+}
+
+/// Represents an instance of a [`Struct`].
+#[derive(Debug)]
+pub struct Instance {
+    /// The struct that created this instance.
+    pub class: Rc<Struct>,
+    /// The values of the fields.
+    pub fields: RefCell<HashMap<String, Value>>,
+}
+
+/// Represents a class definition. Calling a class creates a new [`ClassInstance`] and calls the `init` method (if any).
+pub struct Class {
+    /// The identifier of the class.
+    pub ident: String,
+    /// The methods of the class. Every method is a closure ([`ObjKind::Closure`]) that takes `self` as its first argument.
+    pub methods: RefCell<HashMap<String, Rc<Obj>>>,
+}
+
+/// Represents an instance of a [`Class`]. Unlike [`Instance`], fields are created when they are first assigned.
+pub struct ClassInstance {
+    /// The class that created this instance.
+    pub class: Rc<Class>,
+    /// The values of the fields.
+    pub fields: RefCell<HashMap<String, Value>>,
+}
+
+/// Represents a method that is bound to a receiver.
+/// Bound methods are created when a method is read off a [`ClassInstance`] without being called immediately.
+pub struct BoundMethod {
+    /// The value that is passed as `self` when the method is called.
+    pub receiver: Value,
+    /// The method closure ([`ObjKind::Closure`]).
+    pub method: Rc<Obj>,
+}
+
+/// Represents an enum definition (a tagged union).
+#[derive(Debug)]
+pub struct Enum {
+    /// The identifier of the enum.
+    pub ident: String,
+    /// The variants of the enum, in declaration order. The index of a variant is its tag.
+    pub variants: Vec<Variant>,
+}
+
+/// Represents a variant of an [`Enum`].
+#[derive(Debug)]
+pub struct Variant {
+    /// The identifier of the variant.
+    pub ident: String,
+    /// The number of fields in the payload.
+    pub arity: u32,
+}
+
+/// Represents a value of an [`Enum`]. Enum values are immutable and compared by value.
+pub struct EnumValue {
+    /// The enum that this value belongs to.
+    pub enum_def: Rc<Enum>,
+    /// The index of the variant in [`Enum::variants`].
+    pub tag: usize,
+    /// The values of the payload fields.
+    pub values: Vec<Value>,
+}
+
+impl EnumValue {
+    /// Returns the [`Variant`] of the enum value.
+    pub fn variant(&self) -> &Variant {
+        &self.enum_def.variants[self.tag]
+    }
+}
+
+/// Represents the constructor of an enum variant with a payload.
+/// Calling the constructor creates a new [`EnumValue`].
+pub struct VariantCtor {
+    /// The enum that the variant belongs to.
+    pub enum_def: Rc<Enum>,
+    /// The index of the variant in [`Enum::variants`].
+    pub tag: usize,
+}
+
 /// Represents a captured variable.
 /// The [`UpValue::Open`] state is used when the variable still lives on the stack.
 /// The [`UpValue::Closed`] state is used when the scope is exited and the value is moved onto the heap.
@@ -86,29 +184,78 @@ impl UpValue {
     }
 }
 
+/// The entries of a map value ([`ObjKind::Map`]) in insertion order.
+pub type MapEntries = IndexMap<Value, Value>;
+
 /// Inner representation for [`Obj`].
-#[derive(Clone)]
 pub enum ObjKind {
     Str(String),
     Fn(Function),
     Closure(Closure),
     NativeFn(NativeFn),
+    /// A growable list of values. Lists are mutable and shared by reference.
+    List(RefCell<Vec<Value>>),
+    /// A fixed size sequence of values. Tuples are immutable and compared by value.
+    Tuple(Vec<Value>),
+    /// A hash map. Keys must be hashable (see [`Value::is_hashable`]).
+    /// Entries are iterated in insertion order.
+    Map(RefCell<MapEntries>),
+    /// A struct definition.
+    Struct(Rc<Struct>),
+    /// An instance of a struct. Instances are mutable and shared by reference.
+    Instance(Instance),
+    /// A class definition.
+    Class(Rc<Class>),
+    /// An instance of a class. Instances are mutable and shared by reference.
+    ClassInstance(ClassInstance),
+    /// A method bound to a receiver.
+    BoundMethod(BoundMethod),
+    /// An enum definition.
+    Enum(Rc<Enum>),
+    /// A value of an enum.
+    EnumValue(EnumValue),
+    /// The constructor of an enum variant.
+    VariantCtor(VariantCtor),
 }
 
 impl PartialEq for ObjKind {
     fn eq(&self, other: &ObjKind) -> bool {
+        // An object is always equal to itself. This also prevents infinite recursion when a list
+        // contains itself.
+        if std::ptr::eq(self, other) {
+            return true;
+        }
         match self {
             Self::Str(l) => match other {
                 Self::Str(r) => l == r,
                 _ => false,
             },
-            _ => false,
+            Self::List(l) => match other {
+                Self::List(r) => l == r,
+                _ => false,
+            },
+            Self::Tuple(l) => match other {
+                Self::Tuple(r) => l == r,
+                _ => false,
+            },
+            Self::Map(l) => match other {
+                Self::Map(r) => l == r,
+                _ => false,
+            },
+            Self::EnumValue(l) => match other {
+                Self::EnumValue(r) => {
+                    Rc::ptr_eq(&l.enum_def, &r.enum_def) && l.tag == r.tag && l.values == r.values
+                }
+                _ => false,
+            },
+            // functions are only equal to themselves
+            _ => std::ptr::eq(self, other),
         }
     }
 }
 
 /// Represents a (heap allocated) object.
-#[derive(Clone, PartialEq)]
+#[derive(PartialEq)]
 pub struct Obj {
     /// Inner representation.
     pub kind: ObjKind,
@@ -121,6 +268,63 @@ impl Obj {
             kind: ObjKind::Str(str),
         }
     }
+
+    /// Create a new heap allocated list ([`ObjKind::List`]).
+    pub fn new_list(values: Vec<Value>) -> Self {
+        Self {
+            kind: ObjKind::List(RefCell::new(values)),
+        }
+    }
+
+    /// Create a new heap allocated tuple ([`ObjKind::Tuple`]).
+    pub fn new_tuple(values: Vec<Value>) -> Self {
+        Self {
+            kind: ObjKind::Tuple(values),
+        }
+    }
+
+    /// Create a new heap allocated map ([`ObjKind::Map`]).
+    #[allow(clippy::mutable_key_type)] // Hashable values never contain interior mutability (see `Value::is_hashable`).
+    pub fn new_map(entries: MapEntries) -> Self {
+        Self {
+            kind: ObjKind::Map(RefCell::new(entries)),
+        }
+    }
+
+    /// Create a new heap allocated instance of `class` ([`ObjKind::Instance`]).
+    /// `values` are assigned to the fields of `class` in declaration order.
+    pub fn new_instance(class: Rc<Struct>, values: Vec<Value>) -> Self {
+        debug_assert_eq!(class.fields.len(), values.len());
+        let fields = class.fields.iter().cloned().zip(values).collect();
+        Self {
+            kind: ObjKind::Instance(Instance {
+                class,
+                fields: RefCell::new(fields),
+            }),
+        }
+    }
+
+    /// Create a new heap allocated value of the variant `tag` of `enum_def` ([`ObjKind::EnumValue`]).
+    pub fn new_enum_value(enum_def: Rc<Enum>, tag: usize, values: Vec<Value>) -> Self {
+        debug_assert_eq!(enum_def.variants[tag].arity as usize, values.len());
+        Self {
+            kind: ObjKind::EnumValue(EnumValue {
+                enum_def,
+                tag,
+                values,
+            }),
+        }
+    }
+
+    /// Create a new heap allocated instance of `class` with no fields ([`ObjKind::ClassInstance`]).
+    pub fn new_class_instance(class: Rc<Class>) -> Self {
+        Self {
+            kind: ObjKind::ClassInstance(ClassInstance {
+                class,
+                fields: RefCell::new(HashMap::new()),
+            }),
+        }
+    }
 }
 
 impl PartialOrd for Obj {
@@ -147,6 +351,31 @@ impl Drop for Obj {
                 ObjKind::NativeFn(NativeFn { ident, .. }) => {
                     eprintln!("Collecting native function object {:?}", ident)
                 }
+                ObjKind::List(values) => {
+                    eprintln!("Collecting list object {:?}", values.borrow())
+                }
+                ObjKind::Tuple(values) => eprintln!("Collecting tuple object {:?}", values),
+                ObjKind::Map(entries) => {
+                    eprintln!("Collecting map object {:?}", entries.borrow())
+                }
+                ObjKind::Struct(class) => eprintln!("Collecting struct object {:?}", class.ident),
+                ObjKind::Instance(Instance { class, .. }) => {
+                    eprintln!("Collecting instance object {:?}", class.ident)
+                }
+                ObjKind::Class(class) => eprintln!("Collecting class object {:?}", class.ident),
+                ObjKind::ClassInstance(ClassInstance { class, .. }) => {
+                    eprintln!("Collecting class instance object {:?}", class.ident)
+                }
+                ObjKind::BoundMethod(_) => eprintln!("Collecting bound method object"),
+                ObjKind::Enum(enum_def) => {
+                    eprintln!("Collecting enum object {:?}", enum_def.ident)
+                }
+                ObjKind::EnumValue(enum_value) => eprintln!(
+                    "Collecting enum value object {}.{}",
+                    enum_value.enum_def.ident,
+                    enum_value.variant().ident
+                ),
+                ObjKind::VariantCtor(_) => eprintln!("Collecting variant constructor object"),
             }
         }
     }
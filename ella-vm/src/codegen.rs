@@ -1,21 +1,36 @@
 //! Lowers AST into a `Chunk` (bytecode).
 
-use ella_parser::ast::{ExprKind, StmtKind};
+use ella_parser::ast::{ExprKind, MatchArm, PatternKind, StmtKind};
 use ella_parser::{
     ast::{Expr, Stmt},
     lexer::Token,
     visitor::Visitor,
 };
-use ella_passes::resolve::{ResolveResult, Symbol};
+use ella_passes::resolve::{match_call_args, CallArg, ResolveResult, ResolvedSymbol, Symbol};
 use ella_source::Source;
 use ella_value::chunk::{Chunk, OpCode};
-use ella_value::object::{Function, Obj, ObjKind};
+use ella_value::object::{Enum, Function, Obj, ObjKind, Struct, Variant};
 use ella_value::{BuiltinVars, Value};
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::{collections::HashMap, rc::Rc};
 
 const DUMP_CHUNK: bool = true;
 
+/// Bookkeeping for the innermost loop. Used for lowering `break` and `continue` statements.
+struct LoopContext {
+    /// The length of `scope_stack` before entering the loop body.
+    /// All scopes above this depth are cleaned up when jumping out of the body.
+    scope_depth: usize,
+    /// Offsets of `jmp` instructions that should be patched to the loop exit.
+    break_jumps: Vec<usize>,
+    /// Offsets of `jmp` instructions that should be patched to the end of the loop body.
+    continue_jumps: Vec<usize>,
+    /// The number of enclosing `try` blocks before entering the loop body.
+    /// Handlers of the `try` blocks inside the loop body are removed when jumping out of the body.
+    handler_depth: usize,
+}
+
 /// Generate bytecode from an abstract syntax tree.
 pub struct Codegen<'a> {
     chunk: Chunk,
@@ -24,6 +39,10 @@ pub struct Codegen<'a> {
     /// Every time a new scope is created, a new value is pushed onto the stack.
     /// This is to keep track of how many `pop` instructions to emit when exiting the scope.
     scope_stack: Vec<Vec<Rc<RefCell<Symbol>>>>,
+    /// A stack of loops that are currently being lowered.
+    loop_stack: Vec<LoopContext>,
+    /// The number of enclosing `try` blocks (exception handlers that are registered at runtime).
+    handler_depth: usize,
     source: &'a Source<'a>,
 }
 
@@ -34,6 +53,8 @@ impl<'a> Codegen<'a> {
             constant_strings: HashMap::new(),
             resolve_result,
             scope_stack: vec![Vec::new()],
+            loop_stack: Vec::new(),
+            handler_depth: 0,
             source,
         }
     }
@@ -81,9 +102,155 @@ impl<'a> Codegen<'a> {
         self.scope_stack.last_mut().unwrap().push(Rc::clone(symbol));
     }
 
+    /// Declares the value on the top of the stack as the variable declared by `stmt`.
+    /// Hoisted declarations (inside block expressions) are stored into their stack slot instead.
+    fn define_symbol(&mut self, stmt: &Stmt, line: usize) {
+        if self.resolve_result.lookup_binding(stmt).is_some() {
+            self.emit_store_binding(stmt, line);
+            self.chunk.write_chunk(OpCode::Pop, line);
+        } else {
+            self.add_symbol(stmt);
+        }
+    }
+
+    /// Emits an instruction to load the variable that `resolved_symbol` refers to.
+    fn emit_load_symbol(&mut self, resolved_symbol: &ResolvedSymbol, ident: &str, line: usize) {
+        if let Some(literal) = &resolved_symbol.symbol.borrow().const_literal {
+            // constants with a literal value are inlined
+            self.emit_literal(literal, line);
+        } else if resolved_symbol.is_global {
+            self.chunk.write_chunk(OpCode::LdGlobal, line);
+            self.chunk
+                .add_debug_annotation_at_last(format!("load global variable {}", ident));
+            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+        } else if resolved_symbol.is_upvalue {
+            self.chunk.write_chunk(OpCode::LdUpVal, line);
+            self.chunk
+                .add_debug_annotation_at_last(format!("load upvalue {}", ident));
+            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+        } else {
+            self.chunk.write_chunk(OpCode::LdLoc, line);
+            self.chunk
+                .add_debug_annotation_at_last(format!("load local variable {}", ident));
+            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+        }
+    }
+
+    /// Emits placeholder values for the symbols hoisted to `stmt` and adds them to the current scope.
+    /// See [`ResolveResult::lookup_hoisted_symbols`].
+    fn emit_hoisted(&mut self, stmt: &Stmt, line: usize) {
+        for symbol in self.resolve_result.lookup_hoisted_symbols(stmt) {
+            self.chunk.write_chunk(OpCode::Ld0, line);
+            self.chunk
+                .add_debug_annotation_at_last("hoisted local variable".to_string());
+            self.scope_stack.last_mut().unwrap().push(Rc::clone(symbol));
+        }
+    }
+
+    /// Emits an instruction to load the hoisted variable declared by `stmt`.
+    fn emit_load_binding(&mut self, stmt: &Stmt, line: usize) {
+        let resolved_symbol = self.resolve_result.lookup_binding(stmt).unwrap();
+        match resolved_symbol.is_global {
+            true => self.chunk.write_chunk(OpCode::LdGlobal, line),
+            false => self.chunk.write_chunk(OpCode::LdLoc, line),
+        };
+        self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+    }
+
+    /// Emits an instruction to store the top value on the stack into the hoisted variable declared by `stmt`.
+    fn emit_store_binding(&mut self, stmt: &Stmt, line: usize) {
+        let resolved_symbol = self.resolve_result.lookup_binding(stmt).unwrap();
+        if resolved_symbol.symbol.borrow().is_captured && !resolved_symbol.is_global {
+            // The declaration can be executed more than once (e.g. in a loop inside a block expression).
+            // Every execution declares a new variable, closures created before keep the previous value.
+            self.chunk.write_chunk(OpCode::CloseUpValAt, line);
+            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+        }
+        match resolved_symbol.is_global {
+            true => self.chunk.write_chunk(OpCode::StGlobal, line),
+            false => self.chunk.write_chunk(OpCode::StLoc, line),
+        };
+        self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+    }
+
+    /// Lowers a match expression. The matched value is stored in the hoisted variable declared by `inner_stmt`.
+    /// Each variant arm compares the tag of the value and jumps to the next arm if it does not match.
+    fn codegen_match(
+        &mut self,
+        scrutinee: &'a Expr,
+        inner_stmt: &'a Stmt,
+        arms: &'a [MatchArm],
+        line: usize,
+    ) {
+        self.visit_expr(scrutinee);
+        self.emit_store_binding(inner_stmt, line);
+        self.chunk.write_chunk(OpCode::Pop, line);
+
+        let mut end_jumps = Vec::new();
+        let mut has_wildcard = false;
+        for arm in arms {
+            let arm_line = self.source.lookup_line(arm.pattern.span.start);
+            match &arm.pattern.kind {
+                PatternKind::Wildcard => {
+                    self.visit_expr(&arm.body);
+                    end_jumps.push(self.emit_jump(OpCode::Jmp, arm_line));
+                    has_wildcard = true;
+                    break; // following arms are unreachable
+                }
+                PatternKind::Variant {
+                    enum_ident,
+                    variant,
+                    bindings,
+                } => {
+                    let tag = self
+                        .resolve_result
+                        .lookup_variant_tag(enum_ident, variant)
+                        .unwrap();
+                    self.emit_load_binding(inner_stmt, arm_line);
+                    self.visit_expr(enum_ident);
+                    self.chunk.write_chunk(OpCode::TestVariant, arm_line);
+                    self.chunk.write_chunk(tag as u8, arm_line);
+
+                    let next_jump = self.emit_jump(OpCode::JmpIfFalse, arm_line);
+                    self.chunk.write_chunk(OpCode::Pop, arm_line);
+
+                    for (i, binding) in bindings.iter().enumerate() {
+                        self.emit_load_binding(inner_stmt, arm_line);
+                        self.chunk.write_chunk(OpCode::LdVariantField, arm_line);
+                        self.chunk.write_chunk(i as u8, arm_line);
+                        self.emit_store_binding(binding, arm_line);
+                        self.chunk.write_chunk(OpCode::Pop, arm_line);
+                    }
+
+                    self.visit_expr(&arm.body);
+                    end_jumps.push(self.emit_jump(OpCode::Jmp, arm_line));
+
+                    self.chunk.patch_jump(next_jump);
+                    self.chunk.write_chunk(OpCode::Pop, arm_line); // pop condition
+                }
+            }
+        }
+
+        if !has_wildcard {
+            let constant = self.add_string_constant("No match arm matched the value.");
+            self.chunk.write_chunk(OpCode::Abort, line);
+            self.chunk.write_chunk(constant, line);
+        }
+
+        for jump in end_jumps {
+            self.chunk.patch_jump(jump);
+        }
+    }
+
     fn exit_scope(&mut self, line: usize) {
         let scope = self.scope_stack.pop().unwrap();
-        for symbol in scope {
+        self.emit_cleanup(&scope, line);
+    }
+
+    /// Emits `pop` or `close_upval` instructions for every symbol in `symbols`.
+    /// Symbols are cleaned up in reverse order of declaration as the last symbol is on the top of the stack.
+    fn emit_cleanup(&mut self, symbols: &[Rc<RefCell<Symbol>>], line: usize) {
+        for symbol in symbols.iter().rev() {
             match symbol.borrow().is_captured {
                 true => {
                     self.chunk.write_chunk(OpCode::CloseUpVal, line);
@@ -91,12 +258,133 @@ impl<'a> Codegen<'a> {
                 false => {
                     self.chunk.write_chunk(OpCode::Pop, line);
                     self.chunk
-                        .add_debug_annotation_at_last(format!("cleanup local variable"));
+                        .add_debug_annotation_at_last("cleanup local variable".to_string());
                 }
             };
         }
     }
 
+    /// Emits cleanup for all the scopes and `try` blocks inside the innermost loop body followed by a placeholder `jmp`.
+    /// Returns the index of the start of the jump offset (see [`Self::emit_jump`]).
+    fn emit_loop_exit_jump(&mut self, line: usize) -> usize {
+        let loop_ctx = self.loop_stack.last().unwrap();
+        let scope_depth = loop_ctx.scope_depth;
+        let handler_depth = loop_ctx.handler_depth;
+        let symbols: Vec<_> = self.scope_stack[scope_depth..]
+            .iter()
+            .flatten()
+            .cloned()
+            .collect();
+        self.emit_cleanup(&symbols, line);
+        for _ in handler_depth..self.handler_depth {
+            self.chunk.write_chunk(OpCode::PopHandler, line);
+        }
+        self.emit_jump(OpCode::Jmp, line)
+    }
+
+    /// Lowers a loop body. `break` and `continue` jumps are collected into the returned [`LoopContext`].
+    /// `continue` jumps are patched to the end of the body. `break` jumps should be patched by the caller.
+    fn codegen_loop_body(&mut self, body: &'a [Stmt], line: usize) -> LoopContext {
+        self.loop_stack.push(LoopContext {
+            scope_depth: self.scope_stack.len(),
+            break_jumps: Vec::new(),
+            continue_jumps: Vec::new(),
+            handler_depth: self.handler_depth,
+        });
+
+        self.enter_scope();
+        for stmt in body {
+            self.visit_stmt(stmt);
+        }
+        self.exit_scope(line);
+
+        let loop_ctx = self.loop_stack.pop().unwrap();
+        for &jump in &loop_ctx.continue_jumps {
+            self.chunk.patch_jump(jump);
+        }
+        loop_ctx
+    }
+
+    /// Codegens a function declaration in a new [`Codegen`] instance and emits a `closure` instruction for it.
+    fn emit_closure(&mut self, stmt: &'a Stmt, ident: &str, params: &[Stmt], line: usize) {
+        // Create a new `Codegen` instance, codegen the function, and add the chunk to the `ObjKind::Fn`.
+        let fn_chunk = {
+            let mut cg = Codegen::new(ident.to_string(), self.resolve_result, self.source);
+            cg.codegen_function(stmt);
+            cg.chunk
+        };
+
+        let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
+
+        let func = Rc::new(Obj {
+            kind: ObjKind::Fn(Function {
+                ident: ident.to_string(),
+                arity: params.len() as u32,
+                defaults: default_values(params),
+                variadic: is_variadic(params),
+                chunk: Rc::new(fn_chunk),
+                upvalues_count: symbol.borrow().upvalues.len(),
+            }),
+        });
+        let constant = self.chunk.add_constant(Value::Object(func));
+        self.chunk.write_chunk(OpCode::Closure, line);
+        self.chunk.write_chunk(constant, line);
+
+        for symbol in &symbol.borrow().upvalues {
+            self.chunk.write_chunk(symbol.is_local as u8, line);
+            self.chunk.write_chunk(symbol.index as u8, line);
+        }
+    }
+
+    /// Adds a string constant to the chunk, reusing the same [`Obj`] for identical strings.
+    /// Returns the index of the constant.
+    fn add_string_constant(&mut self, val: &str) -> u8 {
+        let obj = if let Some(obj) = self.constant_strings.get(val) {
+            // reuse same String
+            obj.clone()
+        } else {
+            let obj = Rc::new(Obj::new_string(val.to_string()));
+            self.constant_strings.insert(val.to_string(), obj.clone());
+            obj
+        };
+        self.chunk.add_constant(Value::Object(obj))
+    }
+
+    /// Emits the instructions for loading a number, bool or string literal.
+    fn emit_literal(&mut self, literal: &ExprKind, line: usize) {
+        match literal {
+            ExprKind::NumberLit(val) => {
+                if *val == 0.0 {
+                    self.chunk.write_chunk(OpCode::Ld0, line);
+                } else if *val == 1.0 {
+                    self.chunk.write_chunk(OpCode::Ld1, line);
+                } else {
+                    self.chunk.emit_ldf64(*val, line);
+                }
+            }
+            ExprKind::BoolLit(val) => {
+                match val {
+                    true => self.chunk.write_chunk(OpCode::LdTrue, line),
+                    false => self.chunk.write_chunk(OpCode::LdFalse, line),
+                };
+            }
+            ExprKind::StringLit(val) => {
+                let constant = self.add_string_constant(val);
+                self.chunk.write_chunk(OpCode::Ldc, line);
+                self.chunk.write_chunk(constant, line);
+            }
+            // negative number literal (e.g. a default value of `-1`)
+            ExprKind::Unary {
+                op: Token::Minus,
+                arg,
+            } => match arg.kind {
+                ExprKind::NumberLit(val) => self.emit_literal(&ExprKind::NumberLit(-val), line),
+                _ => unreachable!("not a literal"),
+            },
+            _ => unreachable!("not a literal"),
+        }
+    }
+
     /// Emits a placeholder jump.
     /// Returns the index of the start of the jump offset. This should be later patched using [`Chunk::patch_jump`].
     fn emit_jump(&mut self, instr: OpCode, line: usize) -> usize {
@@ -106,6 +394,20 @@ impl<'a> Codegen<'a> {
         self.chunk.code.len() - 2
     }
 
+    /// Emits an instruction with a `u16` operand (e.g. the number of elements of a list literal).
+    fn emit_u16_instr(&mut self, instr: OpCode, operand: usize, line: usize) {
+        self.chunk.write_chunk(instr, line);
+        self.emit_u16(operand, line);
+    }
+
+    /// Emits a `u16` operand (high byte first).
+    fn emit_u16(&mut self, operand: usize, line: usize) {
+        let operand = u16::try_from(operand).expect("operand does not fit in a u16");
+
+        self.chunk.write_chunk((operand >> 8) as u8, line);
+        self.chunk.write_chunk((operand & 0xff) as u8, line);
+    }
+
     /// Emits a `loop` instruction.
     fn emit_loop(&mut self, instr: OpCode, loop_start: usize, line: usize) {
         let offset = self.chunk.code.len() - loop_start + 3;
@@ -116,6 +418,41 @@ impl<'a> Codegen<'a> {
     }
 }
 
+/// Returns the default values of the function parameters `params` that have one.
+/// Default values are always literals (checked by the resolver).
+fn default_values(params: &[Stmt]) -> Vec<Value> {
+    params
+        .iter()
+        .filter_map(|param| match &param.kind {
+            StmtKind::FnParam {
+                default: Some(default),
+                ..
+            } => Some(match &default.kind {
+                ExprKind::NumberLit(val) => Value::Number(*val),
+                ExprKind::BoolLit(val) => Value::Bool(*val),
+                ExprKind::StringLit(val) => Value::Object(Rc::new(Obj::new_string(val.clone()))),
+                ExprKind::Unary {
+                    op: Token::Minus,
+                    arg,
+                } => match arg.kind {
+                    ExprKind::NumberLit(val) => Value::Number(-val),
+                    _ => unreachable!("default values are always literals"),
+                },
+                _ => unreachable!("default values are always literals"),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns `true` if the last of the function parameters `params` is a rest parameter.
+fn is_variadic(params: &[Stmt]) -> bool {
+    matches!(
+        params.last().map(|param| &param.kind),
+        Some(StmtKind::FnParam { is_rest: true, .. })
+    )
+}
+
 impl<'a> Visitor<'a> for Codegen<'a> {
     fn visit_expr(&mut self, expr: &'a Expr) {
         // Do not use default walking logic.
@@ -125,94 +462,200 @@ impl<'a> Visitor<'a> for Codegen<'a> {
         /// Generate codegen for shorthand assignments (e.g. `+=`).
         macro_rules! gen_op_assign {
             ($instr: expr, $lhs: expr, $rhs: expr, $line: expr) => {{
-                let resolved_symbol = self.resolve_result.lookup_identifier($lhs).unwrap();
-
-                // load value
-                if resolved_symbol.is_global {
-                    self.chunk.write_chunk(OpCode::LdGlobal, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
-                } else if resolved_symbol.is_upvalue {
-                    self.chunk.write_chunk(OpCode::LdUpVal, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
-                } else {
-                    self.chunk.write_chunk(OpCode::LdLoc, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
-                }
+                if let ExprKind::Index { target, index } = &$lhs.kind {
+                    self.visit_expr(target);
+                    self.visit_expr(index);
+                    // keep list and index on the stack for stindex
+                    self.chunk.write_chunk(OpCode::Dup2, $line);
+                    self.chunk.write_chunk(OpCode::LdIndex, $line);
+
+                    self.visit_expr($rhs);
+                    self.chunk.write_chunk($instr, $line);
 
-                self.visit_expr($rhs);
-                self.chunk.write_chunk($instr, $line);
+                    self.chunk.write_chunk(OpCode::StIndex, $line);
+                } else if let ExprKind::FieldAccess { target, ident } = &$lhs.kind {
+                    let constant = self.add_string_constant(ident);
+                    self.visit_expr(target);
+                    // keep instance on the stack for stprop
+                    self.chunk.write_chunk(OpCode::Dup, $line);
+                    self.chunk.write_chunk(OpCode::LdProp, $line);
+                    self.chunk.write_chunk(constant, $line);
 
-                // store value
-                if resolved_symbol.is_global {
-                    self.chunk.write_chunk(OpCode::StGlobal, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
-                } else if resolved_symbol.is_upvalue {
-                    self.chunk.write_chunk(OpCode::StUpVal, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    self.visit_expr($rhs);
+                    self.chunk.write_chunk($instr, $line);
+
+                    self.chunk.write_chunk(OpCode::StProp, $line);
+                    self.chunk.write_chunk(constant, $line);
                 } else {
-                    self.chunk.write_chunk(OpCode::StLoc, $line);
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    let resolved_symbol = self.resolve_result.lookup_identifier($lhs).unwrap();
+
+                    // load value
+                    if resolved_symbol.is_global {
+                        self.chunk.write_chunk(OpCode::LdGlobal, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    } else if resolved_symbol.is_upvalue {
+                        self.chunk.write_chunk(OpCode::LdUpVal, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    } else {
+                        self.chunk.write_chunk(OpCode::LdLoc, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    }
+
+                    self.visit_expr($rhs);
+                    self.chunk.write_chunk($instr, $line);
+
+                    // store value
+                    if resolved_symbol.is_global {
+                        self.chunk.write_chunk(OpCode::StGlobal, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    } else if resolved_symbol.is_upvalue {
+                        self.chunk.write_chunk(OpCode::StUpVal, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    } else {
+                        self.chunk.write_chunk(OpCode::StLoc, $line);
+                        self.chunk.write_chunk(resolved_symbol.offset as u8, $line);
+                    }
                 }
             }};
         }
 
         match &expr.kind {
-            ExprKind::NumberLit(val) => {
-                if *val == 0.0 {
-                    self.chunk.write_chunk(OpCode::Ld0, line);
-                } else if *val == 1.0 {
-                    self.chunk.write_chunk(OpCode::Ld1, line);
-                } else {
-                    self.chunk.emit_ldf64(*val, line);
+            ExprKind::NumberLit(_) | ExprKind::BoolLit(_) | ExprKind::StringLit(_) => {
+                self.emit_literal(&expr.kind, line)
+            }
+            ExprKind::Identifier(ident) => {
+                let resolved_symbol = self.resolve_result.lookup_identifier(expr).unwrap();
+                self.emit_load_symbol(resolved_symbol, ident, line);
+            }
+            ExprKind::FieldAccess { target, ident }
+                if self
+                    .resolve_result
+                    .lookup_module_member(target, ident)
+                    .is_some() =>
+            {
+                let member = self
+                    .resolve_result
+                    .lookup_module_member(target, ident)
+                    .unwrap();
+                self.emit_load_symbol(&member, ident, line);
+            }
+            ExprKind::FnCall {
+                callee,
+                args,
+                named_args,
+            } => {
+                let arity = args.len();
+                match &callee.kind {
+                    // functions exported by modules are called like any other function
+                    ExprKind::FieldAccess { target, ident }
+                        if self
+                            .resolve_result
+                            .lookup_module_member(target, ident)
+                            .is_none() =>
+                    {
+                        // method call, the receiver is below the arguments
+                        let constant = self.add_string_constant(ident);
+                        self.visit_expr(target);
+                        for arg in args {
+                            self.visit_expr(arg);
+                        }
+                        self.chunk.write_chunk(OpCode::Invoke, line);
+                        self.chunk.write_chunk(constant, line);
+                        self.emit_u16(arity, line);
+                    }
+                    _ => {
+                        let arity = match self.resolve_result.lookup_fn_params(callee) {
+                            // the callee is statically known, pass the arguments in parameter order
+                            Some(params) => {
+                                let call_args =
+                                    match_call_args(params, args, named_args, expr.span.clone())
+                                        .expect("arguments are checked by the type checker");
+                                // evaluate the arguments in the order they are written
+                                // (the extra arguments are collected into a list by the VM)
+                                let given: Vec<&Expr> = args
+                                    .iter()
+                                    .chain(named_args.iter().map(|named_arg| &named_arg.value))
+                                    .collect();
+                                for arg in &given {
+                                    self.visit_expr(arg);
+                                }
+                                // followed by the default values of the omitted arguments
+                                let mut arity = given.len();
+                                // the index of the value of every param that is not the rest param
+                                let mut order = Vec::new();
+                                for arg in &call_args {
+                                    match arg {
+                                        CallArg::Given(arg) => order.push(
+                                            given
+                                                .iter()
+                                                .position(|given| std::ptr::eq(*given, *arg))
+                                                .unwrap(),
+                                        ),
+                                        CallArg::Default(default) => {
+                                            self.emit_literal(default, line);
+                                            order.push(arity);
+                                            arity += 1;
+                                        }
+                                        CallArg::Rest(_) => {}
+                                    }
+                                }
+                                // pass the arguments in parameter order
+                                if order.iter().enumerate().any(|(i, index)| i != *index) {
+                                    self.chunk.write_chunk(OpCode::Reorder, line);
+                                    self.chunk.write_chunk(order.len() as u8, line);
+                                    for index in order {
+                                        self.chunk.write_chunk(index as u8, line);
+                                    }
+                                }
+                                arity
+                            }
+                            None => {
+                                for arg in args {
+                                    self.visit_expr(arg);
+                                }
+                                arity
+                            }
+                        };
+                        self.visit_expr(callee);
+                        self.emit_u16_instr(OpCode::Calli, arity, line);
+                    }
                 }
             }
-            ExprKind::BoolLit(val) => {
-                match val {
-                    true => self.chunk.write_chunk(OpCode::LdTrue, line),
-                    false => self.chunk.write_chunk(OpCode::LdFalse, line),
-                };
+            ExprKind::Interpolation(segments) => {
+                for segment in segments {
+                    self.visit_expr(segment);
+                }
+                self.emit_u16_instr(OpCode::Concat, segments.len(), line);
             }
-            ExprKind::StringLit(val) => {
-                let obj = if let Some(obj) = self.constant_strings.get(val) {
-                    // reuse same String
-                    obj.clone()
-                } else {
-                    let obj = Rc::new(Obj::new_string(val.clone()));
-                    self.constant_strings.insert(val.clone(), obj.clone());
-                    obj
-                };
-                let constant = self.chunk.add_constant(Value::Object(obj));
-                self.chunk.write_chunk(OpCode::Ldc, line);
-                self.chunk.write_chunk(constant, line);
+            ExprKind::ListLit(elements) => {
+                for element in elements {
+                    self.visit_expr(element);
+                }
+                self.emit_u16_instr(OpCode::BuildList, elements.len(), line);
             }
-            ExprKind::Identifier(ident) => {
-                let resolved_symbol = self.resolve_result.lookup_identifier(expr).unwrap();
-
-                if resolved_symbol.is_global {
-                    self.chunk.write_chunk(OpCode::LdGlobal, line);
-                    self.chunk
-                        .add_debug_annotation_at_last(format!("load global variable {}", ident));
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, line);
-                } else if resolved_symbol.is_upvalue {
-                    self.chunk.write_chunk(OpCode::LdUpVal, line);
-                    self.chunk
-                        .add_debug_annotation_at_last(format!("load upvalue {}", ident));
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, line);
-                } else {
-                    self.chunk.write_chunk(OpCode::LdLoc, line);
-                    self.chunk
-                        .add_debug_annotation_at_last(format!("load local variable {}", ident));
-                    self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+            ExprKind::TupleLit(elements) => {
+                for element in elements {
+                    self.visit_expr(element);
                 }
+                self.emit_u16_instr(OpCode::BuildTuple, elements.len(), line);
             }
-            ExprKind::FnCall { callee, args } => {
-                let arity = args.len() as u8;
-                for arg in args {
-                    self.visit_expr(arg);
+            ExprKind::MapLit(entries) => {
+                for (key, value) in entries {
+                    self.visit_expr(key);
+                    self.visit_expr(value);
                 }
-                self.visit_expr(callee);
-                self.chunk.write_chunk(OpCode::Calli, line);
-                self.chunk.write_chunk(arity, line);
+                self.emit_u16_instr(OpCode::BuildMap, entries.len(), line);
+            }
+            ExprKind::Index { target, index } => {
+                self.visit_expr(target);
+                self.visit_expr(index);
+                self.chunk.write_chunk(OpCode::LdIndex, line);
+            }
+            ExprKind::FieldAccess { target, ident } => {
+                let constant = self.add_string_constant(ident);
+                self.visit_expr(target);
+                self.chunk.write_chunk(OpCode::LdProp, line);
+                self.chunk.write_chunk(constant, line);
             }
             ExprKind::Binary { lhs, op, rhs } => {
                 match op {
@@ -220,7 +663,16 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                     | Token::PlusEquals
                     | Token::MinusEquals
                     | Token::AsteriskEquals
-                    | Token::SlashEquals => {} // do not codegen anything
+                    | Token::SlashEquals
+                    | Token::PercentEquals
+                    | Token::DoubleAsteriskEquals
+                    | Token::AmpersandEquals
+                    | Token::PipeEquals
+                    | Token::CaretEquals
+                    | Token::ShiftLeftEquals
+                    | Token::ShiftRightEquals
+                    | Token::LogicalAnd
+                    | Token::LogicalOr => {} // do not codegen anything
                     _ => {
                         self.visit_expr(lhs);
                         self.visit_expr(rhs);
@@ -239,27 +691,68 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                     Token::Slash => {
                         self.chunk.write_chunk(OpCode::Div, line);
                     }
+                    Token::Percent => {
+                        self.chunk.write_chunk(OpCode::Rem, line);
+                    }
+                    Token::DoubleAsterisk => {
+                        self.chunk.write_chunk(OpCode::Pow, line);
+                    }
+                    Token::Ampersand => {
+                        self.chunk.write_chunk(OpCode::BitAnd, line);
+                    }
+                    Token::Pipe => {
+                        self.chunk.write_chunk(OpCode::BitOr, line);
+                    }
+                    Token::Caret => {
+                        self.chunk.write_chunk(OpCode::BitXor, line);
+                    }
+                    Token::ShiftLeft => {
+                        self.chunk.write_chunk(OpCode::Shl, line);
+                    }
+                    Token::ShiftRight => {
+                        self.chunk.write_chunk(OpCode::Shr, line);
+                    }
                     Token::Equals => {
-                        self.visit_expr(rhs);
+                        if let ExprKind::Index { target, index } = &lhs.kind {
+                            self.visit_expr(target);
+                            self.visit_expr(index);
+                            self.visit_expr(rhs);
+                            self.chunk.write_chunk(OpCode::StIndex, line);
+                        } else if let ExprKind::FieldAccess { target, ident } = &lhs.kind {
+                            let constant = self.add_string_constant(ident);
+                            self.visit_expr(target);
+                            self.visit_expr(rhs);
+                            self.chunk.write_chunk(OpCode::StProp, line);
+                            self.chunk.write_chunk(constant, line);
+                        } else {
+                            self.visit_expr(rhs);
 
-                        let resolved_symbol =
-                            self.resolve_result.lookup_identifier(lhs.as_ref()).unwrap();
+                            let resolved_symbol =
+                                self.resolve_result.lookup_identifier(lhs.as_ref()).unwrap();
 
-                        if resolved_symbol.is_global {
-                            self.chunk.write_chunk(OpCode::StGlobal, line);
-                            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
-                        } else if resolved_symbol.is_upvalue {
-                            self.chunk.write_chunk(OpCode::StUpVal, line);
-                            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
-                        } else {
-                            self.chunk.write_chunk(OpCode::StLoc, 0);
-                            self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+                            if resolved_symbol.is_global {
+                                self.chunk.write_chunk(OpCode::StGlobal, line);
+                                self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+                            } else if resolved_symbol.is_upvalue {
+                                self.chunk.write_chunk(OpCode::StUpVal, line);
+                                self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+                            } else {
+                                self.chunk.write_chunk(OpCode::StLoc, 0);
+                                self.chunk.write_chunk(resolved_symbol.offset as u8, line);
+                            }
                         }
                     }
                     Token::PlusEquals => gen_op_assign!(OpCode::Add, lhs, rhs, line),
                     Token::MinusEquals => gen_op_assign!(OpCode::Sub, lhs, rhs, line),
                     Token::AsteriskEquals => gen_op_assign!(OpCode::Mul, lhs, rhs, line),
                     Token::SlashEquals => gen_op_assign!(OpCode::Div, lhs, rhs, line),
+                    Token::PercentEquals => gen_op_assign!(OpCode::Rem, lhs, rhs, line),
+                    Token::DoubleAsteriskEquals => gen_op_assign!(OpCode::Pow, lhs, rhs, line),
+                    Token::AmpersandEquals => gen_op_assign!(OpCode::BitAnd, lhs, rhs, line),
+                    Token::PipeEquals => gen_op_assign!(OpCode::BitOr, lhs, rhs, line),
+                    Token::CaretEquals => gen_op_assign!(OpCode::BitXor, lhs, rhs, line),
+                    Token::ShiftLeftEquals => gen_op_assign!(OpCode::Shl, lhs, rhs, line),
+                    Token::ShiftRightEquals => gen_op_assign!(OpCode::Shr, lhs, rhs, line),
                     Token::EqualsEquals => {
                         self.chunk.write_chunk(OpCode::Eq, line);
                     }
@@ -283,6 +776,24 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                         self.chunk.write_chunk(OpCode::Less, line);
                         self.chunk.write_chunk(OpCode::Not, line);
                     }
+                    Token::LogicalAnd => {
+                        // if lhs is false, short circuit and keep lhs as the result
+                        self.visit_expr(lhs);
+                        let end_jump = self.emit_jump(OpCode::JmpIfFalse, line);
+                        self.chunk.write_chunk(OpCode::Pop, line);
+                        self.visit_expr(rhs);
+                        self.chunk.patch_jump(end_jump);
+                    }
+                    Token::LogicalOr => {
+                        // if lhs is true, short circuit and keep lhs as the result
+                        self.visit_expr(lhs);
+                        let else_jump = self.emit_jump(OpCode::JmpIfFalse, line);
+                        let end_jump = self.emit_jump(OpCode::Jmp, line);
+                        self.chunk.patch_jump(else_jump);
+                        self.chunk.write_chunk(OpCode::Pop, line);
+                        self.visit_expr(rhs);
+                        self.chunk.patch_jump(end_jump);
+                    }
                     _ => unreachable!(),
                 };
             }
@@ -291,12 +802,14 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                 match op {
                     Token::LogicalNot => self.chunk.write_chunk(OpCode::Not, line),
                     Token::Minus => self.chunk.write_chunk(OpCode::Neg, line),
+                    Token::Tilde => self.chunk.write_chunk(OpCode::BitNot, line),
                     _ => unreachable!(),
                 };
             }
             ExprKind::Lambda {
                 inner_stmt,
                 params,
+                ret_ty: _,
                 body,
             } => {
                 let ident = "lambda".to_string();
@@ -304,7 +817,7 @@ impl<'a> Visitor<'a> for Codegen<'a> {
 
                 // Create a new `Codegen` instance, codegen the function, and add the chunk to the `ObjKind::Fn`.
                 let fn_chunk = {
-                    let mut cg = Codegen::new(ident.clone(), &self.resolve_result, self.source);
+                    let mut cg = Codegen::new(ident.clone(), self.resolve_result, self.source);
                     for stmt in body {
                         cg.visit_stmt(stmt);
                     }
@@ -320,6 +833,8 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                     kind: ObjKind::Fn(Function {
                         ident,
                         arity,
+                        defaults: default_values(params),
+                        variadic: is_variadic(params),
                         chunk: Rc::new(fn_chunk),
                         upvalues_count: symbol.borrow().upvalues.len(),
                     }),
@@ -333,6 +848,55 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                     self.chunk.write_chunk(symbol.index as u8, line);
                 }
             }
+            ExprKind::Block { stmts, expr } => {
+                // Declarations inside block expressions are hoisted so there is nothing to clean up.
+                for stmt in stmts {
+                    self.visit_stmt(stmt);
+                }
+                match expr {
+                    Some(expr) => self.visit_expr(expr),
+                    None => {
+                        self.chunk.write_chunk(OpCode::LdUnit, line);
+                    }
+                }
+            }
+            ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.visit_expr(condition);
+                self.chunk.add_debug_annotation_at_last("if condition");
+
+                let then_jump = self.emit_jump(OpCode::JmpIfFalse, line);
+                self.chunk.write_chunk(OpCode::Pop, line);
+
+                self.visit_expr(then_branch);
+                if else_branch.is_none() {
+                    // without an else branch, the if expression evaluates to `()`
+                    self.chunk.write_chunk(OpCode::Pop, line);
+                    self.chunk.write_chunk(OpCode::LdUnit, line);
+                }
+
+                let else_jump = self.emit_jump(OpCode::Jmp, line);
+
+                self.chunk.patch_jump(then_jump);
+                self.chunk.write_chunk(OpCode::Pop, line); // pop condition on the else path
+
+                match else_branch {
+                    Some(else_branch) => self.visit_expr(else_branch),
+                    None => {
+                        self.chunk.write_chunk(OpCode::LdUnit, line);
+                    }
+                }
+
+                self.chunk.patch_jump(else_jump);
+            }
+            ExprKind::Match {
+                scrutinee,
+                inner_stmt,
+                arms,
+            } => self.codegen_match(scrutinee, inner_stmt, arms, line),
             ExprKind::Error => unreachable!(),
         }
     }
@@ -342,50 +906,115 @@ impl<'a> Visitor<'a> for Codegen<'a> {
 
         let line = self.source.lookup_line(stmt.span.start);
 
+        if !matches!(stmt.kind, StmtKind::ForStmt { .. }) {
+            // hoisted variables of for statements are declared in the scope of the initializer
+            self.emit_hoisted(stmt, line);
+        }
+
         match &stmt.kind {
             StmtKind::LetDeclaration {
                 ident: _,
                 initializer,
                 ty: _,
+                is_const: _,
+                doc: _,
             } => {
                 self.visit_expr(initializer); // Push value of expression onto top of stack.
-                self.add_symbol(stmt);
+                self.define_symbol(stmt, line);
             }
-            StmtKind::FnParam { ident: _ } => unreachable!("function params are never visited"),
+            StmtKind::TupleDeclaration {
+                bindings,
+                initializer,
+                ty: _,
+                is_const: _,
+                doc: _,
+            } => {
+                self.visit_expr(initializer);
+                self.emit_u16_instr(OpCode::UnpackTuple, bindings.len(), line);
+
+                let is_hoisted = bindings
+                    .iter()
+                    .any(|binding| self.resolve_result.lookup_binding(binding).is_some());
+                if is_hoisted {
+                    // hoisted variables are stored starting from the last element on the top of the stack
+                    for binding in bindings.iter().rev() {
+                        self.define_symbol(binding, line);
+                    }
+                } else {
+                    for binding in bindings {
+                        self.define_symbol(binding, line);
+                    }
+                }
+            }
+            StmtKind::FnParam { .. } => {
+                unreachable!("function params are never visited")
+            }
+            StmtKind::Binding { ident: _ } => unreachable!("bindings are never visited"),
             StmtKind::FnDeclaration {
                 ident,
+                type_params: _,
                 params,
+                ret_ty: _,
                 body: _, // Body is codegen in a new `Codegen` instance.
+                doc: _,
             } => {
                 // NOTE: we don't need to create a new scope here because the VM automatically cleans up the created local variables.
-                let ident = ident.clone();
-                let arity = params.len() as u32;
-
-                // Create a new `Codegen` instance, codegen the function, and add the chunk to the `ObjKind::Fn`.
-                let fn_chunk = {
-                    let mut cg = Codegen::new(ident.clone(), self.resolve_result, self.source);
-                    cg.codegen_function(stmt);
-                    cg.chunk
-                };
-
-                let symbol = self.resolve_result.lookup_declaration(stmt).unwrap();
-
-                let func = Rc::new(Obj {
-                    kind: ObjKind::Fn(Function {
-                        ident,
-                        arity,
-                        chunk: Rc::new(fn_chunk),
-                        upvalues_count: symbol.borrow().upvalues.len(),
-                    }),
+                self.emit_closure(stmt, ident, params, line);
+                self.define_symbol(stmt, line);
+            }
+            StmtKind::StructDeclaration { ident, fields } => {
+                let class = Rc::new(Obj {
+                    kind: ObjKind::Struct(Rc::new(Struct {
+                        ident: ident.clone(),
+                        fields: fields.iter().map(|field| field.ident.clone()).collect(),
+                    })),
                 });
-                let constant = self.chunk.add_constant(Value::Object(func));
-                self.chunk.write_chunk(OpCode::Closure, line);
+                let constant = self.chunk.add_constant(Value::Object(class));
+                self.chunk.write_chunk(OpCode::Ldc, line);
+                self.chunk.write_chunk(constant, line);
+                self.define_symbol(stmt, line);
+            }
+            StmtKind::EnumDeclaration { ident, variants } => {
+                let enum_def = Rc::new(Obj {
+                    kind: ObjKind::Enum(Rc::new(Enum {
+                        ident: ident.clone(),
+                        variants: variants
+                            .iter()
+                            .map(|variant| Variant {
+                                ident: variant.ident.clone(),
+                                arity: variant.fields.len() as u32,
+                            })
+                            .collect(),
+                    })),
+                });
+                let constant = self.chunk.add_constant(Value::Object(enum_def));
+                self.chunk.write_chunk(OpCode::Ldc, line);
+                self.chunk.write_chunk(constant, line);
+                self.define_symbol(stmt, line);
+            }
+            StmtKind::ClassDeclaration { ident, methods } => {
+                let constant = self.add_string_constant(ident);
+                self.chunk.write_chunk(OpCode::Class, line);
                 self.chunk.write_chunk(constant, line);
 
-                for symbol in &symbol.borrow().upvalues {
-                    self.chunk.write_chunk(symbol.is_local as u8, line);
-                    self.chunk.write_chunk(symbol.index as u8, line);
+                for method in methods {
+                    if let StmtKind::FnDeclaration {
+                        ident: method_ident,
+                        type_params: _,
+                        params,
+                        ret_ty: _,
+                        body: _,
+                        doc: _,
+                    } = &method.kind
+                    {
+                        let method_line = self.source.lookup_line(method.span.start);
+                        self.emit_closure(method, method_ident, params, method_line);
+                        let constant = self.add_string_constant(method_ident);
+                        self.chunk.write_chunk(OpCode::Method, method_line);
+                        self.chunk.write_chunk(constant, method_line);
+                    }
                 }
+                self.define_symbol(stmt, line);
             }
             StmtKind::Block(body) => {
                 self.enter_scope();
@@ -411,22 +1040,20 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                 }
                 self.exit_scope(line);
 
-                if let Some(else_block) = else_block {
-                    let else_jump = self.emit_jump(OpCode::Jmp, line);
+                let else_jump = self.emit_jump(OpCode::Jmp, line);
 
-                    self.chunk.patch_jump(then_jump);
-                    self.chunk.write_chunk(OpCode::Pop, line);
+                self.chunk.patch_jump(then_jump);
+                self.chunk.write_chunk(OpCode::Pop, line); // pop condition on the else path
 
+                if let Some(else_block) = else_block {
                     self.enter_scope();
                     for stmt in else_block {
                         self.visit_stmt(stmt);
                     }
                     self.exit_scope(line);
-
-                    self.chunk.patch_jump(else_jump);
-                } else {
-                    self.chunk.patch_jump(then_jump);
                 }
+
+                self.chunk.patch_jump(else_jump);
             }
             StmtKind::WhileStmt { condition, body } => {
                 let loop_start = self.chunk.code.len();
@@ -435,27 +1062,121 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                 let exit_jump = self.emit_jump(OpCode::JmpIfFalse, line);
                 self.chunk.write_chunk(OpCode::Pop, line);
 
+                let loop_ctx = self.codegen_loop_body(body, line);
+
+                self.emit_loop(OpCode::Loop, loop_start, line);
+
+                self.chunk.patch_jump(exit_jump);
+                self.chunk.write_chunk(OpCode::Pop, line);
+
+                for jump in loop_ctx.break_jumps {
+                    self.chunk.patch_jump(jump);
+                }
+            }
+            StmtKind::ForStmt {
+                initializer,
+                condition,
+                step,
+                body,
+            } => {
+                // scope for initializer
                 self.enter_scope();
-                for stmt in body {
+                if let Some(initializer) = initializer {
+                    self.visit_stmt(initializer);
+                }
+                self.emit_hoisted(stmt, line);
+
+                let loop_start = self.chunk.code.len();
+                let exit_jump = if let Some(condition) = condition {
+                    self.visit_expr(condition);
+                    self.chunk.add_debug_annotation_at_last("for condition");
+
+                    let exit_jump = self.emit_jump(OpCode::JmpIfFalse, line);
+                    self.chunk.write_chunk(OpCode::Pop, line);
+                    Some(exit_jump)
+                } else {
+                    None
+                };
+
+                let loop_ctx = self.codegen_loop_body(body, line);
+
+                if let Some(step) = step {
+                    self.visit_expr(step);
+                    self.chunk.write_chunk(OpCode::Pop, line);
+                }
+
+                self.emit_loop(OpCode::Loop, loop_start, line);
+
+                if let Some(exit_jump) = exit_jump {
+                    self.chunk.patch_jump(exit_jump);
+                    self.chunk.write_chunk(OpCode::Pop, line);
+                }
+
+                for jump in loop_ctx.break_jumps {
+                    self.chunk.patch_jump(jump);
+                }
+                self.exit_scope(line);
+            }
+            StmtKind::BreakStmt => {
+                let jump = self.emit_loop_exit_jump(line);
+                self.loop_stack.last_mut().unwrap().break_jumps.push(jump);
+            }
+            StmtKind::ContinueStmt => {
+                let jump = self.emit_loop_exit_jump(line);
+                self.loop_stack
+                    .last_mut()
+                    .unwrap()
+                    .continue_jumps
+                    .push(jump);
+            }
+            StmtKind::TryStmt {
+                try_block,
+                binding,
+                catch_block,
+            } => {
+                let handler_jump = self.emit_jump(OpCode::PushHandler, line);
+
+                self.handler_depth += 1;
+                self.enter_scope();
+                for stmt in try_block {
                     self.visit_stmt(stmt);
                 }
                 self.exit_scope(line);
+                self.handler_depth -= 1;
 
-                self.emit_loop(OpCode::Loop, loop_start, line);
+                self.chunk.write_chunk(OpCode::PopHandler, line);
+                let end_jump = self.emit_jump(OpCode::Jmp, line);
 
-                self.chunk.patch_jump(exit_jump);
-                self.chunk.write_chunk(OpCode::Pop, line);
+                // The VM pushes the thrown value before jumping to the catch block.
+                self.chunk.patch_jump(handler_jump);
+                self.enter_scope();
+                self.define_symbol(binding, line);
+                for stmt in catch_block {
+                    self.visit_stmt(stmt);
+                }
+                self.exit_scope(line);
+
+                self.chunk.patch_jump(end_jump);
             }
+            StmtKind::ThrowStmt(expr) => {
+                self.visit_expr(expr);
+                self.chunk.write_chunk(OpCode::Throw, line);
+            }
+            StmtKind::ImportDeclaration { .. } => {
+                // The module is not a value but it still occupies a stack slot.
+                self.chunk.write_chunk(OpCode::LdUnit, line);
+                self.define_symbol(stmt, line);
+            }
+            StmtKind::Export(declaration) => self.visit_stmt(declaration),
             StmtKind::ExprStmt(expr) => {
                 self.visit_expr(expr);
                 self.chunk.write_chunk(OpCode::Pop, line);
             }
-            StmtKind::ReturnStmt(expr) => {
-                if let Expr {
+            StmtKind::ReturnStmt(expr) => match expr {
+                Some(Expr {
                     kind: ExprKind::NumberLit(number),
                     ..
-                } = expr
-                {
+                }) => {
                     if *number == 0.0 {
                         self.chunk.write_chunk(OpCode::Ret0, line);
                     } else if *number == 1.0 {
@@ -464,11 +1185,16 @@ impl<'a> Visitor<'a> for Codegen<'a> {
                         self.chunk.emit_ldf64(*number, line);
                         self.chunk.write_chunk(OpCode::Ret, line);
                     }
-                } else {
+                }
+                Some(expr) => {
                     self.visit_expr(expr);
                     self.chunk.write_chunk(OpCode::Ret, line);
                 }
-            }
+                None => {
+                    self.chunk.write_chunk(OpCode::LdUnit, line);
+                    self.chunk.write_chunk(OpCode::Ret, line);
+                }
+            },
             StmtKind::Lambda => unreachable!(),
             StmtKind::Error => unreachable!(),
         }
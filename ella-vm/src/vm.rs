@@ -1,9 +1,13 @@
 use ella_value::chunk::{Chunk, OpCode};
-use ella_value::object::{Closure, Function, NativeFn, Obj, ObjKind, UpValue};
+use ella_value::object::{
+    BoundMethod, Class, Closure, Function, MapEntries, NativeFn, Obj, ObjKind, UpValue, VariantCtor,
+};
 use ella_value::{BuiltinVars, Value, ValueArray};
 use num_traits::FromPrimitive;
 
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::rc::Rc;
 
 const INSPECT_VM_STACK: bool = false;
@@ -21,6 +25,41 @@ struct CallFrame {
     /// NOTE: not actually a pointer but rather an index to the start of the `CallFrame`.
     frame_pointer: usize,
     closure: Rc<Closure>,
+    /// `true` if the frame is for an `init` method. Initializers always return the receiver.
+    is_initializer: bool,
+    /// The exception handlers registered in this frame. The innermost handler is last.
+    handlers: Vec<Handler>,
+}
+
+/// An exception handler registered by a `try` statement.
+#[derive(Debug, Clone, Copy)]
+struct Handler {
+    /// The instruction pointer of the catch block.
+    catch_ip: usize,
+    /// The height of the stack when the handler was registered. The stack is unwound to this height.
+    stack_len: usize,
+}
+
+/// The different ways a [`Closure`] can be called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallKind {
+    Function,
+    /// The first argument is the receiver.
+    Method,
+    /// The first argument is the receiver which is also the result of the call.
+    Initializer,
+}
+
+impl CallKind {
+    /// Returns the [`CallKind`] for calling `method` on a receiver.
+    /// Calling `init` explicitly behaves the same as when it is called by the class.
+    fn method(method: &Closure) -> Self {
+        if method.func.ident == "init" {
+            Self::Initializer
+        } else {
+            Self::Method
+        }
+    }
 }
 
 pub struct Vm<'a> {
@@ -80,8 +119,11 @@ impl<'a> Vm<'a> {
     }
 
     fn cleanup_function(&mut self) {
-        let return_value = self.stack.pop().unwrap();
+        let mut return_value = self.stack.pop().unwrap();
         let frame = self.call_stack.pop().unwrap(); // remove a `CallFrame` from the call stack.
+        if frame.is_initializer {
+            return_value = self.stack[frame.frame_pointer].clone(); // initializers always return `self`
+        }
 
         for i in frame.frame_pointer..self.stack.len() {
             self.close_upvalues(i);
@@ -96,7 +138,7 @@ impl<'a> Vm<'a> {
     fn try_implicit_ret(&mut self) -> bool {
         if self.call_stack.len() > 1 {
             // inside a function
-            self.stack.push(Value::Number(0.0)); // FIXME: returns 0.0 by default
+            self.stack.push(Value::Unit);
             self.cleanup_function();
             true
         } else {
@@ -141,6 +183,275 @@ impl<'a> Vm<'a> {
         None
     }
 
+    /// Converts a runtime value into an `i64`. Returns `None` if the value is not an integer.
+    fn cast_to_int(value: &Value) -> Option<i64> {
+        match value {
+            Value::Number(val) if val.fract() == 0.0 && val.abs() <= i64::MAX as f64 => {
+                Some(*val as i64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts a runtime index into a `usize`. Returns `None` if the index is not an integer in `0..len`.
+    fn list_index(index: &Value, len: usize) -> Option<usize> {
+        match index {
+            Value::Number(index) if index.fract() == 0.0 && *index >= 0.0 => {
+                let index = *index as usize;
+                if index < len {
+                    Some(index)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the field named `field` on `target` or `None` if the instance does not have the field.
+    /// The fields of an enum are its variants: a variant without a payload is a value, otherwise it is a constructor.
+    /// Returns an error message if `target` is not an instance or an enum.
+    fn get_field(target: &Value, field: &str) -> Result<Option<Value>, &'static str> {
+        if let Some(instance) = target.cast_to_instance() {
+            Ok(instance.fields.borrow().get(field).cloned())
+        } else if let Some(instance) = target.cast_to_class_instance() {
+            Ok(instance.fields.borrow().get(field).cloned())
+        } else if let Some(enum_def) = target.cast_to_enum() {
+            let tag = match enum_def.variants.iter().position(|v| v.ident == field) {
+                Some(tag) => tag,
+                None => return Ok(None),
+            };
+            let obj = if enum_def.variants[tag].arity == 0 {
+                Obj::new_enum_value(Rc::clone(enum_def), tag, Vec::new())
+            } else {
+                Obj {
+                    kind: ObjKind::VariantCtor(VariantCtor {
+                        enum_def: Rc::clone(enum_def),
+                        tag,
+                    }),
+                }
+            };
+            Ok(Some(Value::Object(Rc::new(obj))))
+        } else if matches!(target, Value::Object(obj) if matches!(obj.kind, ObjKind::Class(_))) {
+            Err("Can only access fields and methods on instances of a class, not on the class itself.")
+        } else {
+            Err("Can only access fields on instances.")
+        }
+    }
+
+    /// Returns the method named `method` on the class of `target` bound to `target` or `None` if there is no such method.
+    fn bind_method(target: &Value, method: &str) -> Option<Value> {
+        let instance = target.cast_to_class_instance()?;
+        let method = Rc::clone(instance.class.methods.borrow().get(method)?);
+        Some(Value::Object(Rc::new(Obj {
+            kind: ObjKind::BoundMethod(BoundMethod {
+                receiver: target.clone(),
+                method,
+            }),
+        })))
+    }
+
+    /// Calls `callee` with the top `arity` values on the stack as arguments.
+    fn call_value(&mut self, callee: Value, arity: u16) -> Result<(), InterpretResult> {
+        match callee {
+            Value::Object(obj) => match &obj.kind {
+                ObjKind::Fn(_) => {
+                    unreachable!(
+                        "can not call ObjKind::Fn, wrap function in a ObjKind::Closure instead"
+                    );
+                }
+                ObjKind::Closure(closure) => self.call_closure(closure, arity, CallKind::Function),
+                ObjKind::NativeFn(NativeFn {
+                    ident: _,
+                    arity: native_arity,
+                    variadic,
+                    func,
+                }) => {
+                    if *variadic && (arity as u32) < *native_arity {
+                        return Err(self.runtime_error(format!(
+                            "Expected at least {} argument(s), received {}.",
+                            native_arity, arity
+                        )));
+                    } else if !*variadic && *native_arity != arity as u32 {
+                        return Err(self.runtime_error(format!(
+                            "Expected {} argument(s), received {}.",
+                            native_arity, arity
+                        )));
+                    }
+
+                    let stack_len = self.stack.len();
+                    let args = &mut self.stack[stack_len - arity as usize..stack_len];
+                    debug_assert_eq!(args.len(), arity as usize);
+
+                    let result = func(args);
+                    // remove arguments from stack
+                    for _i in 0..arity {
+                        self.stack.pop().unwrap();
+                    }
+                    self.stack.push(result);
+                    Ok(())
+                }
+                ObjKind::Struct(class) => {
+                    if class.fields.len() != arity as usize {
+                        return Err(self.runtime_error(format!(
+                            "Expected {} argument(s), received {}.",
+                            class.fields.len(),
+                            arity
+                        )));
+                    }
+
+                    let values = self.stack.split_off(self.stack.len() - arity as usize);
+                    self.stack.push(Value::Object(Rc::new(Obj::new_instance(
+                        Rc::clone(class),
+                        values,
+                    ))));
+                    Ok(())
+                }
+                ObjKind::Class(class) => {
+                    let instance =
+                        Value::Object(Rc::new(Obj::new_class_instance(Rc::clone(class))));
+                    // the new instance is passed as `self` to `init` or is the result if there is no `init` method
+                    let stack_len = self.stack.len();
+                    self.stack.insert(stack_len - arity as usize, instance);
+
+                    let init = class.methods.borrow().get("init").cloned();
+                    match init {
+                        Some(init) => match &init.kind {
+                            ObjKind::Closure(closure) => {
+                                self.call_closure(closure, arity + 1, CallKind::Initializer)
+                            }
+                            _ => unreachable!("methods are always closures"),
+                        },
+                        None if arity == 0 => Ok(()),
+                        None => Err(self
+                            .runtime_error(format!("Expected 0 argument(s), received {}.", arity))),
+                    }
+                }
+                ObjKind::VariantCtor(VariantCtor { enum_def, tag }) => {
+                    let variant_arity = enum_def.variants[*tag].arity;
+                    if variant_arity != arity as u32 {
+                        return Err(self.runtime_error(format!(
+                            "Expected {} argument(s), received {}.",
+                            variant_arity, arity
+                        )));
+                    }
+
+                    let values = self.stack.split_off(self.stack.len() - arity as usize);
+                    self.stack.push(Value::Object(Rc::new(Obj::new_enum_value(
+                        Rc::clone(enum_def),
+                        *tag,
+                        values,
+                    ))));
+                    Ok(())
+                }
+                ObjKind::BoundMethod(BoundMethod { receiver, method }) => {
+                    let stack_len = self.stack.len();
+                    self.stack
+                        .insert(stack_len - arity as usize, receiver.clone());
+                    match &method.kind {
+                        ObjKind::Closure(closure) => {
+                            self.call_closure(closure, arity + 1, CallKind::method(closure))
+                        }
+                        _ => unreachable!("methods are always closures"),
+                    }
+                }
+                _ => Err(self.runtime_error("Value is not a function.")),
+            },
+            _ => Err(self.runtime_error("Value is not a function.")),
+        }
+    }
+
+    /// Pushes a new [`CallFrame`] for `closure`. The top `arity` values on the stack are the arguments.
+    /// For methods and initializers, `arity` includes the receiver.
+    /// Omitted trailing arguments are filled in with the default values of the function.
+    /// If the function is variadic, the extra arguments are collected into a list.
+    fn call_closure(
+        &mut self,
+        closure: &Closure,
+        arity: u16,
+        kind: CallKind,
+    ) -> Result<(), InterpretResult> {
+        let func = &closure.func;
+        // the number of params that are not the rest param
+        let fixed_arity = func.arity - func.variadic as u32;
+        let min_arity = fixed_arity - func.defaults.len() as u32;
+        let max_arity = if func.variadic { u32::MAX } else { func.arity };
+        if !(min_arity..=max_arity).contains(&(arity as u32)) {
+            // do not count the receiver in error message
+            let receiver_count = if kind == CallKind::Function { 0 } else { 1 };
+            let expected = if func.variadic {
+                format!("at least {}", min_arity.saturating_sub(receiver_count))
+            } else if func.defaults.is_empty() {
+                func.arity.saturating_sub(receiver_count).to_string()
+            } else {
+                format!(
+                    "{} to {}",
+                    min_arity.saturating_sub(receiver_count),
+                    func.arity.saturating_sub(receiver_count)
+                )
+            };
+            return Err(self.runtime_error(format!(
+                "Expected {} argument(s), received {}.",
+                expected,
+                arity as u32 - receiver_count
+            )));
+        }
+        if arity as u32 <= fixed_arity {
+            // push default values for omitted arguments
+            let omitted = (fixed_arity - arity as u32) as usize;
+            self.stack
+                .extend_from_slice(&func.defaults[func.defaults.len() - omitted..]);
+            if func.variadic {
+                self.stack
+                    .push(Value::Object(Rc::new(Obj::new_list(Vec::new()))));
+            }
+        } else {
+            // collect extra arguments into the rest param
+            let rest = self
+                .stack
+                .split_off(self.stack.len() - (arity as u32 - fixed_arity) as usize);
+            self.stack.push(Value::Object(Rc::new(Obj::new_list(rest))));
+        }
+        let arity = func.arity as usize;
+
+        // add new `CallFrame` to call stack
+        self.call_stack.push(CallFrame {
+            ip: 0,
+            frame_pointer: self.stack.len() - arity,
+            closure: Rc::new(closure.clone()),
+            is_initializer: kind == CallKind::Initializer,
+            handlers: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Calls the method named `method` on the receiver below the top `arity` values on the stack.
+    /// Fields that contain a callable value take precedence over methods.
+    fn invoke(&mut self, method: &str, arity: u16) -> Result<(), InterpretResult> {
+        let receiver_index = self.stack.len() - arity as usize - 1;
+        let receiver = self.stack[receiver_index].clone();
+
+        match Self::get_field(&receiver, method) {
+            Ok(Some(field)) => {
+                // the receiver is not passed to the field
+                self.stack.remove(receiver_index);
+                self.call_value(field, arity)
+            }
+            Ok(None) => {
+                let instance = receiver.cast_to_class_instance();
+                let closure = instance
+                    .and_then(|instance| instance.class.methods.borrow().get(method).cloned());
+                match closure.as_ref().map(|closure| &closure.kind) {
+                    Some(ObjKind::Closure(closure)) => {
+                        self.call_closure(closure, arity + 1, CallKind::method(closure))
+                    }
+                    _ => Err(self.runtime_error(format!("Undefined field \"{}\".", method))),
+                }
+            }
+            Err(message) => Err(self.runtime_error(message)),
+        }
+    }
+
     fn runtime_error(&self, message: impl ToString) -> InterpretResult {
         InterpretResult::RuntimeError {
             message: message.to_string(),
@@ -148,7 +459,59 @@ impl<'a> Vm<'a> {
         }
     }
 
+    /// Unwinds the call stack and the value stack to the innermost exception handler and jumps to its catch block with `error`.
+    /// Upvalues that refer to the discarded stack slots are closed.
+    /// Returns `false` and does nothing if there is no exception handler.
+    fn unwind(&mut self, error: Value) -> bool {
+        if self
+            .call_stack
+            .iter()
+            .all(|frame| frame.handlers.is_empty())
+        {
+            return false;
+        }
+
+        loop {
+            let frame = self.call_stack.last_mut().unwrap();
+            let (stack_len, handler) = match frame.handlers.pop() {
+                Some(handler) => (handler.stack_len, Some(handler)),
+                None => (frame.frame_pointer, None),
+            };
+
+            for i in stack_len..self.stack.len() {
+                self.close_upvalues(i);
+            }
+            self.stack.truncate(stack_len);
+
+            match handler {
+                Some(handler) => {
+                    self.stack.push(error);
+                    *self.ip_mut() = handler.catch_ip;
+                    return true;
+                }
+                None => {
+                    self.call_stack.pop().unwrap();
+                }
+            }
+        }
+    }
+
+    /// Executes the current chunk. Runtime errors are thrown as string values and can be caught by `try` statements.
     fn run(&mut self) -> InterpretResult {
+        loop {
+            let result = self.execute();
+            if let InterpretResult::RuntimeError { message, .. } = &result {
+                let error = Value::Object(Rc::new(Obj::new_string(message.clone())));
+                if self.unwind(error) {
+                    continue;
+                }
+            }
+            return result;
+        }
+    }
+
+    /// Executes instructions until the top-level function returns or a runtime error occurs.
+    fn execute(&mut self) -> InterpretResult {
         /// Generate vm for binary operator.
         macro_rules! gen_num_binary_op {
             ($op: tt, $result: path) => {{
@@ -173,6 +536,25 @@ impl<'a> Vm<'a> {
             }
         }
 
+        /// Generate vm for bitwise binary operator.
+        /// The result of `$op` should be an `Option<i64>` where `None` means that the operation overflowed.
+        macro_rules! gen_int_binary_op {
+            (|$a: ident, $b: ident| $op: expr) => {{
+                let b: Value = self.stack.pop().unwrap();
+                let a: Value = self.stack.pop().unwrap();
+
+                let ($a, $b) = match (Self::cast_to_int(&a), Self::cast_to_int(&b)) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return self.runtime_error("Operands must be integers."),
+                };
+
+                match $op {
+                    Some(result) => self.stack.push(Value::Number(result as f64)),
+                    None => return self.runtime_error("Shift amount must be between 0 and 63."),
+                }
+            }};
+        }
+
         while self.ip() < self.code().len() || self.try_implicit_ret() {
             let opcode = self.read_u8();
             let opcode = OpCode::from_u8(opcode).expect("invalid opcode");
@@ -188,14 +570,14 @@ impl<'a> Vm<'a> {
                 OpCode::Ld0 => self.stack.push(Value::Number(0.0)),
                 OpCode::Ld1 => self.stack.push(Value::Number(1.0)),
                 OpCode::LdLoc => {
-                    let local_index = self.read_u8() + self.frame().frame_pointer as u8;
-                    let local = self.stack[local_index as usize].clone();
+                    let local_index = self.read_u8() as usize + self.frame().frame_pointer;
+                    let local = self.stack[local_index].clone();
                     self.stack.push(local);
                 }
                 OpCode::StLoc => {
-                    let local_index = self.read_u8() + self.frame().frame_pointer as u8;
+                    let local_index = self.read_u8() as usize + self.frame().frame_pointer;
                     let value = self.stack.last().unwrap().clone();
-                    self.stack[local_index as usize] = value;
+                    self.stack[local_index] = value;
                 }
                 OpCode::LdGlobal => {
                     let index = self.read_u8();
@@ -228,6 +610,10 @@ impl<'a> Vm<'a> {
                     self.close_upvalues(index);
                     self.stack.pop().unwrap();
                 }
+                OpCode::CloseUpValAt => {
+                    let local_index = self.read_u8() as usize + self.frame().frame_pointer;
+                    self.close_upvalues(local_index);
+                }
                 OpCode::Neg => {
                     let val = self.stack.pop().unwrap();
                     match val {
@@ -235,6 +621,13 @@ impl<'a> Vm<'a> {
                         _ => return self.runtime_error("Operand must be a number."),
                     }
                 }
+                OpCode::BitNot => {
+                    let val = self.stack.pop().unwrap();
+                    match Self::cast_to_int(&val) {
+                        Some(val) => self.stack.push(Value::Number(!val as f64)),
+                        None => return self.runtime_error("Operand must be an integer."),
+                    }
+                }
                 OpCode::Not => {
                     let val = self.stack.pop().unwrap();
                     match val {
@@ -246,33 +639,41 @@ impl<'a> Vm<'a> {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
 
-                    let a_num = a.cast_to_number();
-                    let b_num = b.cast_to_number();
-                    if a_num.is_some() && b_num.is_some() {
-                        self.stack
-                            .push(Value::Number(a_num.unwrap() + b_num.unwrap()));
+                    if let (Some(a_num), Some(b_num)) = (a.cast_to_number(), b.cast_to_number()) {
+                        self.stack.push(Value::Number(a_num + b_num));
+                    } else if let (Some(a_str), Some(b_str)) = (a.cast_to_str(), b.cast_to_str()) {
+                        // handle string concatenation
+                        let obj = Rc::new(Obj::new_string(format!("{}{}", a_str, b_str)));
+                        self.stack.push(Value::Object(obj));
                     } else {
-                        let a_str = a.cast_to_str();
-                        let b_str = b.cast_to_str();
-                        if a_str.is_some() && b_str.is_some() {
-                            // handle string concatenation
-                            let obj = Rc::new(Obj::new_string(format!(
-                                "{}{}",
-                                a_str.unwrap(),
-                                b_str.unwrap()
-                            )));
-                            self.stack.push(Value::Object(obj));
-                        } else {
-                            return self.runtime_error(format!(
-                                "Operands must be numbers or strings. Received {} and {}",
-                                a, b
-                            ));
-                        }
+                        return self.runtime_error(format!(
+                            "Operands must be numbers or strings. Received {} and {}",
+                            a, b
+                        ));
                     }
                 }
                 OpCode::Sub => gen_num_binary_op!(-),
                 OpCode::Mul => gen_num_binary_op!(*),
                 OpCode::Div => gen_num_binary_op!(/),
+                OpCode::Rem => gen_num_binary_op!(%),
+                OpCode::Pow => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+
+                    match (a.cast_to_number(), b.cast_to_number()) {
+                        (Some(a), Some(b)) => self.stack.push(Value::Number(a.powf(b))),
+                        _ => return self.runtime_error("Operands must be numbers."),
+                    }
+                }
+                OpCode::BitAnd => gen_int_binary_op!(|a, b| Some(a & b)),
+                OpCode::BitOr => gen_int_binary_op!(|a, b| Some(a | b)),
+                OpCode::BitXor => gen_int_binary_op!(|a, b| Some(a ^ b)),
+                OpCode::Shl => {
+                    gen_int_binary_op!(|a, b| u32::try_from(b).ok().and_then(|b| a.checked_shl(b)))
+                }
+                OpCode::Shr => {
+                    gen_int_binary_op!(|a, b| u32::try_from(b).ok().and_then(|b| a.checked_shr(b)))
+                }
                 OpCode::Ret => {
                     if self.call_stack.len() <= 1 {
                         return self.runtime_error("Can only use return in a function.");
@@ -293,6 +694,7 @@ impl<'a> Vm<'a> {
                     }
                     self.cleanup_function();
                 }
+                OpCode::LdUnit => self.stack.push(Value::Unit),
                 OpCode::LdTrue => self.stack.push(Value::Bool(true)),
                 OpCode::LdFalse => self.stack.push(Value::Bool(false)),
                 OpCode::Eq => {
@@ -306,56 +708,18 @@ impl<'a> Vm<'a> {
                     self.stack.pop().unwrap(); // throw away result
                 }
                 OpCode::Calli => {
-                    match self.stack.pop().unwrap() {
-                        Value::Object(obj) => match &obj.kind {
-                            ObjKind::Fn(_) => {
-                                unreachable!("can not call ObjKind::Fn, wrap function in a ObjKind::Closure instead");
-                            }
-                            ObjKind::Closure(closure) => {
-                                let calli_arity = self.read_u8();
-
-                                if closure.func.arity != calli_arity as u32 {
-                                    return self.runtime_error(format!(
-                                        "Expected {} argument(s), received {}.",
-                                        closure.func.arity, calli_arity
-                                    ));
-                                }
-
-                                // add new `CallFrame` to call stack
-                                self.call_stack.push(CallFrame {
-                                    ip: 0,
-                                    frame_pointer: self.stack.len() - closure.func.arity as usize,
-                                    closure: Rc::new(closure.clone()),
-                                });
-                            }
-                            ObjKind::NativeFn(NativeFn {
-                                ident: _,
-                                arity,
-                                func,
-                            }) => {
-                                let calli_arity = self.read_u8();
-
-                                if *arity != calli_arity as u32 {
-                                    return self.runtime_error(format!(
-                                        "Expected {} argument(s), received {}.",
-                                        arity, calli_arity
-                                    ));
-                                }
-
-                                let stack_len = self.stack.len();
-                                let args = &mut self.stack[stack_len - *arity as usize..stack_len];
-                                debug_assert_eq!(args.len(), *arity as usize);
-
-                                let result = func(args);
-                                // remove arguments from stack
-                                for _i in 0..*arity {
-                                    self.stack.pop().unwrap();
-                                }
-                                self.stack.push(result);
-                            }
-                            _ => return self.runtime_error("Value is not a function."),
-                        },
-                        _ => return self.runtime_error("Value is not a function."),
+                    let callee = self.stack.pop().unwrap();
+                    let arity = self.read_u16();
+                    if let Err(err) = self.call_value(callee, arity) {
+                        return err;
+                    }
+                }
+                OpCode::Reorder => {
+                    let len = self.read_u8() as usize;
+                    let values = self.stack.split_off(self.stack.len() - len);
+                    for _i in 0..len {
+                        let index = self.read_u8() as usize;
+                        self.stack.push(values[index].clone());
                     }
                 }
                 OpCode::Closure => {
@@ -378,12 +742,12 @@ impl<'a> Vm<'a> {
                         let upvalue_index = self.read_u8();
 
                         let upvalue = if is_local {
-                            match self.find_open_upvalue_with_index(upvalue_index as usize) {
+                            // upvalue index is relative to the current frame
+                            let stack_index = self.frame().frame_pointer + upvalue_index as usize;
+                            match self.find_open_upvalue_with_index(stack_index) {
                                 Some(upvalue) => upvalue,
                                 None => {
-                                    let upvalue = Rc::new(RefCell::new(UpValue::Open(
-                                        upvalue_index as usize,
-                                    )));
+                                    let upvalue = Rc::new(RefCell::new(UpValue::Open(stack_index)));
                                     self.upvalues.push(upvalue.clone());
                                     upvalue
                                 }
@@ -414,6 +778,248 @@ impl<'a> Vm<'a> {
                     let offset = self.read_u16();
                     *self.ip_mut() -= offset as usize;
                 }
+                OpCode::BuildList => {
+                    let len = self.read_u16() as usize;
+                    let values = self.stack.split_off(self.stack.len() - len);
+                    self.stack
+                        .push(Value::Object(Rc::new(Obj::new_list(values))));
+                }
+                OpCode::BuildTuple => {
+                    let len = self.read_u16() as usize;
+                    let values = self.stack.split_off(self.stack.len() - len);
+                    self.stack
+                        .push(Value::Object(Rc::new(Obj::new_tuple(values))));
+                }
+                OpCode::UnpackTuple => {
+                    let len = self.read_u16() as usize;
+                    let value = self.stack.pop().unwrap();
+                    match value.cast_to_tuple() {
+                        Some(values) if values.len() == len => {
+                            self.stack.extend_from_slice(values);
+                        }
+                        _ => {
+                            return self.runtime_error(format!(
+                                "Expected a tuple with {} element(s).",
+                                len
+                            ))
+                        }
+                    }
+                }
+                OpCode::Concat => {
+                    let len = self.read_u16() as usize;
+                    let values = self.stack.split_off(self.stack.len() - len);
+                    let string = values.iter().map(|value| value.to_string()).collect();
+                    self.stack
+                        .push(Value::Object(Rc::new(Obj::new_string(string))));
+                }
+                OpCode::BuildMap => {
+                    let len = self.read_u16() as usize;
+                    let values = self.stack.split_off(self.stack.len() - len * 2);
+
+                    #[allow(clippy::mutable_key_type)] // keys are checked with `Value::is_hashable`
+                    let mut entries = MapEntries::with_capacity(len);
+                    let mut values = values.into_iter();
+                    while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                        if !key.is_hashable() {
+                            return self.runtime_error("Value is not hashable.");
+                        }
+                        entries.insert(key, value);
+                    }
+                    self.stack
+                        .push(Value::Object(Rc::new(Obj::new_map(entries))));
+                }
+                OpCode::LdIndex => {
+                    let index = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    let value = if let Some(list) = target.cast_to_list() {
+                        let list = list.borrow();
+                        match Self::list_index(&index, list.len()) {
+                            Some(index) => list[index].clone(),
+                            None => {
+                                return self.runtime_error(format!(
+                                    "Index out of range. Received {} but length is {}.",
+                                    index,
+                                    list.len()
+                                ))
+                            }
+                        }
+                    } else if let Some(map) = target.cast_to_map() {
+                        if !index.is_hashable() {
+                            return self.runtime_error("Value is not hashable.");
+                        }
+                        match map.borrow().get(&index) {
+                            Some(value) => value.clone(),
+                            None => {
+                                return self
+                                    .runtime_error(format!("Key {} not found in map.", index))
+                            }
+                        }
+                    } else {
+                        return self.runtime_error("Can only index into lists and maps.");
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::StIndex => {
+                    let value = self.stack.pop().unwrap();
+                    let index = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    if let Some(list) = target.cast_to_list() {
+                        let mut list = list.borrow_mut();
+                        match Self::list_index(&index, list.len()) {
+                            Some(index) => list[index] = value.clone(),
+                            None => {
+                                return self.runtime_error(format!(
+                                    "Index out of range. Received {} but length is {}.",
+                                    index,
+                                    list.len()
+                                ))
+                            }
+                        }
+                    } else if let Some(map) = target.cast_to_map() {
+                        if !index.is_hashable() {
+                            return self.runtime_error("Value is not hashable.");
+                        }
+                        map.borrow_mut().insert(index, value.clone());
+                    } else {
+                        return self.runtime_error("Can only index into lists and maps.");
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::Dup2 => {
+                    let len = self.stack.len();
+                    self.stack.extend_from_within(len - 2..);
+                }
+                OpCode::Dup => {
+                    let value = self.stack.last().unwrap().clone();
+                    self.stack.push(value);
+                }
+                OpCode::LdProp => {
+                    let field = self.read_constant();
+                    let field = field.cast_to_str().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    let value = match Self::get_field(&target, field) {
+                        Ok(Some(value)) => value,
+                        Ok(None) => match Self::bind_method(&target, field) {
+                            Some(method) => method,
+                            None => {
+                                return self
+                                    .runtime_error(format!("Undefined field \"{}\".", field))
+                            }
+                        },
+                        Err(message) => return self.runtime_error(message),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::StProp => {
+                    let field = self.read_constant();
+                    let field = field.cast_to_str().unwrap();
+                    let value = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+
+                    if let Some(instance) = target.cast_to_instance() {
+                        match instance.fields.borrow_mut().get_mut(field) {
+                            Some(slot) => *slot = value.clone(),
+                            None => {
+                                return self
+                                    .runtime_error(format!("Undefined field \"{}\".", field))
+                            }
+                        }
+                    } else if let Some(instance) = target.cast_to_class_instance() {
+                        // fields of class instances are created on first assignment
+                        instance
+                            .fields
+                            .borrow_mut()
+                            .insert(field.to_string(), value.clone());
+                    } else {
+                        return self.runtime_error("Can only access fields on instances.");
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::Class => {
+                    let ident = self.read_constant();
+                    let class = Class {
+                        ident: ident.cast_to_str().unwrap().to_string(),
+                        methods: RefCell::new(HashMap::new()),
+                    };
+                    self.stack.push(Value::Object(Rc::new(Obj {
+                        kind: ObjKind::Class(Rc::new(class)),
+                    })));
+                }
+                OpCode::Method => {
+                    let ident = self.read_constant();
+                    let method = match self.stack.pop().unwrap() {
+                        Value::Object(method) => method,
+                        _ => unreachable!("method should be a closure"),
+                    };
+                    match self.stack.last().unwrap() {
+                        Value::Object(obj) => match &obj.kind {
+                            ObjKind::Class(class) => {
+                                class
+                                    .methods
+                                    .borrow_mut()
+                                    .insert(ident.cast_to_str().unwrap().to_string(), method);
+                            }
+                            _ => unreachable!("methods can only be added to classes"),
+                        },
+                        _ => unreachable!("methods can only be added to classes"),
+                    }
+                }
+                OpCode::Invoke => {
+                    let method = self.read_constant();
+                    let method = method.cast_to_str().unwrap();
+                    let arity = self.read_u16();
+                    if let Err(err) = self.invoke(method, arity) {
+                        return err;
+                    }
+                }
+                OpCode::TestVariant => {
+                    let tag = self.read_u8() as usize;
+                    let enum_def = self.stack.pop().unwrap();
+                    let value = self.stack.pop().unwrap();
+
+                    let enum_def = match enum_def.cast_to_enum() {
+                        Some(enum_def) => enum_def,
+                        None => return self.runtime_error("Value is not an enum."),
+                    };
+                    let is_variant = match value.cast_to_enum_value() {
+                        Some(enum_value) => {
+                            Rc::ptr_eq(&enum_value.enum_def, enum_def) && enum_value.tag == tag
+                        }
+                        None => false,
+                    };
+                    self.stack.push(Value::Bool(is_variant));
+                }
+                OpCode::LdVariantField => {
+                    let index = self.read_u8() as usize;
+                    let value = self.stack.pop().unwrap();
+                    // TestVariant guarantees that the value is the right variant
+                    let enum_value = value.cast_to_enum_value().unwrap();
+                    self.stack.push(enum_value.values[index].clone());
+                }
+                OpCode::Abort => {
+                    let message = self.read_constant();
+                    return self.runtime_error(message);
+                }
+                OpCode::PushHandler => {
+                    let offset = self.read_u16();
+                    let handler = Handler {
+                        catch_ip: self.ip() + offset as usize,
+                        stack_len: self.stack.len(),
+                    };
+                    self.call_stack.last_mut().unwrap().handlers.push(handler);
+                }
+                OpCode::PopHandler => {
+                    self.call_stack.last_mut().unwrap().handlers.pop().unwrap();
+                }
+                OpCode::Throw => {
+                    let error = self.stack.pop().unwrap();
+                    if !self.unwind(error.clone()) {
+                        return self.runtime_error(format!("Uncaught exception: {}", error));
+                    }
+                }
             }
 
             if INSPECT_VM_STACK {
@@ -442,6 +1048,8 @@ impl<'a> Vm<'a> {
     pub fn interpret(&mut self, chunk: Chunk) -> InterpretResult {
         let func = Function {
             arity: 0,
+            defaults: Vec::new(),
+            variadic: false,
             chunk: Rc::new(chunk),
             ident: "top".to_string(),
             upvalues_count: 0, // cannot have any upvalues for top-level function
@@ -454,6 +1062,8 @@ impl<'a> Vm<'a> {
             ip: 0,            // start interpreting at first opcode
             frame_pointer: 0, // global frame_pointer points to start of stack
             closure: Rc::new(closure),
+            is_initializer: false,
+            handlers: Vec::new(),
         }];
 
         self.run()
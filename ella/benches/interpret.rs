@@ -13,20 +13,19 @@ use ella_vm::vm::Vm;
 fn codegen_str<'a>(source: &str, builtin_vars: &'a BuiltinVars) -> (Chunk, Vm<'a>) {
     let dummy_source: Source = "".into();
     let mut resolver = Resolver::new(dummy_source.clone());
-    resolver.resolve_builtin_vars(&builtin_vars);
+    resolver.resolve_builtin_vars(builtin_vars);
     let mut resolve_result = resolver.into_resolve_result();
 
-    let mut vm = Vm::new(&builtin_vars);
+    let mut vm = Vm::new(builtin_vars);
     let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &dummy_source);
-    codegen.codegen_builtin_vars(&builtin_vars);
+    codegen.codegen_builtin_vars(builtin_vars);
     vm.interpret(codegen.into_inner_chunk()); // load built in functions into memory
 
     let source = source.into();
     let mut parser = Parser::new(&source);
     let ast = parser.parse_program();
 
-    let mut resolver =
-        Resolver::new_with_existing_resolve_result(source.clone(), resolve_result);
+    let mut resolver = Resolver::new_with_existing_resolve_result(source.clone(), resolve_result);
     resolver.resolve_top_level(&ast);
     resolve_result = resolver.into_resolve_result();
 
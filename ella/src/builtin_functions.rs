@@ -5,28 +5,43 @@ use std::rc::Rc;
 use ella_value::object::{Obj, ObjKind};
 use ella_value::{BuiltinType, BuiltinVars, UniqueType, Value};
 
-#[allow(dead_code)] // This appears to be a bug with rustc. These functions are used in both main.rs and lib.rs
-
 /// Returns the default [`BuiltinVars`] that should be used.
+#[allow(dead_code)] // This appears to be a bug with rustc. These functions are used in both main.rs and lib.rs
 pub fn default_builtin_vars() -> BuiltinVars {
     let mut builtin_vars = BuiltinVars::new();
-    builtin_vars.add_native_fn(
+    builtin_vars.add_variadic_native_fn(
         "print",
         &print,
-        1,
+        0,
         BuiltinType::Fn {
-            params: vec![UniqueType::Any],
-            ret: Box::new(BuiltinType::Bool.into()),
+            params: vec![BuiltinType::List(Box::new(UniqueType::Any)).into()],
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: true,
+            optional: 0,
         }
         .into(),
     );
-    builtin_vars.add_native_fn(
+    builtin_vars.add_variadic_native_fn(
         "println",
         &println,
+        0,
+        BuiltinType::Fn {
+            params: vec![BuiltinType::List(Box::new(UniqueType::Any)).into()],
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: true,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_native_fn(
+        "repl_println",
+        &repl_println,
         1,
         BuiltinType::Fn {
             params: vec![UniqueType::Any],
-            ret: Box::new(BuiltinType::Bool.into()),
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -37,6 +52,8 @@ pub fn default_builtin_vars() -> BuiltinVars {
         BuiltinType::Fn {
             params: Vec::new(),
             ret: Box::new(BuiltinType::String.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -46,7 +63,9 @@ pub fn default_builtin_vars() -> BuiltinVars {
         1,
         BuiltinType::Fn {
             params: vec![BuiltinType::Bool.into()],
-            ret: Box::new(BuiltinType::Bool.into()),
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -56,7 +75,9 @@ pub fn default_builtin_vars() -> BuiltinVars {
         2,
         BuiltinType::Fn {
             params: vec![UniqueType::Any, UniqueType::Any],
-            ret: Box::new(BuiltinType::Bool.into()),
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -67,6 +88,38 @@ pub fn default_builtin_vars() -> BuiltinVars {
         BuiltinType::Fn {
             params: vec![BuiltinType::Number.into()],
             ret: Box::new(BuiltinType::Bool.into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_variadic_native_fn(
+        "max",
+        &max,
+        1,
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::Number.into(),
+                BuiltinType::List(Box::new(BuiltinType::Number.into())).into(),
+            ],
+            ret: Box::new(BuiltinType::Number.into()),
+            variadic: true,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_variadic_native_fn(
+        "min",
+        &min,
+        1,
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::Number.into(),
+                BuiltinType::List(Box::new(BuiltinType::Number.into())).into(),
+            ],
+            ret: Box::new(BuiltinType::Number.into()),
+            variadic: true,
+            optional: 0,
         }
         .into(),
     );
@@ -77,6 +130,8 @@ pub fn default_builtin_vars() -> BuiltinVars {
         BuiltinType::Fn {
             params: vec![UniqueType::Any],
             ret: Box::new(BuiltinType::Number.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -87,6 +142,8 @@ pub fn default_builtin_vars() -> BuiltinVars {
         BuiltinType::Fn {
             params: Vec::new(),
             ret: Box::new(BuiltinType::Number.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
@@ -97,24 +154,114 @@ pub fn default_builtin_vars() -> BuiltinVars {
         BuiltinType::Fn {
             params: vec![UniqueType::Any],
             ret: Box::new(BuiltinType::String.into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_native_fn(
+        "len",
+        &len,
+        1,
+        BuiltinType::Fn {
+            params: vec![UniqueType::Any],
+            ret: Box::new(BuiltinType::Number.into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_generic_native_fn(
+        "push",
+        &push,
+        2,
+        &["T"],
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::List(Box::new(UniqueType::Var(0))).into(),
+                UniqueType::Var(0),
+            ],
+            ret: Box::new(BuiltinType::Unit.into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_native_fn(
+        "keys",
+        &keys,
+        1,
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::Map(Box::new(UniqueType::Any), Box::new(UniqueType::Any)).into(),
+            ],
+            ret: Box::new(BuiltinType::List(Box::new(UniqueType::Any)).into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_native_fn(
+        "contains_key",
+        &contains_key,
+        2,
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::Map(Box::new(UniqueType::Any), Box::new(UniqueType::Any)).into(),
+                UniqueType::Any,
+            ],
+            ret: Box::new(BuiltinType::Bool.into()),
+            variadic: false,
+            optional: 0,
+        }
+        .into(),
+    );
+    builtin_vars.add_native_fn(
+        "remove",
+        &remove,
+        2,
+        BuiltinType::Fn {
+            params: vec![
+                BuiltinType::Map(Box::new(UniqueType::Any), Box::new(UniqueType::Any)).into(),
+                UniqueType::Any,
+            ],
+            ret: Box::new(BuiltinType::Bool.into()),
+            variadic: false,
+            optional: 0,
         }
         .into(),
     );
     builtin_vars
 }
 
+/// Joins the arguments with spaces.
+fn join_args(args: &[Value]) -> String {
+    args.iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 pub fn print(args: &mut [Value]) -> Value {
-    let arg = &args[0];
-    print!("{}", arg);
+    print!("{}", join_args(args));
 
-    Value::Bool(true)
+    Value::Unit
 }
 
 pub fn println(args: &mut [Value]) -> Value {
+    println!("{}", join_args(args));
+
+    Value::Unit
+}
+
+/// Used by the REPL to print the result of an expression statement. Does not print the unit value.
+pub fn repl_println(args: &mut [Value]) -> Value {
     let arg = &args[0];
-    println!("{}", arg);
+    if *arg != Value::Unit {
+        println!("{}", arg);
+    }
 
-    Value::Bool(true)
+    Value::Unit
 }
 
 pub fn readln(_args: &mut [Value]) -> Value {
@@ -127,11 +274,10 @@ pub fn readln(_args: &mut [Value]) -> Value {
 pub fn assert(args: &mut [Value]) -> Value {
     let arg = &args[0];
 
-    match arg {
-        Value::Bool(val) => assert!(*val),
-        _ => {}
+    if let Value::Bool(val) = arg {
+        assert!(*val)
     }
-    Value::Bool(true)
+    Value::Unit
 }
 
 pub fn assert_eq(args: &mut [Value]) -> Value {
@@ -139,7 +285,7 @@ pub fn assert_eq(args: &mut [Value]) -> Value {
     let right = &args[1];
 
     assert_eq!(left, right);
-    Value::Bool(true)
+    Value::Unit
 }
 
 pub fn is_nan(args: &mut [Value]) -> Value {
@@ -151,6 +297,26 @@ pub fn is_nan(args: &mut [Value]) -> Value {
     }
 }
 
+/// Returns the arguments as numbers or `None` if an argument is not a number.
+fn number_args(args: &[Value]) -> Option<Vec<f64>> {
+    args.iter()
+        .map(|arg| match arg {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn max(args: &mut [Value]) -> Value {
+    let max = number_args(args).and_then(|numbers| numbers.into_iter().reduce(f64::max));
+    Value::Number(max.unwrap_or(f64::NAN))
+}
+
+pub fn min(args: &mut [Value]) -> Value {
+    let min = number_args(args).and_then(|numbers| numbers.into_iter().reduce(f64::min));
+    Value::Number(min.unwrap_or(f64::NAN))
+}
+
 pub fn parse_number(args: &mut [Value]) -> Value {
     let string = &args[0];
 
@@ -176,3 +342,57 @@ pub fn str(args: &mut [Value]) -> Value {
     let arg = &args[0];
     Value::Object(Rc::new(Obj::new_string(format!("{}", arg))))
 }
+
+pub fn len(args: &mut [Value]) -> Value {
+    let arg = &args[0];
+
+    if let Some(list) = arg.cast_to_list() {
+        Value::Number(list.borrow().len() as f64)
+    } else if let Some(map) = arg.cast_to_map() {
+        Value::Number(map.borrow().len() as f64)
+    } else if let Some(string) = arg.cast_to_str() {
+        Value::Number(string.chars().count() as f64)
+    } else {
+        Value::Number(f64::NAN)
+    }
+}
+
+pub fn push(args: &mut [Value]) -> Value {
+    let list = &args[0];
+    let value = &args[1];
+
+    if let Some(list) = list.cast_to_list() {
+        list.borrow_mut().push(value.clone());
+    }
+    Value::Unit
+}
+
+pub fn keys(args: &mut [Value]) -> Value {
+    let map = &args[0];
+
+    let keys = match map.cast_to_map() {
+        Some(map) => map.borrow().keys().cloned().collect(),
+        None => Vec::new(),
+    };
+    Value::Object(Rc::new(Obj::new_list(keys)))
+}
+
+pub fn contains_key(args: &mut [Value]) -> Value {
+    let map = &args[0];
+    let key = &args[1];
+
+    match map.cast_to_map() {
+        Some(map) if key.is_hashable() => Value::Bool(map.borrow().contains_key(key)),
+        _ => Value::Bool(false),
+    }
+}
+
+pub fn remove(args: &mut [Value]) -> Value {
+    let map = &args[0];
+    let key = &args[1];
+
+    match map.cast_to_map() {
+        Some(map) if key.is_hashable() => Value::Bool(map.borrow_mut().shift_remove(key).is_some()),
+        _ => Value::Bool(false),
+    }
+}
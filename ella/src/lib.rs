@@ -1,6 +1,10 @@
 pub mod builtin_functions;
+pub mod module_loader;
+
+use std::path::Path;
 
 use builtin_functions::default_builtin_vars;
+use module_loader::ModuleLoader;
 
 use ella_parser::parser::Parser;
 use ella_passes::resolve::Resolver;
@@ -42,8 +46,8 @@ pub fn interpret(source: &str) {
     type_check_result = type_checker.into_type_check_result();
     let _ = type_check_result;
 
-    eprintln!("{}", source);
-    assert!(source.has_no_errors());
+    // the diagnostics are part of the panic message so that tests can match on them
+    assert!(source.has_no_errors(), "{}", source);
 
     let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &source);
 
@@ -52,3 +56,12 @@ pub fn interpret(source: &str) {
     let chunk = codegen.into_inner_chunk();
     assert_eq!(vm.interpret(chunk), InterpretResult::Ok);
 }
+
+/// For testing purposes only. Runs the file at `path` and the modules it imports.
+pub fn interpret_file(path: impl AsRef<Path>) {
+    let builtin_vars = default_builtin_vars();
+    let mut module_loader = ModuleLoader::new(&builtin_vars);
+    if let Err(err) = module_loader.load(path.as_ref()) {
+        panic!("{}", err);
+    }
+}
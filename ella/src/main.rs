@@ -1,4 +1,5 @@
 use ella::builtin_functions::default_builtin_vars;
+use ella::module_loader::{LoadError, ModuleLoader};
 use ella_parser::parser::Parser;
 use ella_passes::resolve::Resolver;
 use ella_passes::type_checker::TypeChecker;
@@ -7,6 +8,7 @@ use ella_vm::vm::InterpretResult;
 use ella_vm::{codegen::Codegen, vm::Vm};
 
 use std::io::{self, Write};
+use std::path::Path;
 
 mod builtin_functions;
 
@@ -59,10 +61,10 @@ fn get_repl_input() -> String {
             }
         }
 
-        if tmp.find(|c| c == '(' || c == '{').is_some() {
+        if tmp.find(['(', '{']).is_some() {
             indent += 1;
         }
-        if tmp.find(|c| c == ')' || c == '}').is_some() {
+        if tmp.find([')', '}']).is_some() {
             indent -= std::cmp::max(1, 0); // at least 0
         }
 
@@ -139,62 +141,15 @@ fn repl() -> ! {
     }
 }
 
-fn interpret_file_contents(source: &str) {
-    let builtin_vars = default_builtin_vars();
-
-    let dummy_source: Source = "".into();
-    let mut resolver = Resolver::new(dummy_source.clone());
-    resolver.resolve_builtin_vars(&builtin_vars);
-    let mut resolve_result = resolver.into_resolve_result();
-
-    let mut type_checker = TypeChecker::new(&resolve_result, dummy_source.clone());
-    type_checker.type_check_builtin_vars(&builtin_vars);
-    let mut type_check_result = type_checker.into_type_check_result();
-
-    let mut vm = Vm::new(&builtin_vars);
-    let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &dummy_source);
-    codegen.codegen_builtin_vars(&builtin_vars);
-    vm.interpret(codegen.into_inner_chunk()); // load built in functions into memory
-
-    let source = source.into();
-    let mut parser = Parser::new(&source);
-    let ast = parser.parse_program();
-
-    let mut resolver = Resolver::new_with_existing_resolve_result(source.clone(), resolve_result);
-    resolver.resolve_top_level(&ast);
-    resolve_result = resolver.into_resolve_result();
-
-    let mut type_checker =
-        TypeChecker::new_with_type_check_result(&resolve_result, source.clone(), type_check_result);
-    type_checker.type_check_global(&ast);
-    type_check_result = type_checker.into_type_check_result();
-    let _ = type_check_result;
-
-    if !source.has_no_errors() {
-        eprintln!("{}", source);
-    } else {
-        let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &source);
-
-        codegen.codegen_function(&ast);
-
-        let chunk = codegen.into_inner_chunk();
-        match vm.interpret(chunk) {
-            InterpretResult::Ok => {}
-            InterpretResult::RuntimeError { message, line } => {
-                eprintln!("Runtime Error: {} at line {}", message, line);
-            }
-        }
-    }
-}
-
 fn main() {
     if std::env::args().len() < 2 {
         repl();
     } else {
         let path = std::env::args().nth(1).unwrap();
-        let contents = std::fs::read_to_string(path);
-        match contents {
-            Ok(contents) => interpret_file_contents(&contents),
+        let builtin_vars = default_builtin_vars();
+        let mut module_loader = ModuleLoader::new(&builtin_vars);
+        match module_loader.load(Path::new(&path)) {
+            Ok(_) | Err(LoadError::Failed) => {} // errors have already been printed
             Err(err) => eprintln!("Error: {}", err),
         }
     }
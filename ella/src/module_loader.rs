@@ -0,0 +1,186 @@
+//! Loading of modules (files) and the modules they import.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use ella_parser::ast::{Stmt, StmtKind};
+use ella_parser::parser::Parser;
+use ella_passes::resolve::{ModuleExports, ResolveResult, Resolver};
+use ella_passes::type_checker::{TypeCheckResult, TypeChecker};
+use ella_source::{Source, SyntaxError};
+use ella_value::BuiltinVars;
+use ella_vm::codegen::Codegen;
+use ella_vm::vm::{InterpretResult, Vm};
+
+/// Error returned by [`ModuleLoader::load`].
+#[derive(Debug)]
+pub enum LoadError {
+    /// The module could not be read.
+    Io(io::Error),
+    /// The module imports itself (directly or through other modules). Contains the chain of imports.
+    Cycle(Vec<PathBuf>),
+    /// The module has errors or a runtime error occurred while running it. The errors have already been printed.
+    Failed,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "{}", err),
+            LoadError::Cycle(chain) => {
+                let chain: Vec<_> = chain
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                write!(f, "import cycle ({})", chain.join(" -> "))
+            }
+            LoadError::Failed => write!(f, "module could not be run"),
+        }
+    }
+}
+
+/// Loads, checks and runs modules. Every module is only run once, even if it is imported multiple times.
+pub struct ModuleLoader<'a> {
+    vm: Vm<'a>,
+    resolve_result: ResolveResult,
+    type_check_result: TypeCheckResult,
+    /// The members exported by the modules that have been loaded, by canonical path.
+    modules: HashMap<PathBuf, Rc<ModuleExports>>,
+    /// The modules that are currently being loaded (canonical paths). Used to detect import cycles.
+    loading: Vec<PathBuf>,
+    /// The ASTs of the modules that have been loaded.
+    /// The passes refer to AST nodes by address so they must be kept alive as long as the results of the passes.
+    asts: Vec<Stmt>,
+}
+
+impl<'a> ModuleLoader<'a> {
+    /// Create a new `ModuleLoader` with the builtin functions loaded.
+    pub fn new(builtin_vars: &'a BuiltinVars) -> Self {
+        let dummy_source: Source = "".into();
+        let mut resolver = Resolver::new(dummy_source.clone());
+        resolver.resolve_builtin_vars(builtin_vars);
+        let resolve_result = resolver.into_resolve_result();
+
+        let mut type_checker = TypeChecker::new(&resolve_result, dummy_source.clone());
+        type_checker.type_check_builtin_vars(builtin_vars);
+        let type_check_result = type_checker.into_type_check_result();
+
+        let mut vm = Vm::new(builtin_vars);
+        let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &dummy_source);
+        codegen.codegen_builtin_vars(builtin_vars);
+        vm.interpret(codegen.into_inner_chunk()); // load built in functions into memory
+
+        Self {
+            vm,
+            resolve_result,
+            type_check_result,
+            modules: HashMap::new(),
+            loading: Vec::new(),
+            asts: Vec::new(),
+        }
+    }
+
+    /// Loads and runs the module at `path` after the modules it imports.
+    /// Returns the members exported by the module.
+    pub fn load(&mut self, path: &Path) -> Result<Rc<ModuleExports>, LoadError> {
+        let canonical_path = path.canonicalize().map_err(LoadError::Io)?;
+        if let Some(exports) = self.modules.get(&canonical_path) {
+            return Ok(Rc::clone(exports));
+        }
+        if let Some(start) = self
+            .loading
+            .iter()
+            .position(|loading| *loading == canonical_path)
+        {
+            let mut chain = self.loading[start..].to_vec();
+            chain.push(canonical_path);
+            return Err(LoadError::Cycle(chain));
+        }
+
+        let contents = fs::read_to_string(&canonical_path).map_err(LoadError::Io)?;
+        self.loading.push(canonical_path.clone());
+        let exports = self.run_module(path, &canonical_path, &contents);
+        self.loading.pop();
+
+        let exports = Rc::new(exports?);
+        self.modules.insert(canonical_path, Rc::clone(&exports));
+        Ok(exports)
+    }
+
+    /// Checks and runs the `contents` of a module. The modules it imports are loaded first.
+    fn run_module(
+        &mut self,
+        path: &Path,
+        canonical_path: &Path,
+        contents: &str,
+    ) -> Result<ModuleExports, LoadError> {
+        let filename = path.display().to_string();
+        let source = Source::new(contents).with_filename(&filename);
+        let ast = Parser::new(&source).parse_program();
+
+        // Imports are resolved relative to the importing module.
+        let dir = canonical_path.parent().unwrap_or_else(|| Path::new("."));
+        let mut imported_modules = Vec::new();
+        if let StmtKind::FnDeclaration { body, .. } = &ast.kind {
+            for stmt in body {
+                if let StmtKind::ImportDeclaration { path, .. } = &stmt.kind {
+                    match self.load(&dir.join(path)) {
+                        Ok(exports) => imported_modules.push((path.clone(), exports)),
+                        Err(err) => source.errors.add_error(SyntaxError::new(
+                            format!("cannot import \"{}\": {}", path, err),
+                            stmt.span.clone(),
+                        )),
+                    }
+                }
+            }
+        }
+        if !source.has_no_errors() {
+            eprintln!("{}", source);
+            return Err(LoadError::Failed);
+        }
+
+        let mut resolver =
+            Resolver::new_with_existing_resolve_result(source.clone(), self.resolve_result.clone());
+        for (path, exports) in imported_modules {
+            resolver.add_module(path, exports);
+        }
+        let exports = resolver.resolve_module(&ast);
+        let resolve_result = resolver.into_resolve_result();
+
+        let mut type_checker = TypeChecker::new_with_type_check_result(
+            &resolve_result,
+            source.clone(),
+            self.type_check_result.clone(),
+        );
+        type_checker.type_check_global(&ast);
+        let type_check_result = type_checker.into_type_check_result();
+
+        if !source.has_no_errors() {
+            eprintln!("{}", source);
+            return Err(LoadError::Failed);
+        }
+
+        let mut codegen = Codegen::new("<global>".to_string(), &resolve_result, &source);
+        codegen.codegen_function(&ast);
+        let chunk = codegen.into_inner_chunk();
+        match self.vm.interpret(chunk) {
+            InterpretResult::Ok => {}
+            InterpretResult::RuntimeError { message, line } => {
+                eprintln!(
+                    "Runtime Error: {} at line {} in {}",
+                    message, line, filename
+                );
+                return Err(LoadError::Failed);
+            }
+        }
+
+        self.resolve_result = resolve_result;
+        self.type_check_result = type_check_result;
+        self.asts.push(ast);
+        Ok(exports)
+    }
+}
@@ -73,6 +73,25 @@ not_a_function();"#,
 mod variables {
     use super::*;
 
+    #[test]
+    fn number_literals() {
+        interpret(
+            r#"
+assert_eq(0xff, 255);
+assert_eq(0b1010, 10);
+assert_eq(1_000_000, 1000000);
+assert_eq(1e3, 1000);
+assert_eq(1.5e-3, 0.0015);
+assert_eq(2E+2, 200);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_number_literal() {
+        interpret("let x = 1.2.3;");
+    }
+
     #[test]
     fn variables() {
         interpret(
@@ -111,6 +130,47 @@ local; // not in scope"#,
         );
     }
 
+    #[test]
+    fn constants() {
+        interpret(
+            r#"
+const PI = 3.14;
+const NAME = "Ella";
+const xs = [1];
+fn area(r) { return PI * r * r; }
+assert_eq(area(1), 3.14);
+fn greet() { return fn() { return "Hello " + NAME; }; }
+assert_eq(greet()(), "Hello Ella");
+push(xs, 2); // the list itself can still be modified
+assert_eq(len(xs), 2);
+{
+    const PI = 3;
+    assert_eq(PI, 3);
+}
+assert_eq(PI, 3.14);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn assign_to_constant() {
+        interpret(
+            r#"
+const x = 1;
+x = 2; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn assign_to_captured_constant() {
+        interpret(
+            r#"
+const x = 1;
+fn inc() { x += 1; } // error"#,
+        );
+    }
+
     #[test]
     fn assign_equality_result() {
         interpret(
@@ -146,6 +206,19 @@ fn parse_number() {
     interpret(r#"assert(is_nan(parse_number(1)));"#);
 }
 
+#[test]
+fn max_min() {
+    interpret(r#"assert_eq(max(1, 5, 3), 5);"#);
+    interpret(r#"assert_eq(min(2, -1), -1);"#);
+    interpret(r#"assert_eq(max(7), 7);"#);
+    interpret(r#"let x: any = "a"; assert(is_nan(max(1, x)));"#);
+}
+
+#[test]
+fn println_multiple_args() {
+    interpret(r#"println("a", 1, true); println(); print("no newline", "\n");"#);
+}
+
 #[test]
 fn op_assign() {
     interpret(
@@ -182,304 +255,2841 @@ assert_eq("Hello " + "world!", "Hello world!");"#,
         );
     }
 
+    #[test]
+    fn string_escapes() {
+        interpret(
+            r#"
+assert_eq(len("a\tb\n"), 4);
+assert_eq(len("\"quoted\""), 8);
+assert_eq(len("\\"), 1);
+assert_eq("\u{41}\u{1F600}", "A😀");"#,
+        );
+    }
+
+    #[test]
+    fn string_interpolation() {
+        interpret(
+            r#"
+let x = 1;
+let list = [1, 2];
+assert_eq("x = ${x}, list = ${list}", "x = 1, list = [1, 2]");
+assert_eq("${x + 1}${x * 3}", "23");
+assert_eq("nested ${"${x}"}", "nested 1");
+assert_eq("{x} $x", "{x} $x");
+assert_eq(len("\${not interpolated}"), 19);
+
+fn greet(name) {
+    return "Hello ${name}!";
+}
+assert_eq(greet("Ella"), "Hello Ella!");"#,
+        );
+    }
+
+    #[test]
+    fn long_interpolation() {
+        let holes: Vec<String> = (0..200).map(|i| format!("${{{}}},", i)).collect();
+        let expected: Vec<String> = (0..200).map(|i| format!("{},", i)).collect();
+        interpret(&format!(
+            r#"
+assert_eq("{}", "{}");"#,
+            holes.concat(),
+            expected.concat()
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_interpolation() {
+        interpret(r#""${}";"#);
+    }
+
     #[test]
     #[should_panic]
     fn no_implicit_cast() {
         interpret(r#""a" + 1;"#);
     }
+
+    #[test]
+    #[should_panic]
+    fn invalid_escape() {
+        interpret(r#""\q";"#);
+    }
 }
 
-mod functions {
+mod lists {
     use super::*;
 
     #[test]
-    fn functions() {
+    fn list_literals() {
         interpret(
             r#"
-            fn foo() {
-                return 1;
-            }
-            assert_eq(foo(), 1);"#,
+let xs = [1, 2, 3];
+assert_eq(xs[0], 1);
+assert_eq(xs[2], 3);
+assert_eq(len(xs), 3);
+assert_eq(len([]), 0);
+assert_eq([1, 2], [1, 2]);
+assert_eq(str([1, 2]), "[1, 2]");"#,
         );
     }
 
     #[test]
-    fn functions_with_params() {
+    fn index_assignment() {
         interpret(
             r#"
-            fn double(x) {
-                let result = x * 2;
-                return result;
-            }
-            assert_eq(double(10), 20);
-            assert_eq(double(-2), -4);"#,
+let xs = [1, 2, 3];
+xs[1] = 5;
+assert_eq(xs[1], 5);
+xs[2] += 10;
+assert_eq(xs, [1, 5, 13]);
+
+let nested = [[1, 2], [3, 4]];
+nested[1][0] *= 2;
+assert_eq(nested[1][0], 6);"#,
         );
     }
 
     #[test]
-    fn functions_implicit_return() {
+    fn push_and_iterate() {
         interpret(
             r#"
-            fn foo() { }
-            assert_eq(foo(), 0);"#,
+fn range(n) {
+    let result = [];
+    for let i = 0; i < n; i += 1 {
+        push(result, i);
+    }
+    return result;
+}
+let xs = range(5);
+let sum = 0;
+for let i = 0; i < len(xs); i += 1 {
+    sum += xs[i];
+}
+assert_eq(sum, 10);"#,
         );
     }
 
     #[test]
-    fn higher_order_function() {
+    fn lists_are_shared() {
         interpret(
             r#"
-            fn twice(f, v) {
-                return f(f(v));
-            }
-            fn double(x) {
-                return x * 2;
-            }
-            
-            assert_eq(twice(double, 10), 40);
-            assert_eq(twice(double, -2), -8);"#,
+fn set_first(list) {
+    list[0] = "changed";
+}
+let xs = ["initial"];
+let ys = xs;
+set_first(ys);
+assert_eq(xs[0], "changed");"#,
         );
     }
 
-    mod closures {
-        use super::*;
+    #[test]
+    fn list_containing_itself() {
+        interpret(
+            r#"
+let xs: List<any> = [];
+push(xs, xs);
+assert(xs == xs);
+assert_eq(str(xs), "[[...]]");
+let ys: List<any> = [1];
+push(ys, [ys]);
+assert_eq(str(ys), "[1, [[...]]]");"#,
+        );
+    }
 
-        #[test]
-        fn close_global_variable() {
-            interpret(
-                r#"
-                fn assert_test() {
-                    assert(true); // captures assert fn from global scope
-                }
-                assert_test();"#,
-            );
-        }
+    #[test]
+    fn large_list_literal() {
+        let elements: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+        interpret(&format!(
+            r#"
+let xs = [{}];
+assert_eq(len(xs), 300);
+assert_eq(xs[0], 0);
+assert_eq(xs[299], 299);"#,
+            elements.join(", ")
+        ));
+    }
 
-        #[test]
-        fn basic_closures() {
-            interpret(
-                r#"
-                fn closure() {
-                    let x = 1;
-                    fn inner() {
-                        return x + 1;
-                    }
-                    return inner();
-                }
-                assert_eq(closure(), 2);"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        interpret(
+            r#"
+let xs = [1, 2, 3];
+xs[3];"#,
+        );
+    }
 
-        #[test]
-        fn capture_with_block_stmt() {
-            interpret(
-                r#"
-                {
-                    assert(true);
-                }"#,
-            )
-        }
+    #[test]
+    #[should_panic]
+    fn negative_index() {
+        interpret(
+            r#"
+let xs = [1, 2, 3];
+xs[-1] = 0;"#,
+        );
+    }
 
-        #[test]
-        fn close_upvalues() {
-            interpret(
-                r#"
-                fn createAdder(x) {
-                    fn adder(y) {
-                        return x + y;
-                    }
-                    return adder;
-                }
-                let addTwo = createAdder(2);
-                assert_eq(addTwo(1), 3);"#,
-            );
-            interpret(
-                r#"
-                fn compose(f, g) {
-                    fn inner(x) {
-                        return f(g(x));
-                    }
-                    return inner;
-                }
-                fn addOne(x) { return x + 1; }
-                fn addTwo(x) { return x + 2; }
-                let addThree = compose(addOne, addTwo);
-                assert_eq(addThree(2), 5);"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn mixed_element_types() {
+        interpret(
+            r#"
+let xs = [1, "two"]; // error, elements must have the same type"#,
+        );
+    }
 
-        #[test]
-        fn capture_by_ref() {
-            interpret(
-                r#"
-                fn dummy() {}
-                let globalSet = dummy; // dummy function to prevent type errors
-                let globalGet = dummy;
+    #[test]
+    #[should_panic]
+    fn wrong_element_type_in_assignment() {
+        interpret(
+            r#"
+let xs = [1, 2];
+xs[0] = true; // error"#,
+        );
+    }
 
-                fn main() {
-                    let a = "initial";
+    #[test]
+    #[should_panic(expected = "wrong type in argument position 2")]
+    fn push_wrong_element_type() {
+        interpret(
+            r#"
+let xs = [1];
+push(xs, "a"); // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "initializer has wrong type")]
+    fn push_infers_element_type_of_empty_list() {
+        interpret(
+            r#"
+let xs = [];
+push(xs, 1);
+let s: string = xs[0]; // error, xs is List<number>"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_non_list() {
+        interpret(
+            r#"
+let x = 1;
+x[0]; // error"#,
+        );
+    }
+}
+
+mod maps {
+    use super::*;
+
+    #[test]
+    fn map_literals() {
+        interpret(
+            r#"
+let m = { "a": 1, "b": 2 };
+assert_eq(m["a"], 1);
+assert_eq(m["b"], 2);
+assert_eq(len(m), 2);
+assert_eq(len({}), 0);
+assert_eq({ 1: true }, { 1: true });"#,
+        );
+    }
+
+    #[test]
+    fn map_assignment() {
+        interpret(
+            r#"
+let m = { "a": 1 };
+m["b"] = 2;
+m["a"] += 10;
+assert_eq(m["a"], 11);
+assert_eq(m["b"], 2);
+assert_eq(len(m), 2);
+
+let empty = {};
+empty[1] = "one";
+assert_eq(empty[1], "one");"#,
+        );
+    }
+
+    #[test]
+    fn map_builtins() {
+        interpret(
+            r#"
+let m = { "a": 1, "b": 2 };
+assert(contains_key(m, "a"));
+assert(!contains_key(m, "c"));
+assert_eq(len(keys(m)), 2);
+assert(remove(m, "a"));
+assert(!remove(m, "a"));
+assert(!contains_key(m, "a"));
+assert_eq(keys(m), ["b"]);"#,
+        );
+    }
+
+    #[test]
+    fn map_insertion_order() {
+        interpret(
+            r#"
+let m = { "c": 1, "a": 2 };
+m["b"] = 3;
+assert_eq(keys(m), ["c", "a", "b"]);
+assert_eq(str(m), "{c: 1, a: 2, b: 3}");
+remove(m, "c");
+m["c"] = 4;
+assert_eq(keys(m), ["a", "b", "c"]);"#,
+        );
+    }
+
+    #[test]
+    fn map_key_equality() {
+        interpret(
+            r#"
+fn f() {}
+let numbers = { 0: "zero" };
+assert_eq(numbers[-0], "zero");
+let strings = { "str": 1 };
+assert_eq(strings["s" + "tr"], 1);
+
+let by_fn = { f: 1 };
+assert_eq(by_fn[f], 1);"#,
+        );
+    }
+
+    #[test]
+    fn large_map_literal() {
+        let entries: Vec<String> = (0..300).map(|i| format!("{}: {}", i, i * 2)).collect();
+        interpret(&format!(
+            r#"
+let m = {{ {} }};
+assert_eq(len(m), 300);
+assert_eq(m[299], 598);"#,
+            entries.join(", ")
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn key_not_found() {
+        interpret(
+            r#"
+let m = { "a": 1 };
+m["b"];"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unhashable_key() {
+        interpret(
+            r#"
+let m = {};
+m[[1, 2]] = 1;"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_key() {
+        interpret(
+            r#"
+let m = { 1: "one" };
+m[parse_number("not a number")] = "nan"; // error, NaN could never be found again"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_key_type() {
+        interpret(
+            r#"
+let m = { "a": 1 };
+m[1]; // error, key should be a string"#,
+        );
+    }
+}
+
+mod tuples {
+    use super::*;
+
+    #[test]
+    fn tuple_literals() {
+        interpret(
+            r#"
+let t = (1, "a", (true,));
+assert_eq(str(t), "(1, a, (true,))");
+assert_eq(t, (1, "a", (true,)));
+assert_eq((1 + 2), 3); // parenthesized expression
+let m = {(1, 2): "pair"};
+assert_eq(m[(1, 2)], "pair");"#,
+        );
+    }
+
+    #[test]
+    fn large_tuple_literal() {
+        let elements: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+        let elements = elements.join(", ");
+        interpret(&format!(
+            r#"
+let t = ({elements});
+assert_eq(t, ({elements}));
+assert_eq(str(t), "({elements})");"#,
+            elements = elements
+        ));
+    }
+
+    #[test]
+    fn destructuring() {
+        interpret(
+            r#"
+fn divmod(a: number, b: number) -> (number, number) {
+    let r = a % b;
+    return ((a - r) / b, r);
+}
+let (q, r) = divmod(7, 2);
+assert_eq(q, 3);
+assert_eq(r, 1);
+
+fn swap(pair) {
+    let (a, b) = pair;
+    return (b, a);
+}
+let (x, y) = swap((1, "one"));
+assert_eq(x, "one");
+assert_eq(y, 1);
+
+let sum = {
+    let (a, b): (number, number) = (10, 20);
+    a + b
+};
+assert_eq(sum, 30);"#,
+        );
+    }
+
+    #[test]
+    fn captured_destructured_variables() {
+        interpret(
+            r#"
+fn make() {
+    let (x, y) = (3, 4);
+    return fn() { return x * y; };
+}
+assert_eq(make()(), 12);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn assign_to_const_destructured_variable() {
+        interpret(
+            r#"
+const (a, b) = (1, 2);
+a = 3; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_number_of_bindings() {
+        interpret(
+            r#"
+let (a, b) = (1, 2, 3); // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn destructure_non_tuple() {
+        interpret(
+            r#"
+let (a, b) = 1; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate binding \"a\"")]
+    fn duplicate_bindings() {
+        interpret(
+            r#"
+let (a, a) = (1, 2); // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_number_of_elements_at_runtime() {
+        interpret(
+            r#"
+let t: any = (1, 2, 3);
+let (a, b) = t;"#,
+        );
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[test]
+    fn struct_construction() {
+        interpret(
+            r#"
+struct Point { x, y }
+let p = Point(1, 2);
+assert_eq(p.x, 1);
+assert_eq(p.y, 2);
+assert_eq(str(p), "Point { x: 1, y: 2 }");
+assert_eq(str(Point), "<struct Point>");"#,
+        );
+    }
+
+    #[test]
+    fn field_assignment() {
+        interpret(
+            r#"
+struct Counter { count: number }
+let c = Counter(0);
+c.count = 5;
+c.count += 1;
+c.count *= 2;
+assert_eq(c.count, 12);
+assert_eq(c.count = 1, 1);"#,
+        );
+    }
+
+    #[test]
+    fn instances_are_shared() {
+        interpret(
+            r#"
+struct Box { value }
+let a = Box(1);
+let b = a;
+b.value = 2;
+assert_eq(a.value, 2);
+assert(a == b);
+assert(Box(1) != Box(1));"#,
+        );
+    }
+
+    #[test]
+    fn nested_structs() {
+        interpret(
+            r#"
+struct Point { x: number, y: number }
+struct Line { start: Point, end: Point }
+fn length_squared(line) {
+    let dx = line.end.x - line.start.x;
+    let dy = line.end.y - line.start.y;
+    return dx * dx + dy * dy;
+}
+let line = Line(Point(0, 0), Point(3, 4));
+assert_eq(length_squared(line), 25);
+line.end.x = 0;
+assert_eq(length_squared(line), 16);"#,
+        );
+    }
+
+    #[test]
+    fn struct_type_annotation() {
+        interpret(
+            r#"
+struct Point { x, y }
+let p: Point = Point(1, 2);
+fn make() {
+    struct Local { a }
+    let l: Local = Local(1);
+    return l.a;
+}
+assert_eq(make(), 1);"#,
+        );
+    }
+
+    #[test]
+    fn shadowed_struct_type() {
+        interpret(
+            r#"
+struct Point { x: number }
+fn make() {
+    struct Point { name: string }
+    let p: Point = Point("local");
+    return p.name;
+}
+let p: Point = Point(1);
+assert_eq(p.x, 1);
+assert_eq(make(), "local");"#,
+        );
+    }
+
+    #[test]
+    fn recursive_struct() {
+        interpret(
+            r#"
+struct Node { value: number, children: List<Node> }
+fn sum(node: Node) -> number {
+    let total = node.value;
+    for let i = 0; i < len(node.children); i += 1 {
+        total += sum(node.children[i]);
+    }
+    return total;
+}
+let root = Node(1, [Node(2, []), Node(3, [Node(4, [])])]);
+assert_eq(sum(root), 10);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_type_out_of_scope() {
+        interpret(
+            r#"
+fn make() {
+    struct Local { a }
+    return Local(1);
+}
+let l: Local = make(); // error, Local is not in scope"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_field() {
+        interpret(
+            r#"
+struct Point { x, y }
+let p = Point(1, 2);
+p.z; // error, no field z"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_field_type() {
+        interpret(
+            r#"
+struct Point { x: number, y: number }
+let p = Point(1, 2);
+p.x = "one"; // error, expected number"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong type in argument position 2")]
+    fn wrong_recursive_field_type() {
+        interpret(
+            r#"
+struct Node { value: number, children: List<Node> }
+Node(1, [2]); // error, expected List<Node>"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_constructor_arity() {
+        interpret(
+            r#"
+struct Point { x, y }
+Point(1);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_type_mismatch() {
+        interpret(
+            r#"
+struct Point { x, y }
+struct Size { x, y }
+let p: Point = Size(1, 2); // error, struct types are nominal"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_type() {
+        interpret(
+            r#"
+let p: Point = 1; // error, Point is not declared"#,
+        );
+    }
+}
+
+mod classes {
+    use super::*;
+
+    #[test]
+    fn class_methods() {
+        interpret(
+            r#"
+class Counter {
+    fn init(self, start) {
+        self.count = start;
+    }
+    fn inc(self) {
+        self.count += 1;
+        return self;
+    }
+    fn get(self) {
+        return self.count;
+    }
+}
+let c = Counter(5);
+c.inc().inc();
+assert_eq(c.get(), 7);
+assert_eq(c.count, 7);
+assert_eq(str(c), "<Counter instance>");
+assert_eq(str(Counter), "<class Counter>");"#,
+        );
+    }
+
+    #[test]
+    fn class_without_init() {
+        interpret(
+            r#"
+class Empty {}
+let e = Empty();
+e.value = 1;
+e.value += 1;
+assert_eq(e.value, 2);"#,
+        );
+    }
+
+    #[test]
+    fn bound_methods() {
+        interpret(
+            r#"
+class Counter {
+    fn init(self) {
+        self.count = 0;
+    }
+    fn inc(self) {
+        self.count += 1;
+        return self.count;
+    }
+}
+let c = Counter();
+let inc = c.inc;
+inc();
+inc();
+assert_eq(c.count, 2);
+
+fn twice(f) {
+    f();
+    return f();
+}
+assert_eq(twice(Counter().inc), 2);"#,
+        );
+    }
+
+    #[test]
+    fn callable_fields() {
+        interpret(
+            r#"
+class Holder {
+    fn init(self, f) {
+        self.f = f;
+    }
+    fn call(self, x) {
+        return self.f(x);
+    }
+}
+let h = Holder(fn(x) { return x * 2; });
+assert_eq(h.f(2), 4);
+assert_eq(h.call(3), 6);"#,
+        );
+    }
+
+    #[test]
+    fn init_returns_self() {
+        interpret(
+            r#"
+class Point {
+    fn init(self, x) {
+        self.x = x;
+        return;
+    }
+}
+let p = Point(1);
+assert_eq(p.x, 1);
+assert_eq(p.init(2), p);
+assert_eq(p.x, 2);"#,
+        );
+    }
+
+    #[test]
+    fn methods_capture_variables() {
+        interpret(
+            r#"
+fn make_class() {
+    let base = 100;
+    class Adder {
+        fn add(self, x) {
+            return base + x;
+        }
+        fn copy(self) {
+            return Adder();
+        }
+    }
+    return Adder();
+}
+assert_eq(make_class().add(1), 101);
+assert_eq(make_class().copy().add(2), 102);"#,
+        );
+    }
+
+    #[test]
+    fn class_type_annotation() {
+        interpret(
+            r#"
+class Counter {
+    fn init(self) {
+        self.count = 0;
+    }
+}
+fn increment(counter: Counter) -> Counter {
+    counter.count += 1;
+    return counter;
+}
+let c: Counter = increment(Counter());
+assert_eq(c.count, 1);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn class_type_mismatch() {
+        interpret(
+            r#"
+class Foo {}
+class Bar {}
+let foo: Foo = Bar(); // error, class types are nominal"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn class_instance_is_not_a_number() {
+        interpret(
+            r#"
+class Foo {}
+Foo() + 1; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn method_without_self() {
+        interpret(
+            r#"
+class Foo {
+    fn bar() {}
+}"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn undefined_method() {
+        interpret(
+            r#"
+class Foo {}
+Foo().bar();"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_init_arity() {
+        interpret(
+            r#"
+class Foo {
+    fn init(self, x) {}
+}
+Foo();"#,
+        );
+    }
+
+    #[test]
+    fn init_default_and_rest_params() {
+        interpret(
+            r#"
+class Point {
+    fn init(self, x = 0, y = 0) {
+        self.x = x;
+        self.y = y;
+    }
+}
+assert_eq(Point().x, 0);
+assert_eq(Point(1, 2).y, 2);
+class Bag {
+    fn init(self, name, ...items) {
+        self.name = name;
+        self.items = items;
+    }
+}
+assert_eq(len(Bag("a").items), 0);
+assert_eq(len(Bag("b", 1, 2, 3).items), 3);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 0 to 1 argument(s), found 3")]
+    fn init_too_many_args() {
+        interpret(
+            r#"
+class P {
+    fn init(self, x = 1) {}
+}
+P(1, 2, 3); // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at least 1 argument(s), found 0")]
+    fn init_too_few_args_with_rest_param() {
+        interpret(
+            r#"
+class Bag {
+    fn init(self, name, ...items) {}
+}
+Bag(); // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "can not access \"area\" on class \"Square\"")]
+    fn method_on_class() {
+        interpret(
+            r#"
+class Square {
+    fn init(self, side) { self.side = side; }
+    fn area(self) { return self.side * self.side; }
+}
+Square.area; // error, methods are accessed on instances"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "can not return a value from init")]
+    fn return_value_from_init() {
+        interpret(
+            r#"
+class Square {
+    fn init(self, side) {
+        self.side = side;
+        return side; // error
+    }
+}"#,
+        );
+    }
+}
+
+mod enums {
+    use super::*;
+
+    #[test]
+    fn enum_values() {
+        interpret(
+            r#"
+enum Shape { Circle(r: number), Rect(w, h), Empty }
+let c = Shape.Circle(2);
+assert_eq(str(c), "Shape.Circle(2)");
+assert_eq(str(Shape.Empty), "Shape.Empty");
+assert_eq(str(Shape), "<enum Shape>");
+assert(c == Shape.Circle(2));
+assert(c != Shape.Circle(3));
+assert(Shape.Empty == Shape.Empty);
+
+// enum values are hashable
+let names = {};
+names[Shape.Rect(1, 2)] = "rect";
+assert_eq(names[Shape.Rect(1, 2)], "rect");"#,
+        );
+    }
+
+    #[test]
+    fn match_expr() {
+        interpret(
+            r#"
+enum Shape { Circle(r), Rect(w, h), Empty }
+fn area(shape) {
+    return match shape {
+        Shape.Circle(r) => r * r * 3,
+        Shape.Rect(w, h) => w * h,
+        Shape.Empty => 0,
+    };
+}
+assert_eq(area(Shape.Circle(2)), 12);
+assert_eq(area(Shape.Rect(2, 5)), 10);
+assert_eq(area(Shape.Empty), 0);
+
+let sum = match Shape.Rect(1, 2) { Shape.Rect(w, h) => w + h, _ => 0 };
+assert_eq(sum, 3);"#,
+        );
+    }
+
+    #[test]
+    fn match_wildcard() {
+        interpret(
+            r#"
+enum Color { Red, Green, Blue }
+fn is_red(color) {
+    return match color { Color.Red => true, _ => false };
+}
+assert_eq(is_red(Color.Red), true);
+assert_eq(is_red(Color.Blue), false);"#,
+        );
+    }
+
+    #[test]
+    fn nested_match() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+fn add(a, b) {
+    return match a {
+        Option.Some(x) => match b {
+            Option.Some(y) => Option.Some(x + y),
+            Option.None => a,
+        },
+        Option.None => b,
+    };
+}
+assert_eq(add(Option.Some(1), Option.Some(2)), Option.Some(3));
+assert_eq(add(Option.None, Option.Some(2)), Option.Some(2));
+assert_eq(add(Option.None, Option.None), Option.None);"#,
+        );
+    }
+
+    #[test]
+    fn match_in_loop() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+let values = [Option.Some(1), Option.None, Option.Some(2)];
+let sum = 0;
+for let i = 0; i < len(values); i += match values[i] { Option.Some(x) => 1, Option.None => 1 } {
+    sum += match values[i] { Option.Some(x) => x, Option.None => 0 };
+}
+assert_eq(sum, 3);"#,
+        );
+    }
+
+    #[test]
+    fn match_binding_captured() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+fn getter(option) {
+    let get = match option {
+        Option.Some(x) => fn() { return x; },
+        Option.None => fn() { return -1; },
+    };
+    return get;
+}
+assert_eq(getter(Option.Some(5))(), 5);
+assert_eq(getter(Option.None)(), -1);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn non_exhaustive_match() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+let x = match Option.None { Option.Some(x) => x }; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_variant() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+let x = match Option.None { Option.Other => 1, _ => 0 }; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_number_of_bindings() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+let x = match Option.None { Option.Some(a, b) => 1, _ => 0 }; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn binding_out_of_scope() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+let x = match Option.None { Option.Some(a) => a, _ => 0 };
+a; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_arm_matched() {
+        interpret(
+            r#"
+enum Option { Some(value), None }
+fn unwrap_or_zero(option) {
+    return match option { Option.Some(x) => x, Option.None => 0 };
+}
+unwrap_or_zero(1); // runtime error, 1 is not an Option"#,
+        );
+    }
+}
+
+mod arithmetic_operators {
+    use super::*;
+
+    #[test]
+    fn remainder_and_exponentiation() {
+        interpret(
+            r#"
+assert_eq(7 % 3, 1);
+assert_eq(-7 % 3, -1);
+assert_eq(7.5 % 2, 1.5);
+assert_eq(2 ** 10, 1024);
+assert_eq(-2 ** 2, -4); // -(2 ** 2)
+assert_eq(2 ** 3 ** 2, 512); // 2 ** (3 ** 2)
+assert_eq(2 * 3 ** 2, 18);"#,
+        );
+    }
+
+    #[test]
+    fn bitwise() {
+        interpret(
+            r#"
+assert_eq(6 & 3, 2);
+assert_eq(6 | 3, 7);
+assert_eq(6 ^ 3, 5);
+assert_eq(~5, -6);
+assert_eq(1 << 4, 16);
+assert_eq(-16 >> 2, -4);
+assert_eq(1 + 1 << 2, 8); // (1 + 1) << 2
+assert_eq(1 | 6 & 3, 3); // 1 | (6 & 3)"#,
+        );
+    }
+
+    #[test]
+    fn compound_assignment() {
+        interpret(
+            r#"
+let x = 10;
+x %= 4;
+assert_eq(x, 2);
+x **= 3;
+assert_eq(x, 8);
+x <<= 2;
+assert_eq(x, 32);
+x >>= 1;
+assert_eq(x, 16);
+x |= 3;
+assert_eq(x, 19);
+x &= 6;
+assert_eq(x, 2);
+x ^= 7;
+assert_eq(x, 5);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn bitwise_non_integer() {
+        interpret(
+            r#"
+1.5 & 1; // runtime error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn shift_out_of_range() {
+        interpret(
+            r#"
+1 << 64; // runtime error"#,
+        );
+    }
+}
+
+mod logical_operators {
+    use super::*;
+
+    #[test]
+    fn logical_and_or() {
+        interpret(
+            r#"
+assert_eq(true && true, true);
+assert_eq(true && false, false);
+assert_eq(false && true, false);
+assert_eq(true || false, true);
+assert_eq(false || false, false);
+assert_eq(false || true && false, false);
+assert_eq(!false && 1 < 2, true);"#,
+        );
+    }
+
+    #[test]
+    fn short_circuit() {
+        interpret(
+            r#"
+let calls = 0;
+fn side_effect() {
+    calls += 1;
+    return true;
+}
+false && side_effect();
+true || side_effect();
+assert_eq(calls, 0);
+true && side_effect();
+false || side_effect();
+assert_eq(calls, 2);"#,
+        );
+    }
+
+    #[test]
+    fn logical_in_condition() {
+        interpret(
+            r#"
+fn in_range(x) {
+    let lo = 0;
+    return x >= lo && x < 10;
+}
+let count = 0;
+for let i = -5; i < 15; i += 1 {
+    if in_range(i) || i == 12 {
+        count += 1;
+    }
+}
+assert_eq(count, 11);"#,
+        );
+    }
+}
+
+mod functions {
+    use super::*;
+
+    #[test]
+    fn functions() {
+        interpret(
+            r#"
+            fn foo() {
+                return 1;
+            }
+            assert_eq(foo(), 1);"#,
+        );
+    }
+
+    #[test]
+    fn functions_with_params() {
+        interpret(
+            r#"
+            fn double(x) {
+                let result = x * 2;
+                return result;
+            }
+            assert_eq(double(10), 20);
+            assert_eq(double(-2), -4);"#,
+        );
+    }
+
+    #[test]
+    fn functions_implicit_return() {
+        interpret(
+            r#"
+            fn foo() { }
+            assert_eq(str(foo()), "()");
+            let unit: any = foo();
+            assert_eq(unit == 0, false);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "initializer has wrong type")]
+    fn functions_implicit_return_is_unit() {
+        interpret(
+            r#"
+            fn f() { }
+            let x: number = f(); // error, f returns ()"#,
+        );
+    }
+
+    #[test]
+    fn return_on_every_path() {
+        interpret(
+            r#"
+            fn sign(x: number) -> number {
+                if x > 0 {
+                    return 1;
+                } else {
+                    if x < 0 { return -1; } else { return 0; }
+                }
+            }
+            fn parse(x: number) -> number {
+                try {
+                    if x < 0 { throw "negative"; }
+                    return x;
+                } catch e {
+                    return 0;
+                }
+            }
+            assert_eq(sign(-5), -1);
+            assert_eq(parse(-1), 0);
+            assert_eq(parse(2), 2);"#,
+        );
+    }
+
+    #[test]
+    fn functions_bare_return() {
+        interpret(
+            r#"
+            fn foo(x) -> any {
+                if x > 0 {
+                    return;
+                }
+                return x;
+            }
+            fn bar() { }
+            assert_eq(foo(1), bar());
+            assert_eq(foo(-1), -1);
+            assert_eq(str(println("")), "()");"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "function returns both a value and no value")]
+    fn functions_mixed_returns() {
+        interpret(
+            r#"
+            fn f(a) {
+                if a {
+                    return;
+                }
+                return 1; // error, annotate with `-> any` to allow
+            }
+            let x: number = f(true);"#,
+        );
+    }
+
+    #[test]
+    fn higher_order_function() {
+        interpret(
+            r#"
+            fn twice(f, v) {
+                return f(f(v));
+            }
+            fn double(x) {
+                return x * 2;
+            }
+            
+            assert_eq(twice(double, 10), 40);
+            assert_eq(twice(double, -2), -8);"#,
+        );
+    }
+
+    #[test]
+    fn function_declared_in_block() {
+        interpret(
+            r#"
+            fn outer() {
+                {
+                    fn inner() { return 1; }
+                    inner();
+                }
+                let x = 2;
+                return x;
+            }
+            assert_eq(outer(), 2);"#,
+        );
+    }
+
+    #[test]
+    fn typed_functions() {
+        interpret(
+            r#"
+            fn add(a: number, b: number) -> number {
+                return a + b;
+            }
+            let x: number = add(1, 2);
+            assert_eq(x, 3);
+            fn greet(name: string) -> string { return "Hello " + name; }
+            assert_eq(greet("Ella"), "Hello Ella");
+            let square = fn(x: number) -> number { return x * x; };
+            assert_eq(square(4), 16);"#,
+        );
+    }
+
+    #[test]
+    fn inferred_function_types() {
+        interpret(
+            r#"
+            fn double(x) { return x * 2; }
+            let x: number = double(2);
+            assert_eq(x, 4);
+            fn identity(x) { return x; }
+            let n: number = identity(1);
+            let s: string = identity("a");
+            assert_eq(str(n) + s, "1a");
+            fn compose(f, g) {
+                return fn(x) { return f(g(x)); };
+            }
+            let f = compose(double, fn(x) { return x + 1; });
+            assert_eq(f(1), 4);
+            fn concat(a, b) { return a + b; }
+            assert_eq(concat(1, 2), 3);
+            assert_eq(concat("a", "b"), "ab");
+            fn apply(f: fn(number) -> number, x: number) -> number {
+                return f(x);
+            }
+            assert_eq(apply(fn(x) { return x * 3; }, 2), 6);
+            fn log(x: any) { }
+            log(1);
+            log("a");"#,
+        );
+    }
+
+    #[test]
+    fn generic_functions() {
+        interpret(
+            r#"
+            fn map<T, U>(xs: List<T>, f: fn(T) -> U) -> List<U> {
+                let result: List<U> = [];
+                for let i = 0; i < len(xs); i += 1 {
+                    push(result, f(xs[i]));
+                }
+                return result;
+            }
+            let strings: List<string> = map([1, 2], fn(x) { return str(x); });
+            assert_eq(strings[1], "2");
+            fn first<T>(xs: List<T>) -> T { return xs[0]; }
+            let n: number = first([1, 2]);
+            let s: string = first(["a"]);
+            assert_eq(str(n) + s, "1a");
+            fn compose<A, B, C>(f: fn(B) -> C, g: fn(A) -> B) -> fn(A) -> C {
+                return fn(x: A) -> C { return f(g(x)); };
+            }
+            let f: fn(number) -> string = compose(str, fn(x) { return x * 2; });
+            assert_eq(f(2), "4");"#,
+        );
+    }
+
+    #[test]
+    fn function_type_annotations() {
+        interpret(
+            r#"
+            fn apply(f: fn(number) -> number, x: number) -> number {
+                return f(x);
+            }
+            fn double(x: number) -> number { return x * 2; }
+            assert_eq(apply(double, 4), 8);
+            let f: fn(any) -> string = str;
+            assert_eq(f(1), "1");
+            let lists: Map<string, List<number>> = {"a": [1, 2]};
+            let nested: List<List<number>>= [[3]];
+            assert_eq(lists["a"][1] + nested[0][0], 5);"#,
+        );
+    }
+
+    mod closures {
+        use super::*;
+
+        #[test]
+        fn close_global_variable() {
+            interpret(
+                r#"
+                fn assert_test() {
+                    assert(true); // captures assert fn from global scope
+                }
+                assert_test();"#,
+            );
+        }
+
+        #[test]
+        fn basic_closures() {
+            interpret(
+                r#"
+                fn closure() {
+                    let x = 1;
+                    fn inner() {
+                        return x + 1;
+                    }
+                    return inner();
+                }
+                assert_eq(closure(), 2);"#,
+            );
+        }
+
+        #[test]
+        fn capture_with_block_stmt() {
+            interpret(
+                r#"
+                {
+                    assert(true);
+                }"#,
+            )
+        }
+
+        #[test]
+        fn close_upvalues() {
+            interpret(
+                r#"
+                fn createAdder(x) {
+                    fn adder(y) {
+                        return x + y;
+                    }
+                    return adder;
+                }
+                let addTwo = createAdder(2);
+                assert_eq(addTwo(1), 3);"#,
+            );
+            interpret(
+                r#"
+                fn compose(f, g) {
+                    fn inner(x) {
+                        return f(g(x));
+                    }
+                    return inner;
+                }
+                fn addOne(x) { return x + 1; }
+                fn addTwo(x) { return x + 2; }
+                let addThree = compose(addOne, addTwo);
+                assert_eq(addThree(2), 5);"#,
+            );
+        }
+
+        #[test]
+        fn capture_by_ref() {
+            interpret(
+                r#"
+                fn dummy() -> any {}
+                let globalSet = dummy; // dummy function to prevent type errors
+                let globalGet = dummy;
+
+                fn main() {
+                    let a = "initial";
+
+                    fn set() { a = "updated"; }
+                    fn get() { return a; }
+
+                    globalSet = set;
+                    globalGet = get;
+                }
+
+                main();
+                assert_eq(globalGet(), "initial");
+                globalSet();
+                assert_eq(globalGet(), "updated");"#,
+            );
+        }
+
+        #[test]
+        fn nested_upvalues() {
+            interpret(
+                r#"
+                fn outer() {
+                    let x = "value";
+
+                    fn middle() {
+                        fn inner() {
+                            return x;
+                        }
+                        return inner;
+                    }
+                    return middle;
+                }
+
+                let mid = outer();
+                let in = mid();
+                assert_eq(in(), "value");"#,
+            );
+        }
+
+        #[test]
+        fn capture_block_local() {
+            interpret(
+                r#"
+                fn make() {
+                    let a = 1;
+                    {
+                        let b = 4;
+                        return fn() { return a + b; };
+                    }
+                }
+                assert_eq(make()(), 5);"#,
+            );
+        }
+
+        #[test]
+        fn call_closure_in_expression() {
+            interpret(
+                r#"
+                fn adder(x) {
+                    return fn(y) { return x + y; };
+                }
+                assert_eq(1 + adder(2)(3), 6);"#,
+            );
+        }
+        #[test]
+        fn top_level_for_loop_closures() {
+            interpret(
+                r#"
+                let g = fn() { return 0; };
+                for let i = 0; i < 3; i += 1 {
+                    g = fn() { return i; };
+                }
+                assert_eq(g(), 3); // the loop variable is shared by every iteration
+                let fs = [];
+                for let i = 0; i < 3; i += 1 {
+                    let j = i;
+                    push(fs, fn() { return j; });
+                }
+                assert_eq(fs[0](), 0);
+                assert_eq(fs[2](), 2);"#,
+            );
+        }
+
+        #[test]
+        fn top_level_while_loop_closures() {
+            interpret(
+                r#"
+                let fs = [];
+                let i = 0;
+                while i < 3 {
+                    let j = i * 10;
+                    push(fs, fn() { return j; });
+                    i += 1;
+                }
+                assert_eq(fs[0](), 0);
+                assert_eq(fs[1](), 10);
+                assert_eq(fs[2](), 20);"#,
+            );
+        }
+
+        #[test]
+        fn closure_captures_earlier_local_in_scope() {
+            interpret(
+                r#"
+                let fs = [];
+                if true {
+                    let a = "a";
+                    let b = "b";
+                    push(fs, fn() { return a; });
+                }
+                let c = "c"; // reuses the stack slot of a
+                assert_eq(fs[0](), "a");"#,
+            );
+        }
+    }
+
+    mod lambda_expr {
+        use super::*;
+
+        #[test]
+        fn basic_lambda() {
+            interpret(
+                r#"
+                let f = fn() { return 2; };
+                assert_eq(f(), 2);"#,
+            );
+        }
+
+        #[test]
+        fn basic_lambda_with_param() {
+            interpret(
+                r#"
+                let square = fn(x) { return x * x; };
+                assert_eq(square(10), 100);"#,
+            );
+        }
+
+        #[test]
+        fn lambda_closure() {
+            interpret(
+                r#"
+                fn create_closure(x) {
+                    return fn() { return x * x; };
+                }
+                let f = create_closure(10);
+                assert_eq(f(), 100);"#,
+            );
+        }
+
+        /// IIFE: Immediately invoked function expression
+        #[test]
+        #[should_panic]
+        fn lambda_iife() {
+            interpret(
+                r#"
+                (fn() {
+                    assert(false); // should be called and panic
+                })();"#,
+            );
+        }
+    }
+
+    mod default_params {
+        use super::*;
+
+        #[test]
+        fn default_values() {
+            interpret(
+                r#"
+                fn connect(host: string, port = 8080, secure = false) {
+                    return host + ":" + str(port) + ":" + str(secure);
+                }
+                assert_eq(connect("x"), "x:8080:false");
+                assert_eq(connect("x", 9000), "x:9000:false");
+                assert_eq(connect("x", 9000, true), "x:9000:true");"#,
+            );
+        }
+
+        #[test]
+        fn negative_default_values() {
+            interpret(
+                r#"
+                fn offset(x, by = -1, scale = -0.5) { return (x + by) * scale; }
+                assert_eq(offset(5), -2);
+                assert_eq(offset(5, by: 1), -3);
+                let f: any = offset; // defaults are filled in by the VM
+                assert_eq(f(5), -2);"#,
+            );
+        }
+
+        #[test]
+        fn default_values_through_variable() {
+            interpret(
+                r#"
+                fn connect(host: string, port = 8080, secure = false) {
+                    return host + ":" + str(port) + ":" + str(secure);
+                }
+                let g = connect;
+                assert_eq(g("x"), "x:8080:false");
+                assert_eq(g("x", 9000), "x:9000:false");
+                let h: fn(string, number, bool) -> string = connect;
+                assert_eq(h("x", 1, true), "x:1:true");"#,
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 1 to 3 argument(s), found 0")]
+        fn too_few_args_through_variable() {
+            interpret(
+                r#"
+                fn connect(host: string, port = 8080, secure = false) {}
+                let g = connect;
+                g();"#,
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 3 argument(s), found 1")]
+        fn omitted_args_through_typed_variable() {
+            interpret(
+                r#"
+                fn connect(host: string, port = 8080, secure = false) {}
+                let h: fn(string, number, bool) = connect;
+                h("x"); // the type does not have default values"#,
+            );
+        }
+
+        #[test]
+        fn named_args() {
+            interpret(
+                r#"
+                fn connect(host: string, port = 8080, secure = false) {
+                    return host + ":" + str(port) + ":" + str(secure);
+                }
+                assert_eq(connect("x", port: 9000), "x:9000:false");
+                assert_eq(connect("x", secure: true), "x:8080:true"); // skips port
+                assert_eq(connect(secure: true, host: "y"), "y:8080:true");"#,
+            );
+        }
+
+        #[test]
+        fn named_args_evaluated_in_source_order() {
+            interpret(
+                r#"
+                let log = [];
+                fn arg(x) {
+                    push(log, x);
+                    return x;
+                }
+                fn sub(a, b) { return a - b; }
+                assert_eq(sub(b: arg(1), a: arg(3)), 2);
+                assert_eq(log, [1, 3]);
+
+                let trace: List<any> = [];
+                fn traced(x: any) {
+                    push(trace, x);
+                    return x;
+                }
+                fn connect(host: string, port = 8080, secure = false) {
+                    return host + ":" + str(port) + ":" + str(secure);
+                }
+                assert_eq(connect(traced("x"), secure: traced(true), port: traced(1)), "x:1:true");
+                assert_eq(connect(secure: traced(false), host: traced("y")), "y:8080:false");
+                assert_eq(str(trace), "[x, true, 1, false, y]");"#,
+            );
+        }
+
+        #[test]
+        fn method_default_values() {
+            interpret(
+                r#"
+                class Greeter {
+                    fn greet(self, name, greeting = "Hello") {
+                        return greeting + " " + name;
+                    }
+                }
+                let greeter = Greeter();
+                assert_eq(greeter.greet("Luke"), "Hello Luke");
+                assert_eq(greeter.greet("Luke", "Hi"), "Hi Luke");
+                let f: any = fn(a, b = 2) { return a + b; };
+                assert_eq(f(1), 3);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn method_too_few_args() {
+            interpret(
+                r#"
+                class Greeter {
+                    fn greet(self, name, greeting = "Hello") {}
+                }
+                Greeter().greet();"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn unknown_named_arg() {
+            interpret(
+                r#"
+                fn connect(host, port = 8080) {}
+                connect("x", prot: 9000);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn duplicate_arg() {
+            interpret(
+                r#"
+                fn connect(host, port = 8080) {}
+                connect("x", host: "y");"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn missing_required_arg() {
+            interpret(
+                r#"
+                fn connect(host, port = 8080) {}
+                connect(port: 9000);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn non_literal_default() {
+            interpret(
+                r#"
+                let x = 1;
+                fn foo(a = x) {}"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn required_param_after_default() {
+            interpret(
+                r#"
+                fn foo(a = 1, b) {}"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn positional_after_named_arg() {
+            interpret(
+                r#"
+                fn foo(a, b) {}
+                foo(a: 1, 2);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn named_args_on_lambda() {
+            interpret(
+                r#"
+                let f = fn(a) {};
+                f(a: 1);"#,
+            );
+        }
+    }
+
+    mod rest_params {
+        use super::*;
+
+        #[test]
+        fn rest_params() {
+            interpret(
+                r#"
+                fn sum(...xs: List<number>) -> number {
+                    let total = 0;
+                    for let i = 0; i < len(xs); i += 1 {
+                        total += xs[i];
+                    }
+                    return total;
+                }
+                assert_eq(sum(), 0);
+                assert_eq(sum(1, 2, 3), 6);
+                fn rest(first, ...others) { return others; }
+                assert_eq(rest(1, "a", "b"), ["a", "b"]);
+                let f = fn(...xs) { return len(xs); };
+                assert_eq(f(1, 2), 2);"#,
+            );
+        }
+
+        #[test]
+        fn rest_params_with_defaults() {
+            interpret(
+                r#"
+                fn join(sep = ",", ...items) {
+                    let result = "";
+                    for let i = 0; i < len(items); i += 1 {
+                        if i > 0 { result += sep; }
+                        result += items[i];
+                    }
+                    return result;
+                }
+                assert_eq(join(), "");
+                assert_eq(join(";"), "");
+                assert_eq(join(";", "a", "b"), "a;b");"#,
+            );
+        }
+
+        #[test]
+        fn many_rest_args() {
+            let args: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+            interpret(&format!(
+                r#"
+                fn count(...xs) {{ return len(xs); }}
+                assert_eq(count({args}), 300);
+                let f: any = count;
+                assert_eq(f({args}), 300);
+                class Counter {{
+                    fn count(self, ...xs) {{ return len(xs); }}
+                }}
+                assert_eq(Counter().count({args}), 300);
+                assert_eq(max({args}), 299);"#,
+                args = args.join(", ")
+            ));
+        }
+
+        #[test]
+        fn rest_param_type_annotation() {
+            interpret(
+                r#"
+                fn count(label: string, ...xs: List<number>) -> string {
+                    return label + str(len(xs));
+                }
+                let f: fn(string, ...List<number>) -> string = count;
+                assert_eq(f("n"), "n0");
+                assert_eq(f("n", 1, 2, 3), "n3");"#,
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "wrong type in argument position 3")]
+        fn rest_param_type_annotation_wrong_type() {
+            interpret(
+                r#"
+                let f: fn(string, ...List<number>) = fn(label, ...xs) {};
+                f("n", 1, "a"); // error"#,
+            );
+        }
+
+        #[test]
+        fn generic_rest_params() {
+            interpret(
+                r#"
+                fn list<T>(...xs: List<T>) -> List<T> { return xs; }
+                let numbers: List<number> = list(1, 2);
+                assert_eq(numbers, [1, 2]);
+                let strings: List<string> = list("a");
+                assert_eq(strings, ["a"]);"#,
+            );
+        }
+
+        #[test]
+        fn method_rest_params() {
+            interpret(
+                r#"
+                class Counter {
+                    fn count(self, ...xs) { return len(xs); }
+                }
+                let counter = Counter();
+                assert_eq(counter.count(), 0);
+                assert_eq(counter.count(1, 2, 3), 3);
+                let f: any = fn(a, ...xs) { return xs; };
+                assert_eq(f(1, 2), [2]);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn rest_params_wrong_type() {
+            interpret(
+                r#"
+                fn sum(...xs: List<number>) {}
+                sum(1, "2");"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn rest_param_not_list() {
+            interpret(
+                r#"
+                fn sum(...xs: number) {}"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn rest_param_by_name() {
+            interpret(
+                r#"
+                fn sum(...xs) {}
+                sum(xs: [1]);"#,
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn too_few_args_dynamic() {
+            interpret(
+                r#"
+                let f: any = fn(a, ...xs) {};
+                f();"#,
+            );
+        }
+    }
+}
+
+mod control_flow {
+    use super::*;
+
+    #[test]
+    fn r#if() {
+        interpret(
+            r#"
+let x = 0;
+let condition = true;
+if condition {
+    x = 1;
+}
+assert_eq(x, 1);
+
+condition = false;
+if condition {
+    x = 2;
+}
+assert_eq(x, 1);"#,
+        );
+    }
+
+    #[test]
+    fn if_without_else_pops_condition() {
+        interpret(
+            r#"
+            fn f(x) {
+                let a = 1;
+                if x {
+                    a = 3;
+                }
+                let b = 2; // must not be shadowed by the condition left on the stack
+                return a + b;
+            }
+            assert_eq(f(false), 3);
+            assert_eq(f(true), 5);"#,
+        );
+    }
+
+    #[test]
+    fn if_and_else() {
+        interpret(
+            r#"
+            let x = 0;
+
+            let condition = true;
+            if condition {
+                x = 1;
+            } else {
+                x = 2;
+            }
+            assert_eq(x, 1);
+
+            condition = false;
+            if condition {
+                x = 1;
+            } else {
+                x = 2;
+            }
+            assert_eq(x, 2);"#,
+        );
+    }
+
+    #[test]
+    fn fibonacci() {
+        interpret(
+            r#"
+            fn fib(x) {
+                if x <= 1 { return 1; }
+                else { return fib(x - 1) + fib(x - 2); }
+            }
+            
+            assert_eq(fib(20), 10946);"#,
+        );
+    }
+
+    #[test]
+    fn accumulate() {
+        interpret(
+            r#"
+            let x = 0;
+            let i = 0;
+            while i < 20 {
+                x = x + i;
+                i = i + 1;
+            }
+            assert_eq(x, 190);"#,
+        );
+    }
+
+    #[test]
+    fn for_loop() {
+        interpret(
+            r#"
+            let x = 0;
+            for let i = 0; i < 20; i += 1 {
+                x += i;
+            }
+            assert_eq(x, 190);"#,
+        );
+    }
+
+    #[test]
+    fn for_loop_without_let() {
+        interpret(
+            r#"
+            let x = 0;
+            let i = 0;
+            for i = 5; i < 10; i += 1 {
+                x += 1;
+            }
+            assert_eq(x, 5);
+            assert_eq(i, 10);
+
+            x = 0;
+            for ; x < 3; {
+                x += 1;
+            }
+            assert_eq(x, 3);"#,
+        );
+    }
+
+    #[test]
+    fn nested_for_loop() {
+        interpret(
+            r#"
+            let count = 0;
+            for let i = 0; i < 5; i += 1 {
+                for let j = 0; j < i; j += 1 {
+                    let tmp = count;
+                    count = tmp + 1;
+                }
+            }
+            assert_eq(count, 10);"#,
+        );
+    }
+
+    #[test]
+    fn for_loop_in_function() {
+        interpret(
+            r#"
+            fn sum(n) {
+                let total = 0;
+                for let i = 1; i <= n; i += 1 {
+                    let f = fn() { return i; };
+                    total += f();
+                }
+                return total;
+            }
+            assert_eq(sum(4), 10);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn for_loop_variable_scope() {
+        interpret(
+            r#"
+            for let i = 0; i < 3; i += 1 {}
+            i;"#,
+        );
+    }
+
+    #[test]
+    fn break_stmt() {
+        interpret(
+            r#"
+            let i = 0;
+            while true {
+                if i == 5 { break; }
+                i += 1;
+            }
+            assert_eq(i, 5);
+
+            let x = 0;
+            for let j = 0; j < 10; j += 1 {
+                let tmp = j;
+                if tmp == 3 { break; }
+                x += tmp;
+            }
+            assert_eq(x, 3);"#,
+        );
+    }
+
+    #[test]
+    fn continue_stmt() {
+        interpret(
+            r#"
+            let x = 0;
+            for let i = 0; i < 10; i += 1 {
+                let tmp = i;
+                if tmp == 3 { continue; }
+                x += tmp;
+            }
+            assert_eq(x, 42);
+
+            let i = 0;
+            let y = 0;
+            while i < 5 {
+                i += 1;
+                let tmp = i;
+                if tmp == 2 { continue; }
+                y += tmp;
+            }
+            assert_eq(y, 13);"#,
+        );
+    }
+
+    #[test]
+    fn break_nested_loops() {
+        interpret(
+            r#"
+            let count = 0;
+            for let i = 0; i < 5; i += 1 {
+                for let j = 0; j < 5; j += 1 {
+                    if j == 2 { break; }
+                    count += 1;
+                }
+            }
+            assert_eq(count, 10);"#,
+        );
+    }
+
+    #[test]
+    fn break_closes_upvalues() {
+        interpret(
+            r#"
+            fn dummy() { return 0; }
+            fn make() {
+                let f = dummy;
+                for let i = 0; i < 10; i += 1 {
+                    let captured = i * 2;
+                    f = fn() { return captured; };
+                    if i == 3 { break; }
+                }
+                return f;
+            }
+            assert_eq(make()(), 6);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn break_outside_loop() {
+        interpret(r#"break;"#);
+    }
+
+    #[test]
+    #[should_panic]
+    fn continue_outside_loop() {
+        interpret(
+            r#"
+            while true {
+                fn f() { continue; }
+                break;
+            }"#,
+        );
+    }
+}
+
+mod block_expressions {
+    use super::*;
+
+    #[test]
+    fn if_expr() {
+        interpret(
+            r#"
+            let x = if true { 1 } else { 2 };
+            assert_eq(x, 1);
+            let y = if x == 2 { "a" } else if x == 1 { "b" } else { "c" };
+            assert_eq(y, "b");"#,
+        );
+    }
+
+    #[test]
+    fn if_expr_without_else() {
+        interpret(
+            r#"
+            fn unit() {}
+            let x = if false { 1; };
+            assert_eq(x, unit());"#,
+        );
+    }
+
+    #[test]
+    fn block_expr() {
+        interpret(
+            r#"
+            let x = 10 + { let a = 3; let b = 4; a * b };
+            assert_eq(x, 22);
+            let y = { let a = { let b = 2; b * 3 }; a + 1 };
+            assert_eq(y, 7);
+            fn unit() {}
+            assert_eq({ x; }, unit());"#,
+        );
+    }
+
+    #[test]
+    fn block_expr_shadowing() {
+        interpret(
+            r#"
+            let x = 1;
+            let xs = [x, { let x = 2; x }, x];
+            assert_eq(xs[0], 1);
+            assert_eq(xs[1], 2);
+            assert_eq(xs[2], 1);"#,
+        );
+    }
+
+    #[test]
+    fn block_expr_in_function() {
+        interpret(
+            r#"
+            fn sum_doubled(n) {
+                return 100 + {
+                    let sum = 0;
+                    for let i = 0; i < n; i += 1 {
+                        let doubled = i * 2;
+                        sum += doubled;
+                    }
+                    sum
+                };
+            }
+            assert_eq(sum_doubled(4), 112);
+
+            fn abs(x) {
+                return if x > 0 { let y = x; y } else { 0 - x };
+            }
+            assert_eq(abs(3), 3);
+            assert_eq(abs(-4), 4);"#,
+        );
+    }
+
+    #[test]
+    fn block_expr_closure() {
+        interpret(
+            r#"
+            fn make(n) {
+                let fs = [];
+                let k = 5 + {
+                    let captured = n;
+                    push(fs, fn() { return captured; });
+                    captured
+                };
+                return fs[0]() + k;
+            }
+            assert_eq(make(3), 11);"#,
+        );
+    }
+
+    #[test]
+    fn block_expr_loop_closures() {
+        interpret(
+            r#"
+            fn make() {
+                let fs = [];
+                let r = {
+                    for let i = 0; i < 3; i += 1 {
+                        let j = i;
+                        push(fs, fn() { return j; });
+                    }
+                    0
+                };
+                return fs;
+            }
+            let fs = make();
+            assert_eq(fs[0](), 0);
+            assert_eq(fs[1](), 1);
+            assert_eq(fs[2](), 2);"#,
+        );
+    }
+
+    #[test]
+    fn match_arm_block() {
+        interpret(
+            r#"
+            enum Shape { Circle(r), Square(s) }
+            fn area(shape) {
+                return match shape {
+                    Shape.Circle(r) => { let r2 = r * r; r2 * 3 },
+                    Shape.Square(s) => s * s,
+                };
+            }
+            assert_eq(area(Shape.Circle(2)), 12);
+            assert_eq(area(Shape.Square(3)), 9);"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn block_expr_scope() {
+        interpret(
+            r#"
+            let x = { let inner = 1; inner };
+            inner;"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn break_out_of_block_expr() {
+        interpret(
+            r#"
+            while true {
+                let x = 1 + { break; 2 };
+            }"#,
+        );
+    }
+
+    #[test]
+    fn break_inside_block_expr() {
+        interpret(
+            r#"
+            let x = {
+                let i = 0;
+                while true {
+                    i += 1;
+                    if i == 3 { break; }
+                }
+                i
+            };
+            assert_eq(x, 3);"#,
+        );
+    }
+}
+
+mod modules {
+    use super::*;
+    use ella::interpret_file;
+
+    /// Returns the path of a file in `tests/modules`.
+    fn module_path(file: &str) -> String {
+        format!("{}/tests/modules/{}", env!("CARGO_MANIFEST_DIR"), file)
+    }
+
+    #[test]
+    fn imports() {
+        interpret_file(module_path("main.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn private_member() {
+        interpret_file(module_path("private_member.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn globals_do_not_leak() {
+        interpret_file(module_path("global_leak.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn assign_to_module_member() {
+        interpret_file(module_path("assign_member.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_type_for_module_function() {
+        interpret_file(module_path("wrong_type.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_module_type() {
+        interpret_file(module_path("unknown_type.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_cycle() {
+        interpret_file(module_path("cycle_a.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_module() {
+        interpret_file(module_path("missing_module.ella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_without_module_loader() {
+        interpret("import util;");
+    }
+}
+
+mod exceptions {
+    use super::*;
+
+    #[test]
+    fn try_catch() {
+        interpret(
+            r#"
+let caught = "";
+try {
+    throw "error";
+    caught = "not thrown";
+} catch e {
+    caught = e;
+}
+assert_eq(caught, "error");
+
+let finished = false;
+try {
+    finished = true;
+} catch e {
+    finished = false;
+}
+assert(finished);"#,
+        );
+    }
+
+    #[test]
+    fn throw_across_calls() {
+        interpret(
+            r#"
+fn fail(x) {
+    let y = x + 1;
+    throw y;
+}
+fn middle() {
+    let a = 10;
+    fail(a);
+    return 0;
+}
+let result = 0;
+try {
+    result = middle();
+} catch e {
+    result = e;
+}
+assert_eq(result, 11);"#,
+        );
+    }
+
+    #[test]
+    fn catch_runtime_error() {
+        interpret(
+            r#"
+fn sub(a: any, b: any) {
+    return a - b;
+}
+let caught = "";
+try {
+    sub("a", 1);
+} catch e {
+    caught = e;
+}
+assert_eq(caught, "Operands must be numbers.");"#,
+        );
+    }
+
+    #[test]
+    fn unwinding_closes_upvalues() {
+        interpret(
+            r#"
+let closures = [];
+fn capture() {
+    let local = 42;
+    push(closures, fn() { return local; });
+    throw "error";
+}
+try {
+    capture();
+} catch e {}
+assert_eq(closures[0](), 42);"#,
+        );
+    }
+
+    #[test]
+    fn rethrow() {
+        interpret(
+            r#"
+let caught = 0;
+try {
+    try {
+        throw 1;
+    } catch e {
+        throw e + 1;
+    }
+} catch e {
+    caught = e;
+}
+assert_eq(caught, 2);"#,
+        );
+    }
 
-                    fn set() { a = "updated"; }
-                    fn get() { return a; }
+    #[test]
+    fn break_and_continue_inside_try() {
+        interpret(
+            r#"
+let sum = 0;
+for let i = 0; i < 10; i += 1 {
+    try {
+        if i == 1 { continue; }
+        if i == 3 { break; }
+        sum += i;
+    } catch e {}
+}
+assert_eq(sum, 2);
+
+// the handlers inside the loop have been removed
+let caught = false;
+try {
+    let x = 1;
+    throw "error";
+} catch e {
+    caught = true;
+}
+assert(caught);"#,
+        );
+    }
 
-                    globalSet = set;
-                    globalGet = get;
-                }
+    #[test]
+    fn try_inside_block_expression() {
+        interpret(
+            r#"
+let x = 1 + {
+    let y = 0;
+    try {
+        throw 2;
+    } catch e {
+        y = e;
+    }
+    y
+};
+assert_eq(x, 3);"#,
+        );
+    }
 
-                main();
-                assert_eq(globalGet(), "initial");
-                globalSet();
-                assert_eq(globalGet(), "updated");"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn uncaught_throw() {
+        interpret(r#"throw "error";"#);
+    }
 
-        #[test]
-        fn nested_upvalues() {
-            interpret(
-                r#"
-                fn outer() {
-                    let x = "value";
+    #[test]
+    #[should_panic]
+    fn catch_variable_is_scoped_to_catch_block() {
+        interpret(
+            r#"
+try {} catch e {}
+e; // error"#,
+        );
+    }
+}
 
-                    fn middle() {
-                        fn inner() {
-                            return x;
-                        }
-                        return inner;
-                    }
-                    return middle;
-                }
+/// Test cases designed to trigger to type checker.
+mod type_errors {
+    use super::*;
 
-                let mid = outer();
-                let in = mid();
-                assert_eq(in(), "value");"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn if_else_different_types() {
+        interpret(
+            r#"
+let x = if true { 1 } else { "a" }; // error"#,
+        );
     }
 
-    mod lambda_expr {
-        use super::*;
+    #[test]
+    #[should_panic(expected = "if without else must have type ()")]
+    fn if_without_else_value() {
+        interpret(
+            r#"
+let v = if true { 1 }; // error"#,
+        );
+    }
 
-        #[test]
-        fn basic_lambda() {
-            interpret(
-                r#"
-                let f = fn() { return 2; };
-                assert_eq(f(), 2);"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn wrong_param_type() {
+        interpret(
+            r#"
+fn double(x: number) -> number { return x * 2; }
+double("a"); // error"#,
+        );
+    }
 
-        #[test]
-        fn basic_lambda_with_param() {
-            interpret(
-                r#"
-                let square = fn(x) { return x * x; };
-                assert_eq(square(10), 100);"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn wrong_return_type() {
+        interpret(
+            r#"
+fn double(x: number) -> string { return x * 2; } // error"#,
+        );
+    }
 
-        #[test]
-        fn lambda_closure() {
-            interpret(
-                r#"
-                fn create_closure(x) {
-                    return fn() { return x * x; };
-                }
-                let f = create_closure(10);
-                assert_eq(f(), 100);"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn missing_return() {
+        interpret(
+            r#"
+fn sign(x: number) -> number { // error, falls off the end if x == 0
+    if x > 0 {
+        return 1;
+    } else {
+        if x < 0 { return -1; }
+    }
+}"#,
+        );
+    }
 
-        /// IIFE: Immediately invoked function expression
-        #[test]
-        #[should_panic]
-        fn lambda_iife() {
-            interpret(
-                r#"
-                (fn() {
-                    assert(false); // should be called and panic
-                })();"#,
-            );
-        }
+    #[test]
+    #[should_panic]
+    fn wrong_type_from_typed_function() {
+        interpret(
+            r#"
+fn double(x: number) -> number { return x * 2; }
+let s: string = double(1); // error"#,
+        );
     }
-}
 
-mod control_flow {
-    use super::*;
+    #[test]
+    #[should_panic]
+    fn wrong_type_for_inferred_param() {
+        interpret(
+            r#"
+fn double(x) { return x * 2; }
+double("a"); // error"#,
+        );
+    }
 
     #[test]
-    fn r#if() {
+    #[should_panic]
+    fn wrong_type_from_inferred_function() {
         interpret(
             r#"
-let x = 0;
-let condition = true;
-if condition {
-    x = 1;
-}
-assert_eq(x, 1);
+fn double(x) { return x * 2; }
+let s: string = double(1); // error"#,
+        );
+    }
 
-condition = false;
-if condition {
-    x = 2;
-}
-assert_eq(x, 1);"#,
+    #[test]
+    #[should_panic]
+    fn wrong_type_for_inferred_lambda() {
+        interpret(
+            r#"
+fn apply(f: fn(number) -> number, x: number) -> number { return f(x); }
+apply(fn(x) { return x + "a"; }, 1); // error"#,
         );
     }
 
     #[test]
-    fn if_and_else() {
+    #[should_panic(expected = "type parameter \"T\" can not be both number and string")]
+    fn conflicting_type_args() {
         interpret(
             r#"
-            let x = 0;
+fn pair<T>(a: T, b: T) -> List<T> { return [a, b]; }
+pair(1, "a"); // error"#,
+        );
+    }
 
-            let condition = true;
-            if condition {
-                x = 1;
-            } else {
-                x = 2;
-            }
-            assert_eq(x, 1);
+    #[test]
+    #[should_panic(expected = "return value has wrong type")]
+    fn type_param_used_as_concrete_type() {
+        interpret(
+            r#"
+fn identity<T>(x: T) -> T { return 1; } // error"#,
+        );
+    }
 
-            condition = false;
-            if condition {
-                x = 1;
-            } else {
-                x = 2;
-            }
-            assert_eq(x, 2);"#,
+    #[test]
+    #[should_panic(expected = "duplicate type parameter \"T\"")]
+    fn duplicate_type_param() {
+        interpret(
+            r#"
+fn f<T, T>(a: T) -> T { return a; } // error"#,
         );
     }
 
     #[test]
-    fn fibonacci() {
+    #[should_panic(expected = "duplicate binding \"a\"")]
+    fn duplicate_param() {
         interpret(
             r#"
-            fn fib(x) {
-                if x <= 1 { return 1; }
-                else { return fib(x - 1) + fib(x - 2); }
-            }
-            
-            assert_eq(fib(20), 10946);"#,
+fn f(a, a) { return a; } // error"#,
         );
     }
 
     #[test]
-    fn accumulate() {
+    #[should_panic]
+    fn wrong_function_type() {
         interpret(
             r#"
-            let x = 0;
-            let i = 0;
-            while i < 20 {
-                x = x + i;
-                i = i + 1;
-            }
-            assert_eq(x, 190);"#,
+fn double(x: number) -> number { return x * 2; }
+let f: fn(string) -> number = double; // error"#,
         );
     }
-}
 
-/// Test cases designed to trigger to type checker.
-mod type_errors {
-    use super::*;
+    #[test]
+    #[should_panic]
+    fn wrong_type_argument_count() {
+        interpret(
+            r#"
+let xs: List<number, number> = []; // error"#,
+        );
+    }
 
     #[test]
     #[should_panic]
@@ -568,43 +3178,82 @@ assert(1); // error, assert takes a bool"#,
     #[test]
     #[should_panic]
     fn cmp() {
-        interpret(r#"
-"1" == 1; // error"#);
+        interpret(
+            r#"
+"1" == 1; // error"#,
+        );
     }
 
     #[test]
     #[should_panic]
     fn logical_not() {
-        interpret(r#"
-!1; // error, should be bool"#);
+        interpret(
+            r#"
+!1; // error, should be bool"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn logical_and() {
+        interpret(
+            r#"
+true && 1; // error, should be bool"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn bitwise() {
+        interpret(
+            r#"
+true & 1; // error"#,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn bitwise_not() {
+        interpret(
+            r#"
+~"a"; // error"#,
+        );
     }
 
     #[test]
     #[should_panic]
     fn unary_minus() {
-        interpret(r#"
--true; // error, should be number"#);
+        interpret(
+            r#"
+-true; // error, should be number"#,
+        );
     }
 
     #[test]
     #[should_panic]
     fn unknown_type() {
-        interpret(r#"
-let x: Foo = 1; // error, Foo unknown"#);
+        interpret(
+            r#"
+let x: Foo = 1; // error, Foo unknown"#,
+        );
     }
 
     #[test]
     #[should_panic]
     fn wrong_type_for_initializer() {
-        interpret(r#"
-let x: bool = 1; // error, expected bool"#);
+        interpret(
+            r#"
+let x: bool = 1; // error, expected bool"#,
+        );
     }
 
     #[test]
     #[should_panic]
     fn wrong_type_for_initializer2() {
-        interpret(r#"
+        interpret(
+            r#"
 let x = 1; // infer to be number
-let y: bool = x; // error, expected bool"#);
+let y: bool = x; // error, expected bool"#,
+        );
     }
 }